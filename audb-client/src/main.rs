@@ -1,7 +1,9 @@
 use anyhow::{anyhow, Result};
-use audb_protocol::{recv_message, send_message, Command, CommandOutput, CommandResult, Request, Response};
-use clap::{Parser, Subcommand};
+use audb_core::tools::macros::print_warning;
+use audb_protocol::{recv_message, send_message, Command, EncodedMessage, CommandOutput, CommandResult, ErrorKind, Request, Response};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use tokio::net::UnixStream;
 
 #[macro_export]
@@ -12,6 +14,36 @@ macro_rules! exit_error {
     }};
 }
 
+/// Error reported by the server, keeping its structured kind
+#[derive(Debug)]
+struct ServerError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+/// Print the final error (as JSON to stderr if requested) and exit
+fn exit_with_error(error: anyhow::Error, json: bool) -> ! {
+    if !json {
+        exit_error!("{}", error);
+    }
+
+    let (kind, message) = match error.downcast_ref::<ServerError>() {
+        Some(e) => (format!("{:?}", e.kind), e.message.clone()),
+        None => ("ClientError".to_string(), error.to_string()),
+    };
+    let body = serde_json::json!({ "error": { "kind": kind, "message": message } });
+    eprintln!("{}", body);
+    std::process::exit(1);
+}
+
 #[derive(Parser)]
 #[command(name = "audb")]
 #[command(about = "Aurora Debug Bridge - Development and debugging CLI tool for Aurora OS", long_about = None)]
@@ -21,11 +53,27 @@ struct Cli {
     #[arg(short = 'd', long, global = true)]
     device: Option<String>,
 
+    /// Print errors as JSON ({"error": {"kind", "message"}}) to stderr
+    #[arg(long, global = true)]
+    json_errors: bool,
+
+    /// Talk to the server on this socket instead of the per-user default (also AUDB_SOCKET)
+    #[arg(long, global = true, value_name = "PATH")]
+    socket: Option<PathBuf>,
+
+    /// Re-run the command every N seconds until interrupted (like `watch`)
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    repeat: Option<u64>,
+
+    /// Retry connecting or sending a request this many times, e.g. while the server restarts (also AUDB_SERVER_RETRIES)
+    #[arg(long, global = true, value_name = "N")]
+    server_retries: Option<u32>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum Commands {
     /// Manage Aurora OS devices
     Device {
@@ -53,6 +101,9 @@ enum Commands {
         /// Run in foreground (don't daemonize)
         #[arg(long)]
         foreground: bool,
+
+        #[command(flatten)]
+        options: ServerOptions,
     },
 
     /// Stop the server daemon
@@ -61,52 +112,190 @@ enum Commands {
     /// Show server status
     ServerStatus,
 
+    /// Show command metrics since server start
+    Metrics {
+        /// Print metrics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Diagnose local setup (server binary, config, devices, Docker)
+    Doctor,
+
+    /// Show copy-pasteable examples for common tasks
+    Examples {
+        /// Only examples for this command (e.g. swipe, logs, install)
+        command: Option<String>,
+    },
+
     /// Execute shell command on device
     Shell {
         /// Run as root (devel-su)
         #[arg(short, long)]
         root: bool,
-        /// Command to execute (required)
+        /// Run as another device user, e.g. nemo (needs the root password to switch)
+        #[arg(long, value_name = "USER", conflicts_with = "root")]
+        as_user: Option<String>,
+        /// Allocate a pseudo-terminal for the command (stdout and stderr are merged)
+        #[arg(short = 't', long)]
+        tty: bool,
+        /// Report how long the command took (appends a `real: X.XXs` line)
+        #[arg(long)]
+        time: bool,
+        /// Run through a login shell (bash -lc) so the profile sets PATH and the
+        /// environment as for an interactive SSH login (slower to start)
+        #[arg(short, long)]
+        login: bool,
+        /// Keep only the first N lines of output (cut on the device, so the rest is not transferred)
+        #[arg(long, value_name = "N", conflicts_with = "tail")]
+        head: Option<usize>,
+        /// Keep only the last N lines of output (cut on the device)
+        #[arg(long, value_name = "N")]
+        tail: Option<usize>,
+        /// Run a local script file on the device (uploaded, then run with /bin/sh)
+        #[arg(long, value_name = "FILE", conflicts_with = "command")]
+        from_file: Option<PathBuf>,
+        /// Command to execute (required). Several arguments are passed as-is; a
+        /// single quoted argument is run as a shell command line (pipes, $VARS)
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         command: Vec<String>,
     },
 
     /// Push file to device
     Push {
-        /// Local file path
-        local: String,
+        /// Local file path ('-' reads stdin; omit with --content)
+        #[arg(required_unless_present = "content")]
+        local: Option<String>,
         /// Remote destination path
-        remote: String,
+        remote: Option<String>,
+        /// Push this text instead of a local file (the only path given is the remote one)
+        #[arg(long, value_name = "TEXT")]
+        content: Option<String>,
+        /// Create missing parent directories on the device
+        #[arg(short = 'p', long)]
+        mkdirs: bool,
     },
 
     /// Pull file from device
     Pull {
-        /// Remote file path
+        /// Remote file path (may contain wildcards, e.g. '/var/log/*.log')
         remote: String,
-        /// Local destination path (optional, defaults to current directory)
+        /// Local directory to extract into (with --tar)
+        #[arg(requires = "tar", conflicts_with = "output")]
+        local: Option<String>,
+        /// Local destination path (optional, defaults to current directory; a directory for wildcards)
         #[arg(short, long)]
         output: Option<String>,
+        /// Directory for the pulled file when --output is not given (also AUDB_OUTPUT_DIR)
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Pull a whole directory as one tarball and extract it locally
+        #[arg(long)]
+        tar: bool,
+    },
+
+    /// Print a remote text file (no local temp file)
+    Cat {
+        /// Remote file path
+        remote: String,
+        /// Read as root (devel-su)
+        #[arg(short, long)]
+        root: bool,
     },
 
     /// Get device information
     Info {
-        /// Info category: device, cpu, memory, battery, storage, features, sim (default: all)
+        /// Info category: device, cpu, memory, battery, storage, features, thermal, system, peripherals, devmode, time, sim (default: all)
         #[arg(value_name = "CATEGORY")]
         category: Option<String>,
+        /// Print only this field's raw value (e.g. battery_level), for scripts
+        #[arg(long, value_name = "NAME", conflicts_with = "category")]
+        field: Option<String>,
+        /// Print all fields as JSON (e.g. to attach to bug reports)
+        #[arg(long, conflicts_with_all = ["category", "field"])]
+        json: bool,
+    },
+
+    /// Show memory and swap usage (like free -h)
+    Free,
+
+    /// Show the device clock and its offset from this machine
+    Date,
+
+    /// Show filesystem usage (like df -h)
+    Df,
+
+    /// Diagnose a problem area on the device (input: why tap/swipe do nothing)
+    Diag {
+        /// Area to check
+        #[arg(value_parser = ["input"])]
+        topic: String,
+    },
+
+    /// Switch WiFi, Bluetooth or airplane mode on or off
+    Radio {
+        /// Radio to switch
+        #[arg(value_parser = ["wifi", "bluetooth", "airplane"])]
+        radio: String,
+        /// New state
+        #[arg(value_parser = ["on", "off"])]
+        state: String,
+        /// Switch even if it drops the connection audb uses (e.g. WiFi off over WiFi)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Start, stop or restart a systemd unit, or show its status
+    Service {
+        /// What to do with the unit
+        #[arg(value_parser = ["start", "stop", "restart", "status"])]
+        action: String,
+        /// Unit name (e.g. sshd.service)
+        unit: String,
+        /// Use the session user's systemd manager (e.g. for lipstick.service)
+        #[arg(long)]
+        user: bool,
     },
 
     /// Tap at coordinates on device screen
     Tap {
         /// X coordinate
-        x: u16,
+        #[arg(required_unless_present = "label")]
+        x: Option<u16>,
         /// Y coordinate
-        y: u16,
+        #[arg(required_unless_present = "label")]
+        y: Option<u16>,
+        /// Tap the center of the on-screen element with this name (from the accessibility tree)
+        #[arg(long, value_name = "TEXT", conflicts_with_all = ["x", "y"])]
+        label: Option<String>,
         /// Direct evdev device for fast mode (e.g., /dev/input/event4 or "auto")
         #[arg(long)]
         event: Option<String>,
+        /// Low-latency evdev mode on the auto-detected touchscreen (same as --event auto)
+        #[arg(long, conflicts_with = "event")]
+        fast: bool,
         /// Duration in milliseconds for long press (default: 30ms, use 500-1000 for long press)
         #[arg(long)]
         duration: Option<u32>,
+        /// Orientation the coordinates refer to: auto, portrait, landscape, inverted-portrait, inverted-landscape (default: auto)
+        #[arg(long)]
+        orientation: Option<String>,
+        /// Clamp off-screen coordinates to the screen edge (with a warning) instead of failing
+        #[arg(long)]
+        clamp: bool,
+    },
+
+    /// Dump the on-screen accessibility tree (role, name, bounds) of running apps
+    UiDump {
+        /// Only applications whose name contains this
+        #[arg(long)]
+        app: Option<String>,
+        /// Print the elements as JSON (id, depth, role, name, bounds)
+        #[arg(long)]
+        json: bool,
+        /// Write the elements as JSON to this file instead of printing them
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     /// Swipe on device screen
@@ -117,6 +306,36 @@ enum Commands {
         /// Direct evdev device for fast mode (e.g., /dev/input/event4 or "auto")
         #[arg(long)]
         event: Option<String>,
+        /// Low-latency evdev mode on the auto-detected touchscreen (same as --event auto)
+        #[arg(long, conflicts_with = "event")]
+        fast: bool,
+        /// Velocity profile: linear, ease-in, ease-out, fling (default: linear)
+        #[arg(long)]
+        profile: Option<String>,
+        /// Total move duration in milliseconds (10-10000)
+        #[arg(long)]
+        duration: Option<u32>,
+        /// Orientation the coordinates refer to: auto, portrait, landscape, inverted-portrait, inverted-landscape (default: auto)
+        #[arg(long)]
+        orientation: Option<String>,
+        /// Clamp off-screen coordinates to the screen edge (with a warning) instead of failing
+        #[arg(long)]
+        clamp: bool,
+    },
+
+    /// Two-finger pinch gesture (in = zoom out, out = zoom in)
+    Pinch {
+        /// Pinch direction: in or out
+        direction: String,
+        /// Pinch center as x,y (default: screen center)
+        #[arg(long, value_name = "X,Y")]
+        center: Option<String>,
+        /// Finger travel span in pixels
+        #[arg(long)]
+        distance: Option<u16>,
+        /// Direct evdev device for fast mode (e.g., /dev/input/event4 or "auto")
+        #[arg(long)]
+        event: Option<String>,
     },
 
     /// Send key event (power, home, back, volume, etc.)
@@ -128,20 +347,69 @@ enum Commands {
     /// Take screenshot of device
     Screenshot {
         /// Output file path (defaults to screenshot_TIMESTAMP.png)
+        #[arg(short, long, conflicts_with = "all_devices")]
+        output: Option<String>,
+        /// Capture every enabled device concurrently (screenshot_<host>_TIMESTAMP.png)
+        #[arg(long)]
+        all_devices: bool,
+        /// Directory for the screenshot when --output is not given (also AUDB_OUTPUT_DIR)
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Compare the capture against a baseline PNG and fail if it differs
+        #[arg(long, value_name = "BASELINE", conflicts_with = "all_devices")]
+        compare: Option<PathBuf>,
+        /// Largest allowed fraction of differing pixels for --compare
+        #[arg(long, default_value_t = 0.02, requires = "compare")]
+        threshold: f64,
+        /// Write an image highlighting the differing pixels in red
+        #[arg(long, value_name = "FILE", requires = "compare")]
+        diff: Option<PathBuf>,
+        /// Display to capture on devices with several outputs, numbered from 0 (default: primary)
+        #[arg(long, value_name = "N")]
+        display: Option<u32>,
+    },
+
+    /// Bundle device info, logs, a screenshot and more into a zip for bug reports
+    Bugreport {
+        /// Output file path (defaults to bugreport_HOST_TIMESTAMP.zip)
         #[arg(short, long)]
         output: Option<String>,
+        /// Directory for the report when --output is not given (also AUDB_OUTPUT_DIR)
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// Collect only these components (comma-separated; default: all)
+        #[arg(long, value_name = "LIST", value_delimiter = ',', value_parser = BUGREPORT_COMPONENTS)]
+        only: Vec<String>,
+        /// Leave these components out (comma-separated)
+        #[arg(long, value_name = "LIST", value_delimiter = ',', value_parser = BUGREPORT_COMPONENTS)]
+        skip: Vec<String>,
+        /// Number of journal lines to include
+        #[arg(long, default_value_t = 2000)]
+        log_lines: usize,
     },
 
     /// Launch application on device
     Launch {
         /// Application name (D-Bus format: ru.domain.AppName)
         app_name: String,
+        /// Argument to pass to the app (repeatable)
+        #[arg(long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// URI to open in the app (deep link), e.g. myapp://item/42
+        #[arg(long)]
+        uri: Option<String>,
     },
 
     /// Stop application on device
     Stop {
         /// Application name (D-Bus format: ru.domain.AppName)
         app_name: String,
+        /// Kill the app (SIGKILL) if it has not exited after --timeout seconds
+        #[arg(long)]
+        force: bool,
+        /// With --force, seconds to wait for a graceful exit (default: 5)
+        #[arg(long, value_name = "SECONDS", requires = "force")]
+        timeout: Option<u64>,
     },
 
     /// Retrieve device logs
@@ -150,7 +418,8 @@ enum Commands {
         #[arg(short = 'n', long, default_value = "100")]
         lines: usize,
 
-        /// Filter by priority level (0-7 or debug, info, notice, warning, err, crit, alert, emerg)
+        /// Filter by priority level (0-7 or debug, info, notice, warning, err, crit, alert, emerg),
+        /// or a range such as warning..err
         #[arg(short, long)]
         priority: Option<String>,
 
@@ -166,6 +435,10 @@ enum Commands {
         #[arg(short, long)]
         since: Option<String>,
 
+        /// Show only entries from the current boot (journalctl -b 0)
+        #[arg(long, conflicts_with = "since")]
+        since_boot: bool,
+
         /// Clear all logs (requires --force)
         #[arg(long)]
         clear: bool,
@@ -177,9 +450,46 @@ enum Commands {
         /// Show kernel messages only
         #[arg(short, long)]
         kernel: bool,
+
+        /// Keep printing new entries as they arrive (Ctrl+C to stop)
+        #[arg(short, long, conflicts_with = "clear")]
+        follow: bool,
+
+        /// Write logs to this file instead of the terminal
+        #[arg(short, long, value_name = "FILE", conflicts_with = "clear")]
+        output_file: Option<PathBuf>,
+
+        /// journalctl output format: short, short-iso, short-precise, short-monotonic,
+        /// short-unix, cat (message only), verbose or json
+        #[arg(long, value_name = "FORMAT", conflicts_with = "clear")]
+        format: Option<String>,
+
+        /// With --follow and -o, roll FILE over to FILE.1, FILE.2, ... at this size (e.g. 50M)
+        #[arg(long, value_name = "SIZE", requires_all = ["follow", "output_file"])]
+        rotate_size: Option<String>,
+
+        /// Block until a new entry contains PATTERN, then print it and exit
+        #[arg(long, value_name = "PATTERN", conflicts_with_all = ["clear", "follow", "output_file"])]
+        wait_for: Option<String>,
+
+        /// With --wait-for, give up (exit non-zero) after this many seconds
+        #[arg(long, value_name = "SECONDS", requires = "wait_for")]
+        timeout: Option<u64>,
+
+        /// Treat the --wait-for pattern as an extended regular expression
+        #[arg(long, requires = "wait_for")]
+        regex: bool,
+
+        /// Always show output through $PAGER (default: less -R)
+        #[arg(long)]
+        pager: bool,
+
+        /// Never use a pager (by default one is used when output exceeds the terminal)
+        #[arg(long, conflicts_with = "pager")]
+        no_pager: bool,
     },
 
-    /// Force reconnection to device(s)
+    /// Force reconnection to device(s), reloading their config from disk
     Reconnect {
         /// Device to reconnect (reconnects all if not specified)
         device: Option<String>,
@@ -190,36 +500,144 @@ enum Commands {
         /// URL to open (https://, file://, tel:, mailto:, etc.)
         url: String,
     },
+
+    /// Push a local file to the device's Downloads folder and open it
+    OpenFile {
+        /// Local file path
+        local: String,
+    },
+}
+
+/// Server settings passed through by `audb start-server`
+#[derive(Args, Default, Clone)]
+struct ServerOptions {
+    /// Close device connections unused for this many minutes (0 = never)
+    #[arg(long)]
+    idle_timeout: Option<u64>,
+
+    /// Record every device command to ~/.config/audb/audit/<host>.log
+    #[arg(long)]
+    audit: bool,
+
+    /// Cut off command output larger than this many MiB
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_output_mb: Option<u64>,
+
+    /// Maximum number of clients served at once
+    #[arg(long)]
+    max_clients: Option<usize>,
+
+    /// Reject requests beyond this many per second on one client connection
+    #[arg(long, value_name = "N")]
+    rate_limit: Option<u32>,
+
+    /// Keep temp files from installs, pushes, screenshots and scripts for debugging
+    #[arg(long)]
+    keep_temp: bool,
+}
+
+impl ServerOptions {
+    /// Convert to audb-server command-line arguments
+    fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(minutes) = self.idle_timeout {
+            args.extend(["--idle-timeout".to_string(), minutes.to_string()]);
+        }
+
+        if self.audit {
+            args.push("--audit".to_string());
+        }
+
+        if let Some(mb) = self.max_output_mb {
+            args.extend(["--max-output-mb".to_string(), mb.to_string()]);
+        }
+
+        if let Some(clients) = self.max_clients {
+            args.extend(["--max-clients".to_string(), clients.to_string()]);
+        }
+
+        if let Some(rate) = self.rate_limit {
+            args.extend(["--rate-limit".to_string(), rate.to_string()]);
+        }
+
+        if self.keep_temp {
+            args.push("--keep-temp".to_string());
+        }
+
+        args
+    }
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum DeviceCommands {
     /// List all devices
     List {
         /// Show only active (reachable) devices
         #[arg(short, long)]
         active: bool,
+        /// With --active, probe at most N devices at once
+        #[arg(long, value_name = "N", default_value_t = audb_core::features::device::list::DEFAULT_PARALLEL_PROBES, requires = "active")]
+        parallel: usize,
+        /// Output format: table, json (with live status) or plain (tab-separated, no header)
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "plain"])]
+        format: String,
     },
     /// Add a new device interactively
-    Add,
+    Add {
+        /// Retry the connection test this many times before giving up
+        #[arg(long, default_value = "0")]
+        retries: u32,
+        /// Delay between connection retries in seconds
+        #[arg(long, default_value = "5")]
+        retry_delay: u64,
+        /// Don't probe ports 22, 2222 and 2223 for an SSH server to suggest the port
+        #[arg(long)]
+        no_probe_port: bool,
+    },
     /// Remove a device
     Remove {
         /// Device identifier (name, IP address, or index)
         identifier: String,
     },
+    /// Check right now whether a device's connection is alive
+    Check {
+        /// Device identifier (name, IP address, or index; default: current device)
+        identifier: Option<String>,
+    },
+    /// Make a running server pick up device config changes
+    Reload,
+    /// Show recent connection state changes of a device
+    History {
+        /// Device identifier (name, IP address, or index; default: current device)
+        identifier: Option<String>,
+    },
+    /// Check devices.json for syntax errors and duplicate devices
+    ValidateConfig,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum PackageCommands {
     /// Install RPM package on device
     Install {
         /// Path to RPM file
-        rpm_path: String,
+        #[arg(required_unless_present = "project")]
+        rpm_path: Option<String>,
+        /// Install the RPM(s) built in this project's RPMS/<arch>/ for the device's platform
+        #[arg(long, value_name = "DIR", conflicts_with = "rpm_path")]
+        project: Option<PathBuf>,
+        /// Check that the package's dependencies are installed first
+        #[arg(long)]
+        check_deps: bool,
+        /// Install with APM over D-Bus (default), or with pkcon or rpm as root
+        #[arg(long, default_value = "apm", value_parser = ["apm", "pkcon", "rpm"])]
+        backend: String,
     },
     /// Uninstall package from device
     Uninstall {
-        /// Package name (e.g., ru.domain.AppName)
-        package_name: String,
+        /// Package name(s) (e.g., ru.domain.AppName); failures do not stop the rest
+        #[arg(required = true)]
+        package_names: Vec<String>,
     },
     /// List installed packages on device
     List {
@@ -227,7 +645,7 @@ enum PackageCommands {
         #[arg(short, long)]
         filter: Option<String>,
     },
-    /// Sign RPM package with Aurora OS keys (local, uses Docker)
+    /// Sign RPM package with Aurora OS keys (local rpmsign-external, else Docker)
     Sign {
         /// Path to RPM file
         rpm_path: String,
@@ -237,50 +655,344 @@ enum PackageCommands {
         /// Custom path to signing certificate (default: auto-download to cache)
         #[arg(long)]
         cert: Option<String>,
+        /// Signing profile; its keys are looked up in ~/.cache/audb/<profile>_{key,cert}.pem
+        #[arg(long, default_value = "regular", value_parser = ["regular", "developer"])]
+        profile: String,
+        #[command(flatten)]
+        image: SdkImageOptions,
     },
     /// Validate RPM package for Aurora OS compliance (local, uses Docker)
     Validate {
         /// Path to RPM file
         rpm_path: String,
+        /// Instead, dry-run the install on the selected device (`rpm -U --test`)
+        /// to catch missing dependencies and conflicts there
+        #[arg(long, conflicts_with_all = ["docker_image", "image_match"])]
+        on_device: bool,
+        #[command(flatten)]
+        image: SdkImageOptions,
+    },
+    /// Show an RPM's name, version, architecture and signature (local, no Docker)
+    Show {
+        /// Path to RPM file
+        rpm_path: String,
     },
 }
 
+/// Selection of the Aurora SDK Docker image used by `package sign`/`validate`
+#[derive(Args, Default, Clone)]
+struct SdkImageOptions {
+    /// Aurora SDK Docker image to use, skipping the search (default: $AUDB_SDK_IMAGE, unless --image-match is given)
+    #[arg(long, value_name = "NAME:TAG")]
+    docker_image: Option<String>,
+
+    /// Only consider local images whose name:tag contains this text
+    /// (default: $AUDB_SDK_IMAGE_MATCH, else Aurora build/sdk/engine images)
+    #[arg(long, value_name = "TEXT", conflicts_with = "docker_image")]
+    image_match: Option<String>,
+}
+
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::from_arg_matches(&command_with_examples().get_matches()).unwrap_or_else(|e| e.exit());
+
+    // Auto-started servers inherit the variable and listen on the same socket
+    if let Some(socket) = &cli.socket {
+        std::env::set_var(audb_core::features::config::instance::SOCKET_ENV, socket);
+    }
 
     let device_override = cli.device;
+    let json_errors = cli.json_errors;
+
+    let retries = cli.server_retries.or_else(|| env_option(SERVER_RETRIES_ENV).and_then(|value| value.parse().ok()));
+    if let Some(retries) = retries {
+        SERVER_RETRIES.store(retries, Ordering::Relaxed);
+    }
+
+    let result = match cli.repeat {
+        Some(interval) => run_repeated(cli.command, device_override, interval).await,
+        None => run_command(cli.command, device_override).await,
+    };
 
-    let result = match cli.command {
+    if let Err(e) = result {
+        exit_with_error(e, json_errors);
+    }
+}
+
+/// A curated example: what it does and the arguments after `audb`
+struct Example {
+    description: &'static str,
+    args: &'static [&'static str],
+}
+
+/// Examples for one subcommand, given as its path (e.g. `package install`)
+struct ExampleTopic {
+    command: &'static str,
+    examples: &'static [Example],
+}
+
+/// Examples shown by `audb examples` and after the matching subcommand's `--help`
+///
+/// Every `command` must name an existing subcommand (help setup panics
+/// otherwise), and debug builds check that each example parses.
+const EXAMPLES: &[ExampleTopic] = &[
+    ExampleTopic {
+        command: "device",
+        examples: &[
+            Example { description: "Add a device (asks for host, port, key and root password)", args: &["device", "add"] },
+            Example { description: "List devices, checking which are reachable", args: &["device", "list", "--active"] },
+            Example { description: "Remove a device by index, IP address or name", args: &["device", "remove", "my-phone"] },
+        ],
+    },
+    ExampleTopic {
+        command: "select",
+        examples: &[
+            Example { description: "Make a device active by its index in 'audb device list'", args: &["select", "0"] },
+            Example { description: "...or by IP address or name", args: &["select", "192.168.2.15"] },
+            Example { description: "Run one command on another device without switching", args: &["-d", "my-phone", "shell", "uname", "-a"] },
+        ],
+    },
+    ExampleTopic {
+        command: "shell",
+        examples: &[
+            Example { description: "Run a command", args: &["shell", "uname", "-a"] },
+            Example { description: "Use pipes and variables: pass one quoted command line", args: &["shell", "ls /usr/share/applications | head"] },
+            Example { description: "Run as root (needs the root password)", args: &["shell", "--root", "systemctl restart connman"] },
+        ],
+    },
+    ExampleTopic {
+        command: "tap",
+        examples: &[
+            Example { description: "Tap at x=360, y=720 (screen pixels)", args: &["tap", "360", "720"] },
+            Example { description: "Long press for 800 ms", args: &["tap", "360", "720", "--duration", "800"] },
+            Example { description: "Tap the on-screen element labelled OK", args: &["tap", "--label", "OK"] },
+        ],
+    },
+    ExampleTopic {
+        command: "swipe",
+        examples: &[
+            Example { description: "Swipe by direction: left, right, up or down", args: &["swipe", "left"] },
+            Example { description: "Swipe between coordinates: x1 y1 x2 y2", args: &["swipe", "360", "1200", "360", "300"] },
+            Example { description: "Fast flick (profiles: linear, ease-in, ease-out, fling)", args: &["swipe", "up", "--profile", "fling"] },
+        ],
+    },
+    ExampleTopic {
+        command: "key",
+        examples: &[
+            Example { description: "Go to the home screen", args: &["key", "home"] },
+            Example { description: "Volume up (also: volumedown/vol-, power, back, menu, close, lock, unlock)", args: &["key", "vol+"] },
+            Example { description: "Wake and unlock the screen", args: &["key", "unlock"] },
+        ],
+    },
+    ExampleTopic {
+        command: "logs",
+        examples: &[
+            Example { description: "Follow an app's logs (Ctrl+C to stop)", args: &["logs", "--follow", "--unit", "lipstick"] },
+            Example { description: "Errors from the last hour", args: &["logs", "--priority", "err", "--since", "1 hour ago"] },
+            Example { description: "Wait up to 60s for a line, e.g. in CI", args: &["logs", "--wait-for", "Ready", "--timeout", "60"] },
+        ],
+    },
+    ExampleTopic {
+        command: "package install",
+        examples: &[
+            Example { description: "Install an RPM", args: &["package", "install", "app.rpm"] },
+            Example { description: "Install the RPM built in a project for the device's platform", args: &["package", "install", "--project", "."] },
+            Example { description: "Check dependencies are installed first", args: &["package", "install", "app.rpm", "--check-deps"] },
+        ],
+    },
+    ExampleTopic {
+        command: "push",
+        examples: &[
+            Example { description: "Copy a file to the device (~ is the device user's home)", args: &["push", "local.txt", "~/Documents/remote.txt"] },
+            Example { description: "Create missing directories on the way", args: &["push", "--mkdirs", "local.txt", "~/new/dir/remote.txt"] },
+        ],
+    },
+    ExampleTopic {
+        command: "pull",
+        examples: &[
+            Example { description: "Copy a file from the device", args: &["pull", "~/Documents/report.pdf"] },
+            Example { description: "Copy a whole directory in one transfer", args: &["pull", "--tar", "~/Documents", "./backup"] },
+        ],
+    },
+    ExampleTopic {
+        command: "screenshot",
+        examples: &[
+            Example { description: "Save a screenshot as screenshot_<timestamp>.png", args: &["screenshot"] },
+            Example { description: "Save it under a given name", args: &["screenshot", "-o", "screen.png"] },
+        ],
+    },
+    ExampleTopic {
+        command: "launch",
+        examples: &[
+            Example { description: "Start an app by its D-Bus name", args: &["launch", "ru.example.MyApp"] },
+            Example { description: "Open a deep link in the app", args: &["launch", "ru.example.MyApp", "--uri", "myapp://item/42"] },
+        ],
+    },
+];
+
+/// Render a topic's examples, each as a comment line followed by the command line
+fn format_examples(topic: &ExampleTopic) -> String {
+    topic
+        .examples
+        .iter()
+        .map(|example| {
+            format!(
+                "  # {}\n  audb {}",
+                example.description,
+                audb_core::tools::shell_escape::join_args(example.args)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The clap command with each topic's examples appended to its subcommand's help
+fn command_with_examples() -> clap::Command {
+    fn add_help(command: clap::Command, path: &[&str], help: String) -> clap::Command {
+        match path.split_first() {
+            Some((name, rest)) => command.mut_subcommand(*name, |sub| add_help(sub, rest, help)),
+            None => command.after_help(help),
+        }
+    }
+
+    EXAMPLES.iter().fold(Cli::command(), |command, topic| {
+        let path: Vec<&str> = topic.command.split(' ').collect();
+        add_help(command, &path, format!("Examples:\n{}", format_examples(topic)))
+    })
+}
+
+/// Execute Examples command
+///
+/// A topic matches by its full command (`package install`) or its last word (`install`).
+fn execute_examples_command(command: Option<&str>) -> Result<()> {
+    let topics: Vec<&ExampleTopic> = match command {
+        None => EXAMPLES.iter().collect(),
+        Some(name) => {
+            let name = name.trim();
+            let found: Vec<&ExampleTopic> = EXAMPLES
+                .iter()
+                .filter(|topic| topic.command == name || topic.command.rsplit(' ').next() == Some(name))
+                .collect();
+            if found.is_empty() {
+                let names: Vec<&str> = EXAMPLES.iter().map(|topic| topic.command).collect();
+                return Err(anyhow!("No examples for '{}'. Examples exist for: {}", name, names.join(", ")));
+            }
+            found
+        }
+    };
+
+    for (index, topic) in topics.iter().enumerate() {
+        for example in topic.examples {
+            debug_assert!(
+                Cli::try_parse_from(std::iter::once("audb").chain(example.args.iter().copied())).is_ok(),
+                "example no longer parses: audb {}",
+                example.args.join(" ")
+            );
+        }
+        if index > 0 {
+            println!();
+        }
+        println!("\x1b[1m{}\x1b[0m", topic.command);
+        println!("{}", format_examples(topic));
+    }
+    Ok(())
+}
+
+/// Re-dispatch a command every `interval` seconds until Ctrl-C, clearing the screen between runs
+async fn run_repeated(command: Commands, device_override: Option<String>, interval: u64) -> Result<()> {
+    let invocation = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    REPEATING.store(true, Ordering::Relaxed);
+    CTRL_C_HANDLED.store(true, Ordering::Relaxed);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        // Clear screen and move cursor home
+        print!("\x1b[2J\x1b[H");
+        println!("Every {}s: audb {}    {}\n", interval, invocation, chrono::Local::now().format("%H:%M:%S"));
+
+        let iteration = async {
+            // A failed run is shown and retried on the next tick rather than ending the loop;
+            // the server may be what failed, so the next run checks it again
+            if let Err(e) = run_command(command.clone(), device_override.clone()).await {
+                eprintln!("\x1b[1m\x1b[31merror\x1b[0m: {}", e);
+                SERVER_CHECKED.store(false, Ordering::Relaxed);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+        };
+
+        tokio::select! {
+            _ = iteration => {}
+            _ = &mut ctrl_c => {
+                println!();
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Dispatch a parsed command to its handler
+async fn run_command(command: Commands, device_override: Option<String>) -> Result<()> {
+    match command {
         // Device management commands (run locally, not through server)
         Commands::Device { action } => match action {
-            DeviceCommands::List { active } => {
-                audb_core::features::device::list::execute(active).await
+            DeviceCommands::List { active, parallel, format } => {
+                use audb_core::features::device::list::{execute, ListFormat};
+                execute(active, parallel, ListFormat::parse(&format)?).await
             }
-            DeviceCommands::Add => {
-                audb_core::features::device::add::execute().await
+            DeviceCommands::Add { retries, retry_delay, no_probe_port } => {
+                audb_core::features::device::add::execute(retries, retry_delay, !no_probe_port).await?;
+                sync_server_devices().await;
+                Ok(())
             }
             DeviceCommands::Remove { identifier } => {
-                audb_core::features::device::remove::execute(&identifier).await
+                audb_core::features::device::remove::execute(&identifier).await?;
+                sync_server_devices().await;
+                Ok(())
+            }
+            DeviceCommands::Check { identifier } => {
+                execute_check_command(identifier.or(device_override)).await
+            }
+            DeviceCommands::Reload => {
+                execute_reload_devices_command().await
+            }
+            DeviceCommands::History { identifier } => {
+                execute_history_command(identifier.or(device_override)).await
+            }
+            DeviceCommands::ValidateConfig => {
+                audb_core::features::device::validate::execute().await
             }
         },
 
         // Package management commands
         Commands::Package { action } => match action {
-            PackageCommands::Install { rpm_path } => {
-                execute_install_command(device_override, rpm_path).await
+            PackageCommands::Install { rpm_path, project, check_deps, backend } => {
+                let backend = parse_install_backend(&backend)?;
+                match (rpm_path, project) {
+                    (Some(rpm_path), _) => execute_install_command(device_override, rpm_path, check_deps, backend).await,
+                    (None, Some(project)) => {
+                        execute_install_project_command(device_override, project, check_deps, backend).await
+                    }
+                    (None, None) => Err(anyhow!("Specify an RPM file or --project")),
+                }
             }
-            PackageCommands::Uninstall { package_name } => {
-                execute_uninstall_command(device_override, package_name).await
+            PackageCommands::Uninstall { package_names } => {
+                execute_uninstall_command(device_override, package_names).await
             }
             PackageCommands::List { filter } => {
                 execute_packages_command(device_override, filter).await
             }
-            PackageCommands::Sign { rpm_path, key, cert } => {
-                execute_sign_command(rpm_path, key, cert).await
+            PackageCommands::Sign { rpm_path, key, cert, profile, image } => {
+                execute_sign_command(rpm_path, key, cert, profile, image).await
+            }
+            PackageCommands::Validate { rpm_path, on_device: true, .. } => {
+                execute_validate_on_device_command(device_override, rpm_path).await
             }
-            PackageCommands::Validate { rpm_path } => {
-                execute_validate_command(rpm_path).await
+            PackageCommands::Validate { rpm_path, image, .. } => {
+                execute_validate_command(rpm_path, image).await
+            }
+            PackageCommands::Show { rpm_path } => {
+                execute_package_show_command(rpm_path)
             }
         },
 
@@ -292,8 +1004,8 @@ async fn main() {
         Commands::Ping => {
             execute_command(Command::Ping).await
         }
-        Commands::StartServer { foreground } => {
-            start_server(foreground).await
+        Commands::StartServer { foreground, options } => {
+            start_server(foreground, &options).await
         }
         Commands::KillServer => {
             kill_server().await
@@ -301,37 +1013,121 @@ async fn main() {
         Commands::ServerStatus => {
             execute_command(Command::ServerStatus).await
         }
+        Commands::Metrics { json } => {
+            execute_metrics_command(json).await
+        }
+        Commands::Doctor => {
+            execute_doctor_command(device_override).await
+        }
+        Commands::Examples { command } => execute_examples_command(command.as_deref()),
 
         // Device commands (through server)
-        Commands::Shell { root, command } => {
-            execute_shell_command(device_override, root, command).await
+        Commands::Shell { root, as_user, tty, time, login, head, tail, from_file, command } => {
+            let script = from_file
+                .map(|path| {
+                    std::fs::read_to_string(&path)
+                        .map_err(|e| anyhow!("Failed to read script {}: {}", path.display(), e))
+                })
+                .transpose()?;
+            execute_shell_command(device_override, ShellOptions { as_root: root, run_as: as_user, pty: tty, time, login, head, tail, script }, command).await
+        }
+        Commands::Push { local, remote, content, mkdirs } => {
+            // With --content the single positional argument is the remote path
+            let (local, remote) = match (content.is_some(), local, remote) {
+                (true, Some(remote), None) => (None, remote),
+                (true, Some(_), Some(_)) => return Err(anyhow!("--content replaces the local file; give only the remote path")),
+                (false, Some(local), Some(remote)) => (Some(local), remote),
+                _ => return Err(anyhow!("Missing remote destination path")),
+            };
+            execute_push_command(device_override, local, content, remote, mkdirs).await
+        }
+        Commands::Pull { remote, local, output, output_dir, tar } => {
+            if tar {
+                execute_pull_tar(device_override, remote, local.or(output), output_dir).await
+            } else {
+                execute_pull_command(device_override, remote, output, output_dir).await
+            }
+        }
+        Commands::Cat { remote, root } => {
+            execute_cat_command(device_override, remote, root).await
         }
-        Commands::Push { local, remote } => {
-            execute_push_command(device_override, local, remote).await
+        Commands::Info { category, field, json } => {
+            execute_info_command(device_override, category, field, json).await
         }
-        Commands::Pull { remote, output } => {
-            execute_pull_command(device_override, remote, output).await
+        Commands::Diag { topic } => {
+            let topic = match topic.as_str() {
+                "input" => audb_protocol::DiagTopic::Input,
+                other => return Err(anyhow!("Unknown diagnostic topic: {}", other)),
+            };
+            let device = get_device(device_override)?;
+            execute_command(Command::Diagnose { device, topic }).await
         }
-        Commands::Info { category } => {
-            execute_info_command(device_override, category).await
+        Commands::Radio { radio, state, force } => {
+            let radio = match radio.as_str() {
+                "wifi" => audb_protocol::Radio::Wifi,
+                "bluetooth" => audb_protocol::Radio::Bluetooth,
+                "airplane" => audb_protocol::Radio::Airplane,
+                other => return Err(anyhow!("Unknown radio: {}", other)),
+            };
+            let device = get_device(device_override)?;
+            execute_command(Command::Radio { device, radio, enabled: state == "on", force }).await
         }
-        Commands::Tap { x, y, event, duration } => {
-            execute_tap_command(device_override, x, y, event, duration).await
+        Commands::Service { action, unit, user } => {
+            let action = match action.as_str() {
+                "start" => audb_protocol::ServiceAction::Start,
+                "stop" => audb_protocol::ServiceAction::Stop,
+                "restart" => audb_protocol::ServiceAction::Restart,
+                "status" => audb_protocol::ServiceAction::Status,
+                other => return Err(anyhow!("Unknown service action: {}", other)),
+            };
+            let device = get_device(device_override)?;
+            execute_command(Command::Service { device, action, unit, user }).await
         }
-        Commands::Swipe { args, event } => {
-            execute_swipe_command(device_override, args, event).await
+        Commands::Free => {
+            execute_builtin_command(device_override, audb_protocol::Builtin::Free).await
+        }
+        Commands::Date => {
+            execute_builtin_command(device_override, audb_protocol::Builtin::Date).await
+        }
+        Commands::Df => {
+            execute_builtin_command(device_override, audb_protocol::Builtin::Df).await
+        }
+        Commands::Tap { x, y, label, event, fast, duration, orientation, clamp } => {
+            let target = match (label, x, y) {
+                (Some(label), _, _) => TapTarget::Label(label),
+                (None, Some(x), Some(y)) => TapTarget::Point(x, y),
+                _ => return Err(anyhow!("Give X and Y coordinates or --label")),
+            };
+            execute_tap_command(device_override, target, fast_event(event, fast), duration, orientation, clamp).await
+        }
+        Commands::UiDump { app, json, output } => {
+            execute_ui_dump_command(device_override, app, json, output).await
+        }
+        Commands::Swipe { args, event, fast, profile, duration, orientation, clamp } => {
+            execute_swipe_command(device_override, args, fast_event(event, fast), profile, duration, orientation, clamp).await
+        }
+        Commands::Pinch { direction, center, distance, event } => {
+            execute_pinch_command(device_override, direction, center, distance, event).await
         }
         Commands::Key { key_name } => {
             execute_key_command(device_override, key_name).await
         }
-        Commands::Screenshot { output } => {
-            execute_screenshot_command(device_override, output).await
+        Commands::Bugreport { output, output_dir, only, skip, log_lines } => {
+            execute_bugreport_command(device_override, output, output_dir, only, skip, log_lines).await
+        }
+        Commands::Screenshot { output, all_devices, output_dir, compare, threshold, diff, display } => {
+            if all_devices {
+                execute_screenshot_all_command(output_dir, display).await
+            } else {
+                let compare = compare.map(|baseline| ScreenshotCompare { baseline, threshold, diff });
+                execute_screenshot_command(device_override, output, output_dir, compare, display).await
+            }
         }
-        Commands::Launch { app_name } => {
-            execute_launch_command(device_override, app_name).await
+        Commands::Launch { app_name, args, uri } => {
+            execute_launch_command(device_override, app_name, args, uri).await
         }
-        Commands::Stop { app_name } => {
-            execute_stop_command(device_override, app_name).await
+        Commands::Stop { app_name, force, timeout } => {
+            execute_stop_command(device_override, app_name, force, timeout).await
         }
         Commands::Logs {
             lines,
@@ -339,34 +1135,116 @@ async fn main() {
             unit,
             grep,
             since,
+            since_boot,
             clear,
             force,
             kernel,
+            follow,
+            output_file,
+            format,
+            rotate_size,
+            wait_for,
+            timeout,
+            regex,
+            pager,
+            no_pager,
         } => {
-            execute_logs_command(device_override, lines, priority, unit, grep, since, clear, force, kernel).await
+            let args = audb_protocol::LogsArgs {
+                lines,
+                priority,
+                unit,
+                grep,
+                since,
+                clear,
+                force,
+                kernel,
+                show_cursor: false,
+                after_cursor: None,
+                wait_for,
+                regex,
+                since_boot,
+                output: format,
+            };
+            let output = LogsOutput {
+                paging: match (pager, no_pager) {
+                    (true, _) => Paging::Always,
+                    (_, true) => Paging::Never,
+                    _ => Paging::Auto,
+                },
+                follow,
+                output_file,
+                rotate_size: rotate_size.as_deref().map(parse_size).transpose()?,
+                timeout,
+            };
+            execute_logs_command(device_override, args, output).await
         }
         Commands::Reconnect { device } => {
+            let device = device.as_deref().map(resolve_device_host).transpose()?;
             execute_command(Command::Reconnect { device }).await
         }
         Commands::Open { url } => {
             execute_open_command(device_override, url).await
         }
-    };
-
-    if let Err(e) = result {
-        exit_error!("{}", e);
+        Commands::OpenFile { local } => {
+            execute_open_file_command(device_override, local).await
+        }
     }
 }
 
+/// How `audb shell` runs its command
+struct ShellOptions {
+    as_root: bool,
+    run_as: Option<String>,
+    pty: bool,
+    time: bool,
+    login: bool,
+    head: Option<usize>,
+    tail: Option<usize>,
+    /// Contents of `--from-file`, run instead of the command
+    script: Option<String>,
+}
+
 /// Execute shell command through server
-async fn execute_shell_command(device_override: Option<String>, as_root: bool, command_parts: Vec<String>) -> Result<()> {
+async fn execute_shell_command(
+    device_override: Option<String>,
+    options: ShellOptions,
+    command_parts: Vec<String>,
+) -> Result<()> {
+    let ShellOptions { as_root, run_as, pty, time, login, head, tail, script } = options;
     let device = get_device(device_override)?;
-    let command = command_parts.join(" ");
+
+    if let Some(ref user) = run_as {
+        audb_core::tools::validation::validate_user_name(user)?;
+        let configured = audb_core::features::config::device_store::DeviceStore::find(
+            &audb_core::tools::types::DeviceIdentifier::Host(device.clone()),
+        );
+        if configured.is_ok_and(|d| d.root_password.is_empty()) {
+            return Err(anyhow!(
+                "Running as {} needs the device's root password (switching users goes through devel-su). Use 'audb device add' to set it.",
+                user
+            ));
+        }
+    }
+
+    // One argument is a command line for the device shell; several are an
+    // argument vector whose boundaries must survive (e.g. paths with spaces)
+    let (command, args) = match <[String; 1]>::try_from(command_parts) {
+        Ok([command_line]) => (command_line, vec![]),
+        Err(args) => (String::new(), args),
+    };
 
     execute_command(Command::Shell {
         device,
         root: as_root,
+        pty,
         command,
+        args,
+        run_as,
+        time,
+        script,
+        login,
+        head,
+        tail,
     }).await
 }
 
@@ -375,20 +1253,13 @@ async fn execute_command(command: Command) -> Result<()> {
     // Ensure server is running (auto-start if needed)
     ensure_server_running().await?;
 
-    // Connect to server
-    let mut stream = connect_to_server().await?;
-
     // Generate request ID
     let request = Request {
         id: generate_request_id(),
         command,
     };
 
-    // Send request
-    send_message(&mut stream, &request).await?;
-
-    // Receive response
-    let response: Response = recv_message(&mut stream).await?;
+    let response = send_request(request).await?;
 
     // Handle response
     handle_response(response)?;
@@ -440,10 +1311,19 @@ fn handle_response(response: Response) -> Result<()> {
                         }
                     }
                 }
+                CommandOutput::Metrics(metrics) => {
+                    print_metrics(&metrics);
+                }
                 CommandOutput::DeviceInfo(info) => {
                     // This is handled specially in execute_info_command
                     print_device_info(&info, None);
                 }
+                CommandOutput::History(transitions) => {
+                    print_history(&transitions);
+                }
+                CommandOutput::Elements(elements) => {
+                    print_ui_elements(&elements);
+                }
                 CommandOutput::Unit => {
                     // No output
                 }
@@ -453,18 +1333,41 @@ fn handle_response(response: Response) -> Result<()> {
         CommandResult::Error { message, kind } => {
             // Improve error message for disconnected device
             if message.contains("deadline has elapsed") || message.contains("Channel send error") {
-                Err(anyhow!("Device disconnected or unreachable. Check 'audb device list' for status."))
+                Err(ServerError {
+                    kind: ErrorKind::DeviceDisconnected,
+                    message: "Device disconnected or unreachable. Check 'audb device list' for status.".to_string(),
+                }.into())
             } else {
-                Err(anyhow!("{:?}: {}", kind, message))
+                Err(ServerError { kind, message }.into())
             }
         }
     }
 }
 
+/// Error for a successful response whose output variant a command did not expect
+///
+/// Names the variant actually received and any text it carries, to make
+/// client/server protocol mismatches easier to track down.
+fn unexpected_output(command: &str, output: &CommandOutput) -> anyhow::Error {
+    let received = match output {
+        CommandOutput::Lines(lines) => match lines.first() {
+            Some(first) => format!("Lines ({} lines, first: {:?})", lines.len(), first),
+            None => "Lines (empty)".to_string(),
+        },
+        CommandOutput::Binary(data) => format!("Binary ({} bytes)", data.len()),
+        CommandOutput::Status(_) => "Status".to_string(),
+        CommandOutput::Metrics(_) => "Metrics".to_string(),
+        CommandOutput::DeviceInfo(_) => "DeviceInfo".to_string(),
+        CommandOutput::History(transitions) => format!("History ({} entries)", transitions.len()),
+        CommandOutput::Elements(elements) => format!("Elements ({} elements)", elements.len()),
+        CommandOutput::Unit => "Unit".to_string(),
+    };
+    anyhow!("Unexpected output format for {}: got {}", command, received)
+}
+
 /// Get the path to the Unix socket
 fn socket_path() -> PathBuf {
-    let uid = unsafe { libc::getuid() };
-    PathBuf::from(format!("/tmp/audb-server-{}.sock", uid))
+    audb_core::features::config::instance::ServerInstance::socket_path()
 }
 
 /// Connect to the server via Unix socket
@@ -475,39 +1378,163 @@ async fn connect_to_server() -> Result<UnixStream> {
         .map_err(|e| anyhow!("Failed to connect to server at {}: {}", socket_path.display(), e))
 }
 
-/// Check if the server is running
-async fn is_server_running() -> bool {
-    connect_to_server().await.is_ok()
-}
+/// Environment variable with the default for `--server-retries`
+const SERVER_RETRIES_ENV: &str = "AUDB_SERVER_RETRIES";
 
-/// Ensure the server is running, auto-starting if needed
-async fn ensure_server_running() -> Result<()> {
-    if !is_server_running().await {
-        println!("Server not running, starting...");
-        start_server(false).await?;
+/// How often a request is retried after a dropped server connection
+static SERVER_RETRIES: AtomicU32 = AtomicU32::new(3);
 
-        // Wait for server to be ready (up to 5 seconds)
-        for _ in 0..50 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            if is_server_running().await {
-                println!("Server started successfully");
-                return Ok(());
-            }
+/// Set while the command handles Ctrl-C itself (`--repeat`, `logs --follow`)
+static CTRL_C_HANDLED: AtomicBool = AtomicBool::new(false);
+
+/// Send a request to the server and wait for its response
+///
+/// On Ctrl-C the server is asked to cancel the request (aborting its device
+/// operation) and the client exits, unless the command handles Ctrl-C itself.
+async fn send_request(request: Request) -> Result<Response> {
+    if CTRL_C_HANDLED.load(Ordering::Relaxed) {
+        return send_request_with_retries(request).await;
+    }
+
+    let id = request.id;
+    tokio::select! {
+        result = send_request_with_retries(request) => result,
+        _ = tokio::signal::ctrl_c() => {
+            cancel_request(id).await;
+            eprintln!("\nCancelled");
+            std::process::exit(130);
         }
+    }
+}
 
-        return Err(anyhow!("Server failed to start within timeout"));
+/// Ask the server to cancel an in-flight request (best effort)
+async fn cancel_request(id: u64) {
+    let cancel = async {
+        let mut stream = connect_to_server().await?;
+        let request = Request {
+            id: generate_request_id(),
+            command: Command::Cancel { request_id: id },
+        };
+        send_message(&mut stream, request).await?;
+        recv_message::<Response, _>(&mut stream).await
+    };
+    if let Ok(Err(e)) = tokio::time::timeout(tokio::time::Duration::from_secs(2), cancel).await {
+        print_warning(format!("Could not cancel the request on the server: {}", e));
     }
-    Ok(())
 }
 
-/// Start the server daemon
-async fn start_server(foreground: bool) -> Result<()> {
-    use std::process::Command as ProcessCommand;
+/// Send a request, retrying it if the server connection drops
+///
+/// Failures to connect or to write the request (socket missing, connection
+/// reset, server gone) are retried with a short backoff, which covers the
+/// window where the server is restarting. Once the request has been sent it
+/// may already be running, so a lost reply is reported rather than retried.
+/// Errors reported by the server are not retried either.
+async fn send_request_with_retries(request: Request) -> Result<Response> {
+    let retries = SERVER_RETRIES.load(Ordering::Relaxed);
+    let encoded = EncodedMessage::new(request)?;
+    let mut attempt = 0;
+    let mut stream = loop {
+        let result = async {
+            let mut stream = UnixStream::connect(socket_path()).await?;
+            encoded.write_to(&mut stream).await?;
+            Ok::<_, anyhow::Error>(stream)
+        }
+        .await;
+
+        match result {
+            Ok(stream) => break stream,
+            Err(e) if e.downcast_ref::<std::io::Error>().is_some() => {
+                if attempt >= retries {
+                    return Err(anyhow!(
+                        "Failed to talk to server at {} ({} attempts): {}",
+                        socket_path().display(),
+                        attempt + 1,
+                        e
+                    ));
+                }
+                attempt += 1;
+                tokio::time::sleep(tokio::time::Duration::from_millis(200 * attempt as u64)).await;
+            }
+            Err(e) => return Err(e),
+        }
+    };
 
-    // Find the server binary - check multiple locations
+    recv_message::<Response, _>(&mut stream)
+        .await
+        .map_err(|e| anyhow!("Lost connection to server at {} while waiting for a reply: {}", socket_path().display(), e))
+}
+
+/// Check if the server is running
+async fn is_server_running() -> bool {
+    connect_to_server().await.is_ok()
+}
+
+/// Reload the running server's device list
+async fn execute_reload_devices_command() -> Result<()> {
+    if !is_server_running().await {
+        println!("Server not running; devices are loaded when it starts");
+        return Ok(());
+    }
+
+    for line in request_lines(Command::ReloadDevices).await? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// After a config change, let a running server pick it up (never starts one)
+async fn sync_server_devices() {
+    if !is_server_running().await {
+        return;
+    }
+
+    if let Err(e) = request_lines(Command::ReloadDevices).await {
+        eprintln!("\x1b[1m\x1b[93mwarning\x1b[0m: Could not reload server devices: {}", e);
+        eprintln!("Run 'audb device reload' to retry");
+    }
+}
+
+/// Set while a `--repeat` loop is running (output must not block on a pager)
+static REPEATING: AtomicBool = AtomicBool::new(false);
+
+/// Set once a `--repeat` run has found the server up, so later runs skip the auto-start check
+static SERVER_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Ensure the server is running, auto-starting if needed
+async fn ensure_server_running() -> Result<()> {
+    if SERVER_CHECKED.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    if !is_server_running().await {
+        println!("Server not running, starting...");
+        start_server(false, &ServerOptions::default()).await?;
+
+        // Wait for server to be ready (up to 5 seconds)
+        for _ in 0..50 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            if is_server_running().await {
+                println!("Server started successfully");
+                SERVER_CHECKED.store(REPEATING.load(Ordering::Relaxed), Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        return Err(anyhow!("Server failed to start within timeout"));
+    }
+    SERVER_CHECKED.store(REPEATING.load(Ordering::Relaxed), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Start the server daemon
+async fn start_server(foreground: bool, options: &ServerOptions) -> Result<()> {
+    use std::process::Command as ProcessCommand;
+
+    // Find the server binary - check multiple locations
     let server_binary = find_server_binary()?;
 
     let mut cmd = ProcessCommand::new(&server_binary);
+    cmd.args(options.to_args());
 
     if foreground {
         cmd.arg("--foreground");
@@ -524,19 +1551,21 @@ async fn start_server(foreground: bool) -> Result<()> {
     Ok(())
 }
 
+/// Look up a program on PATH
+fn which(program: &str) -> Option<PathBuf> {
+    let output = std::process::Command::new("which").arg(program).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
 /// Find the audb-server binary
 fn find_server_binary() -> Result<PathBuf> {
     // 1. Check if audb-server is in PATH
-    if let Ok(output) = std::process::Command::new("which")
-        .arg("audb-server")
-        .output()
-    {
-        if output.status.success() {
-            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path.is_empty() {
-                return Ok(PathBuf::from(path));
-            }
-        }
+    if let Some(path) = which("audb-server") {
+        return Ok(path);
     }
 
     // 2. Check next to the current executable
@@ -580,19 +1609,86 @@ fn generate_request_id() -> u64 {
 
 /// Get device to use: override if provided, otherwise current device
 fn get_device(device_override: Option<String>) -> Result<String> {
-    if let Some(device) = device_override {
-        Ok(device)
+    let identifier = if let Some(device) = device_override {
+        device
     } else {
         std::fs::read_to_string(std::path::PathBuf::from(
             shellexpand::tilde("~/.config/audb/current_device").to_string(),
         ))
         .map(|s| s.trim().to_string())
-        .map_err(|_| anyhow!("No device selected. Use 'audb device list' and 'audb select <device>' first, or use --device flag"))
+        .map_err(|_| anyhow!("No device selected. Use 'audb device list' and 'audb select <device>' first, or use --device flag"))?
+    };
+
+    resolve_device_host(&identifier)
+}
+
+/// Resolve an index, name or host (as accepted by `audb select`) to the host the server knows the device by
+fn resolve_device_host(identifier: &str) -> Result<String> {
+    use audb_core::features::config::device_store::DeviceStore;
+    use audb_core::tools::types::DeviceIdentifier;
+
+    let parsed = DeviceIdentifier::parse(identifier);
+    match DeviceStore::find(&parsed) {
+        Ok(device) => Ok(device.host),
+        // Names may also be hostnames configured as the device's host
+        Err(e) => match DeviceStore::find(&DeviceIdentifier::Host(identifier.to_string())) {
+            Ok(device) => Ok(device.host),
+            // Unknown IPs are passed through so the server can report them
+            Err(_) if matches!(parsed, DeviceIdentifier::Host(_)) => Ok(identifier.to_string()),
+            Err(_) => Err(e),
+        },
+    }
+}
+
+/// Execute device Check command - immediate health probe
+async fn execute_check_command(identifier: Option<String>) -> Result<()> {
+    let device = get_device(identifier)?;
+
+    execute_command(Command::HealthCheck { device }).await
+}
+
+/// Execute device history command
+async fn execute_history_command(identifier: Option<String>) -> Result<()> {
+    let device = get_device(identifier)?;
+
+    execute_command(Command::DeviceHistory { device }).await
+}
+
+/// Print connection state transitions as a timeline
+fn print_history(transitions: &[audb_protocol::StateTransition]) {
+    if transitions.is_empty() {
+        println!("No connection state changes recorded since the server started");
+        return;
+    }
+
+    for transition in transitions {
+        let time = chrono::DateTime::from_timestamp(transition.timestamp as i64, 0)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "?".to_string());
+        let change = format!("{} -> {}", transition.from, transition.to);
+        println!("{}  {:<28} {}", time, change, transition.reason);
+    }
+}
+
+/// Parse the `--backend` of `package install`
+fn parse_install_backend(name: &str) -> Result<audb_protocol::InstallBackend> {
+    use audb_protocol::InstallBackend;
+
+    match name {
+        "apm" => Ok(InstallBackend::Apm),
+        "pkcon" => Ok(InstallBackend::Pkcon),
+        "rpm" => Ok(InstallBackend::Rpm),
+        _ => Err(anyhow!("Unknown install backend: {}. Use apm, pkcon or rpm", name)),
     }
 }
 
 /// Execute Install command
-async fn execute_install_command(device_override: Option<String>, rpm_path: String) -> Result<()> {
+async fn execute_install_command(
+    device_override: Option<String>,
+    rpm_path: String,
+    check_deps: bool,
+    backend: audb_protocol::InstallBackend,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
     // Read RPM file
@@ -603,17 +1699,122 @@ async fn execute_install_command(device_override: Option<String>, rpm_path: Stri
         device,
         rpm_path,
         rpm_data,
+        check_deps,
+        backend,
     }).await
 }
 
+/// Install the RPMs a project build left in RPMS/ for the device's architecture
+async fn execute_install_project_command(
+    device_override: Option<String>,
+    project: PathBuf,
+    check_deps: bool,
+    backend: audb_protocol::InstallBackend,
+) -> Result<()> {
+    use audb_core::features::config::device_store::DeviceStore;
+    use audb_core::tools::types::DeviceIdentifier;
+
+    let device = get_device(device_override)?;
+    let platform = DeviceStore::find(&DeviceIdentifier::Host(device.clone()))
+        .map(|d| d.platform)
+        .map_err(|_| anyhow!("Unknown platform for {}; add it with 'audb device add' or pass the RPM path", device))?;
+    let arch = platform.rpm_arch();
+
+    let rpms = find_project_rpms(&project.join("RPMS"), arch)?;
+    if rpms.is_empty() {
+        return Err(anyhow!(
+            "No {} RPMs for {} ({}) found in {}",
+            arch,
+            device,
+            platform,
+            project.join("RPMS").join(arch).display()
+        ));
+    }
+
+    for rpm in rpms {
+        println!("Installing {}", rpm.display());
+        execute_install_command(Some(device.clone()), rpm.to_string_lossy().to_string(), check_deps, backend).await?;
+    }
+    Ok(())
+}
+
+/// RPMs for `arch` in an RPMS/ directory, in RPMS/<arch>/ or named *.<arch>.rpm
+///
+/// Debug info packages are skipped.
+fn find_project_rpms(rpms_dir: &std::path::Path, arch: &str) -> Result<Vec<PathBuf>> {
+    if !rpms_dir.is_dir() {
+        return Err(anyhow!("No RPMS directory in the project ({} does not exist)", rpms_dir.display()));
+    }
+
+    let suffix = format!(".{}.rpm", arch);
+    let mut rpms = vec![];
+
+    for dir in [rpms_dir.join(arch), rpms_dir.to_path_buf()] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_file()
+                && name.ends_with(&suffix)
+                && !name.contains("-debuginfo-")
+                && !name.contains("-debugsource-")
+            {
+                rpms.push(path);
+            }
+        }
+    }
+
+    rpms.sort();
+    Ok(rpms)
+}
+
 /// Execute Uninstall command
-async fn execute_uninstall_command(device_override: Option<String>, package_name: String) -> Result<()> {
+async fn execute_uninstall_command(device_override: Option<String>, package_names: Vec<String>) -> Result<()> {
     let device = get_device(device_override)?;
 
-    execute_command(Command::Uninstall {
-        device,
-        package_name,
-    }).await
+    if package_names.iter().any(|name| name.trim().is_empty()) {
+        return Err(anyhow!("Package name cannot be empty"));
+    }
+
+    match <[String; 1]>::try_from(package_names) {
+        Ok([package_name]) => execute_command(Command::Uninstall {
+            device,
+            package_name,
+        }).await,
+        Err(package_names) => match request_lines(Command::UninstallMany { device, package_names }).await {
+            Ok(lines) => {
+                for line in &lines {
+                    println!("{}", colour_status_mark(line));
+                }
+                Ok(())
+            }
+            // The message is the per-package lines followed by a summary
+            Err(e) => match e.downcast::<ServerError>() {
+                Ok(ServerError { kind, message }) => {
+                    let mut lines: Vec<&str> = message.lines().collect();
+                    let summary = lines.pop().unwrap_or_default().to_string();
+                    for line in lines {
+                        println!("{}", colour_status_mark(line));
+                    }
+                    Err(ServerError { kind, message: summary }.into())
+                }
+                Err(e) => Err(e),
+            },
+        },
+    }
+}
+
+/// Colour the leading `✓`/`✗` of a per-item result line from the server
+fn colour_status_mark(line: &str) -> String {
+    if let Some(rest) = line.strip_prefix("✓ ") {
+        format!("\x1b[32m✓\x1b[0m {}", rest)
+    } else if let Some(rest) = line.strip_prefix("✗ ") {
+        format!("\x1b[31m✗\x1b[0m {}", rest)
+    } else {
+        line.to_string()
+    }
 }
 
 /// Execute Packages command
@@ -627,105 +1828,468 @@ async fn execute_packages_command(device_override: Option<String>, filter: Optio
 }
 
 /// Execute Push command
-async fn execute_push_command(device_override: Option<String>, local: String, remote: String) -> Result<()> {
+///
+/// The data comes from `content`, stdin (local path `-`) or the local file.
+async fn execute_push_command(
+    device_override: Option<String>,
+    local: Option<String>,
+    content: Option<String>,
+    remote: String,
+    mkdirs: bool,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
-    // Read local file
-    let data = std::fs::read(&local)
-        .map_err(|e| anyhow!("Failed to read local file {}: {}", local, e))?;
+    let (local, data) = match (content, local) {
+        (Some(content), _) => ("<content>".to_string(), content.into_bytes()),
+        (None, Some(local)) if local == "-" => {
+            let mut data = Vec::new();
+            std::io::Read::read_to_end(&mut std::io::stdin(), &mut data)
+                .map_err(|e| anyhow!("Failed to read stdin: {}", e))?;
+            ("<stdin>".to_string(), data)
+        }
+        (None, Some(local)) => {
+            let data = std::fs::read(&local)
+                .map_err(|e| anyhow!("Failed to read local file {}: {}", local, e))?;
+            (local, data)
+        }
+        (None, None) => return Err(anyhow!("Nothing to push: give a local file, '-' or --content")),
+    };
 
     execute_command(Command::Push {
         device,
         local_path: local,
         remote_path: remote,
         data,
+        mkdirs,
     }).await
 }
 
+/// Environment variable naming the default directory for screenshots and pulled files
+const OUTPUT_DIR_ENV: &str = "AUDB_OUTPUT_DIR";
+
+/// Directory for generated files: `--output-dir`, then `AUDB_OUTPUT_DIR`
+///
+/// The directory is created if it does not exist yet.
+fn resolve_output_dir(output_dir: Option<PathBuf>) -> Result<Option<PathBuf>> {
+    let dir = output_dir.or_else(|| {
+        std::env::var_os(OUTPUT_DIR_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    });
+
+    if let Some(dir) = &dir {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("Failed to create output directory {}: {}", dir.display(), e))?;
+    }
+    Ok(dir)
+}
+
+/// Local path for a generated file; an explicit `--output` wins over the output directory
+fn output_path(output: Option<String>, output_dir: Option<PathBuf>, default_name: String) -> Result<PathBuf> {
+    if let Some(output) = output {
+        return Ok(PathBuf::from(output));
+    }
+
+    Ok(match resolve_output_dir(output_dir)? {
+        Some(dir) => dir.join(default_name),
+        None => PathBuf::from(default_name),
+    })
+}
+
 /// Execute Pull command
-async fn execute_pull_command(device_override: Option<String>, remote: String, output: Option<String>) -> Result<()> {
+async fn execute_pull_command(
+    device_override: Option<String>,
+    remote: String,
+    output: Option<String>,
+    output_dir: Option<PathBuf>,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
+    if remote.contains(['*', '?', '[']) {
+        return execute_pull_glob(device, remote, output, output_dir).await;
+    }
+
+    let data = pull_file(device, remote.clone(), false).await?;
+
+    // Determine output filename
+    let default_name = std::path::Path::new(&remote)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| "pulled_file".to_string());
+    let filename = output_path(output, output_dir, default_name)?;
+
+    // Write to file
+    std::fs::write(&filename, &data)?;
+    println!("{}: {} bytes pulled to {}", remote, data.len(), filename.display());
+    Ok(())
+}
+
+/// Pull every regular file matching a wildcard pattern (expanded on the device) into a directory
+async fn execute_pull_glob(
+    device: String,
+    pattern: String,
+    output: Option<String>,
+    output_dir: Option<PathBuf>,
+) -> Result<()> {
+    // For wildcards --output names the destination directory itself
+    let dest_dir = match output {
+        Some(output) => PathBuf::from(output),
+        None => resolve_output_dir(output_dir)?.unwrap_or_else(|| PathBuf::from(".")),
+    };
+    if dest_dir.exists() && !dest_dir.is_dir() {
+        return Err(anyhow!(
+            "{} is not a directory; pulling a wildcard pattern needs a destination directory",
+            dest_dir.display()
+        ));
+    }
+
+    // Let the device shell expand the pattern; unmatched patterns stay literal and fail -f
+    let list_command = format!("for f in {}; do [ -f \"$f\" ] && echo \"$f\"; done; true", pattern);
+    let listing = request_lines(Command::Shell {
+        device: device.clone(),
+        root: false,
+        pty: false,
+        command: list_command,
+        args: vec![],
+        run_as: None,
+        time: false,
+        script: None,
+        login: false,
+        head: None,
+        tail: None,
+    }).await?;
+    let matches: Vec<String> = listing
+        .join("\n")
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+
+    if matches.is_empty() {
+        return Err(anyhow!("No files on the device match {}", pattern));
+    }
+
+    // Files from different directories would overwrite each other in the destination
+    let mut names = std::collections::HashSet::new();
+    for remote in &matches {
+        let name = remote.rsplit('/').next().unwrap_or(remote);
+        if !names.insert(name) {
+            return Err(anyhow!("Several matches are named {}; pull them separately", name));
+        }
+    }
+
+    std::fs::create_dir_all(&dest_dir)?;
+
+    let mut failed = 0;
+    for remote in &matches {
+        let name = remote.rsplit('/').next().unwrap_or(remote);
+        let local = dest_dir.join(name);
+        let result = pull_file(device.clone(), remote.clone(), false)
+            .await
+            .and_then(|data| {
+                std::fs::write(&local, &data)?;
+                Ok(data.len())
+            });
+
+        match result {
+            Ok(size) => println!("\x1b[32m✓\x1b[0m {}: {} bytes pulled to {}", remote, size, local.display()),
+            Err(e) => {
+                failed += 1;
+                println!("\x1b[31m✗\x1b[0m {}: {}", remote, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} of {} files failed to pull", failed, matches.len()));
+    }
+    println!("{} files pulled to {}", matches.len(), dest_dir.display());
+    Ok(())
+}
+
+/// Pull a remote directory as a gzip-compressed tarball and extract it into a local directory
+///
+/// The directory itself is recreated inside the destination, like `cp -r`.
+async fn execute_pull_tar(
+    device_override: Option<String>,
+    remote: String,
+    local: Option<String>,
+    output_dir: Option<PathBuf>,
+) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    let dest_dir = match local {
+        Some(local) => PathBuf::from(local),
+        None => resolve_output_dir(output_dir)?.unwrap_or_else(|| PathBuf::from(".")),
+    };
+    if dest_dir.exists() && !dest_dir.is_dir() {
+        return Err(anyhow!("{} is not a directory", dest_dir.display()));
+    }
+
+    let data = pull_file(device, remote.clone(), true).await?;
+    std::fs::create_dir_all(&dest_dir)?;
+
+    // unpack_in refuses entries that would land outside the destination
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(data.as_slice()));
+    let mut files = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry.map_err(|e| anyhow!("Corrupt tarball from the device: {}", e))?;
+        let is_file = entry.header().entry_type().is_file();
+        if entry.unpack_in(&dest_dir)? && is_file {
+            files += 1;
+        }
+    }
+
+    println!(
+        "{}: {} files ({} bytes compressed) pulled to {}",
+        remote,
+        files,
+        data.len(),
+        dest_dir.display()
+    );
+    Ok(())
+}
+
+/// Pull a single remote file (or with `tar`, a directory as a .tar.gz) through the server
+async fn pull_file(device: String, remote_path: String, tar: bool) -> Result<Vec<u8>> {
     // Ensure server is running
     ensure_server_running().await?;
 
-    // Connect to server
-    let mut stream = connect_to_server().await?;
-
     // Send pull command
     let request = Request {
         id: generate_request_id(),
         command: Command::Pull {
             device,
-            remote_path: remote.clone(),
+            remote_path,
+            tar,
         },
     };
 
-    send_message(&mut stream, &request).await?;
-
-    // Receive response
-    let response: Response = recv_message(&mut stream).await?;
+    let response = send_request(request).await?;
 
     // Handle pull response specially (binary data)
     match response.result {
-        CommandResult::Success { output: CommandOutput::Binary(data) } => {
-            // Determine output filename
-            let filename = output.unwrap_or_else(|| {
-                std::path::Path::new(&remote)
-                    .file_name()
-                    .map(|f| f.to_string_lossy().to_string())
-                    .unwrap_or_else(|| "pulled_file".to_string())
-            });
-
-            // Write to file
-            std::fs::write(&filename, &data)?;
-            println!("{}: {} bytes pulled to {}", remote, data.len(), filename);
-            Ok(())
+        CommandResult::Success { output: CommandOutput::Binary(data) } => Ok(data),
+        CommandResult::Success { output } => {
+            Err(unexpected_output("pull", &output))
         }
-        CommandResult::Success { output: _ } => {
-            Err(anyhow!("Unexpected output format for pull"))
+        CommandResult::Error { message, kind } => {
+            Err(ServerError { kind, message }.into())
+        }
+    }
+}
+
+/// Send a command through the server and return its output lines instead of printing them
+async fn request_lines(command: Command) -> Result<Vec<String>> {
+    ensure_server_running().await?;
+
+    let name = command.name();
+    let request = Request {
+        id: generate_request_id(),
+        command,
+    };
+    let response = send_request(request).await?;
+
+    match response.result {
+        CommandResult::Success { output: CommandOutput::Lines(lines) } => Ok(lines),
+        CommandResult::Success { output } => {
+            Err(unexpected_output(name, &output))
         }
         CommandResult::Error { message, kind } => {
-            Err(anyhow!("{:?}: {}", kind, message))
+            Err(ServerError { kind, message }.into())
         }
     }
 }
 
+/// Execute Cat command
+async fn execute_cat_command(device_override: Option<String>, remote: String, as_root: bool) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    execute_command(Command::Cat {
+        device,
+        remote_path: remote,
+        as_root,
+    }).await
+}
+
 /// Execute Info command
-async fn execute_info_command(device_override: Option<String>, category: Option<String>) -> Result<()> {
+async fn execute_info_command(
+    device_override: Option<String>,
+    category: Option<String>,
+    field: Option<String>,
+    json: bool,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
     // Ensure server is running
     ensure_server_running().await?;
 
-    // Connect to server
-    let mut stream = connect_to_server().await?;
-
     // Send info command
     let request = Request {
         id: generate_request_id(),
         command: Command::Info {
-            device,
+            device: device.clone(),
             category: category.clone(),
         },
     };
 
-    send_message(&mut stream, &request).await?;
-
-    // Receive response
-    let response: Response = recv_message(&mut stream).await?;
+    let response = send_request(request).await?;
 
     // Handle response
     match response.result {
-        CommandResult::Success { output: CommandOutput::DeviceInfo(info) } => {
-            print_device_info(&info, category.as_deref());
+        CommandResult::Success { output: CommandOutput::DeviceInfo(info) } => match field {
+            Some(field) => print_info_field(&info, &field),
+            None if json => {
+                println!("{}", serde_json::to_string_pretty(&info)?);
+                Ok(())
+            }
+            None => {
+                print_device_info(&info, category.as_deref());
+                Ok(())
+            }
+        },
+        CommandResult::Success { output } => {
+            Err(unexpected_output("info", &output))
+        }
+        CommandResult::Error { message, kind: ErrorKind::DeviceDisconnected } => {
+            // Fall back to the last info fetched while the device was online
+            use audb_core::features::config::info_cache::InfoCache;
+            match InfoCache::load(&device).ok().flatten() {
+                Some((info, fetched_at)) => {
+                    let age = fetched_at.elapsed().unwrap_or_default().as_secs();
+                    if let Some(field) = field {
+                        // Keep stdout to the bare value
+                        eprintln!("\x1b[33m(cached, device offline - fetched {} ago)\x1b[0m", format_age(age));
+                        return print_info_field(&info, &field);
+                    }
+                    if json {
+                        eprintln!("\x1b[33m(cached, device offline - fetched {} ago)\x1b[0m", format_age(age));
+                        println!("{}", serde_json::to_string_pretty(&info)?);
+                        return Ok(());
+                    }
+                    println!("\x1b[33m(cached, device offline - fetched {} ago)\x1b[0m\n", format_age(age));
+                    print_device_info(&info, category.as_deref());
+                    Ok(())
+                }
+                None => Err(ServerError { kind: ErrorKind::DeviceDisconnected, message }.into()),
+            }
+        }
+        CommandResult::Error { message, kind } => {
+            Err(ServerError { kind, message }.into())
+        }
+    }
+}
+
+/// Execute metrics command
+async fn execute_metrics_command(json: bool) -> Result<()> {
+    ensure_server_running().await?;
+
+    let request = Request {
+        id: generate_request_id(),
+        command: Command::Metrics,
+    };
+    let response = send_request(request).await?;
+
+    match response.result {
+        CommandResult::Success { output: CommandOutput::Metrics(metrics) } => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&metrics)?);
+            } else {
+                print_metrics(&metrics);
+            }
             Ok(())
         }
-        CommandResult::Success { output: _ } => {
-            Err(anyhow!("Unexpected output format for info"))
+        CommandResult::Success { output } => {
+            Err(unexpected_output("metrics", &output))
         }
         CommandResult::Error { message, kind } => {
-            Err(anyhow!("{:?}: {}", kind, message))
+            Err(ServerError { kind, message }.into())
+        }
+    }
+}
+
+/// Print server metrics as a table
+fn print_metrics(metrics: &audb_protocol::ServerMetrics) {
+    let error_rate = |count: u64, failed: u64| {
+        if count == 0 { 0.0 } else { failed as f64 * 100.0 / count as f64 }
+    };
+
+    println!("Server Metrics (uptime {}):", format_age(metrics.uptime_secs));
+    println!("  Commands: {} total, {} failed ({:.1}% errors)",
+        metrics.total_commands,
+        metrics.failed_commands,
+        error_rate(metrics.total_commands, metrics.failed_commands)
+    );
+
+    if metrics.commands.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("\x1b[1m  {:<16} {:>8} {:>8} {:>8}\x1b[0m", "Command", "Count", "Failed", "Errors");
+    for command in &metrics.commands {
+        println!("  {:<16} {:>8} {:>8} {:>7.1}%",
+            command.name,
+            command.count,
+            command.failed,
+            error_rate(command.count, command.failed)
+        );
+    }
+}
+
+/// Format an age in seconds as a short human-readable string
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Print one `DeviceInfo` field by its JSON name, without label or units
+///
+/// Lists print one element per line; a missing optional value prints nothing.
+fn print_info_field(info: &audb_protocol::DeviceInfo, field: &str) -> Result<()> {
+    let serde_json::Value::Object(fields) = serde_json::to_value(info)? else {
+        return Err(anyhow!("Device info is not an object"));
+    };
+
+    let Some(value) = fields.get(field) else {
+        let valid: Vec<&str> = fields.keys().map(String::as_str).collect();
+        return Err(anyhow!("Unknown info field '{}'. Valid fields: {}", field, valid.join(", ")));
+    };
+
+    fn raw(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    match value {
+        serde_json::Value::Null => {}
+        serde_json::Value::Array(items) => {
+            for item in items {
+                println!("{}", raw(item));
+            }
+        }
+        other => println!("{}", raw(other)),
+    }
+    Ok(())
+}
+
+/// Kernel, OS build and hardware revision lines of the device category
+///
+/// Values the device did not report are left out.
+fn print_build_info(info: &audb_protocol::DeviceInfo) {
+    for (label, value) in [
+        ("Kernel", &info.kernel_version),
+        ("Build", &info.build_id),
+        ("Hardware Revision", &info.hardware_revision),
+    ] {
+        if !value.is_empty() {
+            println!("  {}: {}", label, value);
         }
     }
 }
@@ -737,6 +2301,7 @@ fn print_device_info(info: &audb_protocol::DeviceInfo, category: Option<&str>) {
             println!("Device:");
             println!("  Model: {}", info.device_model);
             println!("  OS Version: {}", info.os_version);
+            print_build_info(info);
             println!("  Screen: {}", info.screen_resolution);
         }
         Some("cpu") => {
@@ -762,6 +2327,27 @@ fn print_device_info(info: &audb_protocol::DeviceInfo, category: Option<&str>) {
             println!("Storage:");
             println!("  Internal Total: {} MB ({:.1} GB)", info.internal_storage_total_mb, info.internal_storage_total_mb as f64 / 1024.0);
             println!("  Internal Free: {} MB ({:.1} GB)", info.internal_storage_free_mb, info.internal_storage_free_mb as f64 / 1024.0);
+            if !info.storage_mounts.is_empty() {
+                println!("  Mounts:");
+                for mount in &info.storage_mounts {
+                    println!(
+                        "    {:<24} {:.1} GB / {:.1} GB free",
+                        mount.mount,
+                        mount.total_mb as f64 / 1024.0,
+                        mount.free_mb as f64 / 1024.0
+                    );
+                }
+            }
+        }
+        Some("thermal") | Some("temp") => {
+            println!("Thermal:");
+            println!("  CPU: {}", format_temp(info.cpu_temp_c));
+            println!("  Battery: {}", format_temp(info.battery_temp_c));
+        }
+        Some("system") | Some("sys") => {
+            println!("System:");
+            println!("  Uptime: {}", format_uptime(info.uptime_secs));
+            println!("  Load Average: {:.2} {:.2} {:.2}", info.load_avg[0], info.load_avg[1], info.load_avg[2]);
         }
         Some("features") | Some("hw") => {
             println!("Features:");
@@ -774,11 +2360,21 @@ fn print_device_info(info: &audb_protocol::DeviceInfo, category: Option<&str>) {
             println!("  Main: {:.1} MP", info.main_camera_mp);
             println!("  Frontal: {:.1} MP", info.frontal_camera_mp);
         }
+        Some("peripherals") | Some("usb") | Some("bt") => {
+            print_peripherals(info);
+        }
+        Some("devmode") | Some("ssh") => {
+            print_devmode(info);
+        }
+        Some("time") | Some("clock") => {
+            print_clock(info);
+        }
         _ => {
             // Show all info (default)
             println!("Device:");
             println!("  Model: {}", info.device_model);
             println!("  OS Version: {}", info.os_version);
+            print_build_info(info);
             println!("  Screen: {}", info.screen_resolution);
             println!();
             println!("CPU:");
@@ -800,6 +2396,14 @@ fn print_device_info(info: &audb_protocol::DeviceInfo, category: Option<&str>) {
             println!("  Level: {}%", info.battery_level);
             println!("  State: {}", info.battery_state);
             println!();
+            println!("Thermal:");
+            println!("  CPU: {}", format_temp(info.cpu_temp_c));
+            println!("  Battery: {}", format_temp(info.battery_temp_c));
+            println!();
+            println!("System:");
+            println!("  Uptime: {}", format_uptime(info.uptime_secs));
+            println!("  Load Average: {:.2} {:.2} {:.2}", info.load_avg[0], info.load_avg[1], info.load_avg[2]);
+            println!();
             println!("Features:");
             println!("  NFC: {}", if info.has_nfc { "Yes" } else { "No" });
             println!("  Bluetooth: {}", if info.has_bluetooth { "Yes" } else { "No" });
@@ -809,13 +2413,159 @@ fn print_device_info(info: &audb_protocol::DeviceInfo, category: Option<&str>) {
             println!("Cameras:");
             println!("  Main: {:.1} MP", info.main_camera_mp);
             println!("  Frontal: {:.1} MP", info.frontal_camera_mp);
+            println!();
+            print_peripherals(info);
+            println!();
+            print_devmode(info);
+            println!();
+            print_clock(info);
         }
     }
 }
 
+/// Print the device clock, timezone and skew from the host clock
+fn print_clock(info: &audb_protocol::DeviceInfo) {
+    println!("Clock:");
+    if info.device_time.is_empty() {
+        println!("  (not reported)");
+        return;
+    }
+    println!("  Device Time: {}", info.device_time);
+    println!("  Timezone: {}", info.timezone);
+
+    let Some(offset_ms) = info.clock_offset_ms else {
+        println!("  Skew: unknown");
+        return;
+    };
+    let precision = info.clock_rtt_ms.map(|rtt| format!(" (±{:.2}s)", rtt as f64 / 2000.0)).unwrap_or_default();
+    let seconds = offset_ms.abs() as f64 / 1000.0;
+    let direction = if offset_ms >= 0 { "ahead of" } else { "behind" };
+    println!("  Skew: {:.2}s {} this machine{}", seconds, direction, precision);
+    if offset_ms.abs() >= 1000 {
+        let adjust = if offset_ms >= 0 { "subtract it from" } else { "add it to" };
+        println!("  Note: {} device log timestamps to compare them with host logs", adjust);
+    }
+}
+
+/// Print developer mode and SSH daemon status
+fn print_devmode(info: &audb_protocol::DeviceInfo) {
+    let state = |value: Option<bool>, yes: &str, no: &str| match value {
+        Some(true) => yes.to_string(),
+        Some(false) => no.to_string(),
+        None => "unknown".to_string(),
+    };
+
+    println!("Developer Mode:");
+    println!("  Enabled: {}", state(info.developer_mode, "Yes", "No"));
+    println!("  SSH Service: {}, {}",
+        state(info.ssh_enabled, "enabled", "disabled"),
+        state(info.ssh_running, "running", "stopped"));
+    match info.ssh_port {
+        Some(port) => println!("  SSH Port: {}", port),
+        None => println!("  SSH Port: unknown"),
+    }
+    if info.ssh_enabled == Some(false) {
+        println!("  Note: SSH will not start after a reboot; enable it in Settings > Developer tools");
+    }
+}
+
+/// Print attached USB devices and paired/connected Bluetooth devices
+fn print_peripherals(info: &audb_protocol::DeviceInfo) {
+    println!("USB:");
+    if info.usb_devices.is_empty() {
+        println!("  (none)");
+    }
+    for device in &info.usb_devices {
+        println!("  {}", device);
+    }
+    println!();
+    println!("Bluetooth:");
+    if !info.has_bluetooth {
+        println!("  (not supported)");
+    } else if info.bluetooth_devices.is_empty() {
+        println!("  (no paired devices)");
+    }
+    for device in &info.bluetooth_devices {
+        let state = if device.connected { "connected" } else { "paired" };
+        println!("  {} {} ({})", device.address, device.name, state);
+    }
+}
+
+/// Format an uptime in seconds as days/hours/minutes
+fn format_uptime(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let minutes = (secs % 3600) / 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Format an optional temperature reading
+fn format_temp(temp_c: Option<f64>) -> String {
+    match temp_c {
+        Some(t) => format!("{:.1} °C", t),
+        None => "unavailable".to_string(),
+    }
+}
+
+/// Resolve the evdev device for input commands (`--fast` means auto-detect)
+fn fast_event(event: Option<String>, fast: bool) -> Option<String> {
+    if fast {
+        Some("auto".to_string())
+    } else {
+        event
+    }
+}
+
+/// Where `audb tap` taps
+enum TapTarget {
+    Point(u16, u16),
+    /// Center of the on-screen element with this name
+    Label(String),
+}
+
 /// Execute Tap command
-async fn execute_tap_command(device_override: Option<String>, x: u16, y: u16, event: Option<String>, duration: Option<u32>) -> Result<()> {
+async fn execute_tap_command(
+    device_override: Option<String>,
+    target: TapTarget,
+    event: Option<String>,
+    duration: Option<u32>,
+    orientation: Option<String>,
+    clamp: bool,
+) -> Result<()> {
     let device = get_device(device_override)?;
+    let orientation = parse_orientation(orientation.as_deref())?;
+
+    let (x, y) = match target {
+        TapTarget::Point(x, y) => (x, y),
+        TapTarget::Label(label) => {
+            let elements = request_elements(Command::FindElement {
+                device: device.clone(),
+                query: Some(label.clone()),
+                app: None,
+            }).await?;
+            let (element, bounds) = elements
+                .iter()
+                .find_map(|element| element.bounds.map(|bounds| (element, bounds)))
+                .ok_or_else(|| anyhow!("Element '{}' has no on-screen bounds", label))?;
+            if elements.len() > 1 {
+                print_warning(format!("{} elements match '{}'; tapping the first", elements.len(), label));
+            }
+            let (cx, cy) = bounds.center();
+            println!("Tapping {} '{}' at ({}, {})", element.role, element.name, cx, cy);
+            // Accessibility extents are in screen coordinates, as the user sees them
+            (
+                u16::try_from(cx.max(0)).unwrap_or(u16::MAX),
+                u16::try_from(cy.max(0)).unwrap_or(u16::MAX),
+            )
+        }
+    };
 
     execute_command(Command::Tap {
         device,
@@ -823,12 +2573,108 @@ async fn execute_tap_command(device_override: Option<String>, x: u16, y: u16, ev
         y,
         event_device: event,
         duration_ms: duration,
+        orientation,
+        clamp,
     }).await
 }
 
+/// Send a FindElement request and return the elements
+async fn request_elements(command: Command) -> Result<Vec<audb_protocol::UiElement>> {
+    ensure_server_running().await?;
+
+    let name = command.name();
+    let request = Request {
+        id: generate_request_id(),
+        command,
+    };
+    let response = send_request(request).await?;
+
+    match response.result {
+        CommandResult::Success { output: CommandOutput::Elements(elements) } => Ok(elements),
+        CommandResult::Success { output } => {
+            Err(unexpected_output(name, &output))
+        }
+        CommandResult::Error { message, kind } => {
+            Err(ServerError { kind, message }.into())
+        }
+    }
+}
+
+/// Execute UiDump command
+async fn execute_ui_dump_command(
+    device_override: Option<String>,
+    app: Option<String>,
+    json: bool,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let device = get_device(device_override)?;
+    let elements = request_elements(Command::FindElement { device, query: None, app }).await?;
+
+    if let Some(path) = output {
+        std::fs::write(&path, serde_json::to_string_pretty(&elements)?)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        println!("Saved {} elements to {}", elements.len(), path.display());
+        return Ok(());
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&elements)?);
+        return Ok(());
+    }
+
+    if elements.is_empty() {
+        println!("No accessible elements found (apps started before accessibility was enabled may need a restart)");
+        return Ok(());
+    }
+    print_ui_elements(&elements);
+    Ok(())
+}
+
+/// Print accessibility elements as an indented tree
+fn print_ui_elements(elements: &[audb_protocol::UiElement]) {
+    for element in elements {
+        let indent = "  ".repeat(element.depth as usize);
+        let bounds = match element.bounds {
+            Some(b) => format!(" [{},{} {}x{}]", b.x, b.y, b.width, b.height),
+            None => String::new(),
+        };
+        if element.name.is_empty() {
+            println!("{}{}{}", indent, element.role, bounds);
+        } else {
+            println!("{}{} \"{}\"{}", indent, element.role, element.name, bounds);
+        }
+    }
+}
+
+/// Parse an `--orientation` value
+fn parse_orientation(orientation: Option<&str>) -> Result<audb_protocol::ScreenOrientation> {
+    use audb_protocol::ScreenOrientation;
+
+    match orientation.map(str::to_lowercase).as_deref() {
+        None | Some("auto") => Ok(ScreenOrientation::Auto),
+        Some("portrait") => Ok(ScreenOrientation::Portrait),
+        Some("landscape") => Ok(ScreenOrientation::Landscape),
+        Some("inverted-portrait") => Ok(ScreenOrientation::InvertedPortrait),
+        Some("inverted-landscape") => Ok(ScreenOrientation::InvertedLandscape),
+        Some(other) => Err(anyhow!(
+            "Invalid orientation: {}. Use: auto, portrait, landscape, inverted-portrait, or inverted-landscape",
+            other
+        )),
+    }
+}
+
 /// Execute Swipe command
-async fn execute_swipe_command(device_override: Option<String>, args: Vec<String>, event: Option<String>) -> Result<()> {
+async fn execute_swipe_command(
+    device_override: Option<String>,
+    args: Vec<String>,
+    event: Option<String>,
+    profile: Option<String>,
+    duration: Option<u32>,
+    orientation: Option<String>,
+    clamp: bool,
+) -> Result<()> {
     let device = get_device(device_override)?;
+    let orientation = parse_orientation(orientation.as_deref())?;
 
     // Parse swipe arguments
     let mode = if args.len() == 1 {
@@ -852,124 +2698,747 @@ async fn execute_swipe_command(device_override: Option<String>, args: Vec<String
         return Err(anyhow!("Invalid swipe arguments. Use: <direction> OR <x1> <y1> <x2> <y2>"));
     };
 
+    let profile = match profile.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("linear") => audb_protocol::SwipeProfile::Linear,
+        Some("ease-in") => audb_protocol::SwipeProfile::EaseIn,
+        Some("ease-out") => audb_protocol::SwipeProfile::EaseOut,
+        Some("fling") => audb_protocol::SwipeProfile::Fling,
+        Some(other) => return Err(anyhow!("Invalid swipe profile: {}. Use: linear, ease-in, ease-out, or fling", other)),
+    };
+
+    if let Some(ms) = duration {
+        if !(10..=10000).contains(&ms) {
+            return Err(anyhow!("Invalid swipe duration: {}ms. Use 10-10000ms", ms));
+        }
+    }
+
     execute_command(Command::Swipe {
         device,
         mode,
         event_device: event,
+        profile,
+        duration_ms: duration,
+        orientation,
+        clamp,
     }).await
 }
 
-/// Execute Key command
-async fn execute_key_command(device_override: Option<String>, key_name: String) -> Result<()> {
-    let device = get_device(device_override)?;
+/// Execute Pinch command
+async fn execute_pinch_command(
+    device_override: Option<String>,
+    direction: String,
+    center: Option<String>,
+    distance: Option<u16>,
+    event: Option<String>,
+) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    let direction = match direction.to_lowercase().as_str() {
+        "in" => audb_protocol::PinchDirection::In,
+        "out" => audb_protocol::PinchDirection::Out,
+        _ => return Err(anyhow!("Invalid pinch direction: {}. Use: in or out", direction)),
+    };
+
+    let center = match center {
+        Some(center) => {
+            let (x, y) = center
+                .split_once(',')
+                .ok_or_else(|| anyhow!("Invalid center: {}. Use: x,y", center))?;
+            let x = x.trim().parse().map_err(|_| anyhow!("Invalid center x coordinate: {}", x))?;
+            let y = y.trim().parse().map_err(|_| anyhow!("Invalid center y coordinate: {}", y))?;
+            Some((x, y))
+        }
+        None => None,
+    };
+
+    execute_command(Command::Pinch {
+        device,
+        direction,
+        center,
+        distance,
+        event_device: event,
+    }).await
+}
+
+/// Execute Key command
+async fn execute_key_command(device_override: Option<String>, key_name: String) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    execute_command(Command::Key {
+        device,
+        key_name,
+    }).await
+}
+
+/// Execute Screenshot command with special binary handling
+async fn execute_screenshot_command(
+    device_override: Option<String>,
+    output: Option<String>,
+    output_dir: Option<PathBuf>,
+    compare: Option<ScreenshotCompare>,
+    display: Option<u32>,
+) -> Result<()> {
+    let device = get_device(device_override)?;
+    let data = capture_screenshot(device, display).await?;
+
+    // Generate output filename
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let filename = output_path(output, output_dir, format!("screenshot_{}.png", timestamp))?;
+
+    // Write to file
+    std::fs::write(&filename, &data)?;
+    match display {
+        Some(display) => println!("Screenshot of display {} saved to: {}", display, filename.display()),
+        None => println!("Screenshot saved to: {}", filename.display()),
+    }
+
+    match compare {
+        Some(compare) => compare_screenshot(&data, &compare),
+        None => Ok(()),
+    }
+}
+
+/// Baseline comparison requested with `screenshot --compare`
+struct ScreenshotCompare {
+    baseline: PathBuf,
+    /// Largest allowed fraction of differing pixels (0.0 - 1.0)
+    threshold: f64,
+    diff: Option<PathBuf>,
+}
+
+/// Per-channel difference below which pixels count as equal (absorbs PNG/dithering noise)
+const PIXEL_TOLERANCE: u8 = 16;
+
+/// Compare a captured PNG against the baseline, failing when too many pixels differ
+fn compare_screenshot(data: &[u8], compare: &ScreenshotCompare) -> Result<()> {
+    if !(0.0..=1.0).contains(&compare.threshold) {
+        return Err(anyhow!("Threshold must be between 0 and 1, got {}", compare.threshold));
+    }
+
+    let actual = image::load_from_memory_with_format(data, image::ImageFormat::Png)
+        .map_err(|e| anyhow!("Failed to decode screenshot: {}", e))?
+        .to_rgba8();
+    let baseline = image::open(&compare.baseline)
+        .map_err(|e| anyhow!("Failed to read baseline {}: {}", compare.baseline.display(), e))?
+        .to_rgba8();
+
+    if actual.dimensions() != baseline.dimensions() {
+        return Err(anyhow!(
+            "Screenshot is {}x{} but baseline is {}x{}",
+            actual.width(),
+            actual.height(),
+            baseline.width(),
+            baseline.height()
+        ));
+    }
+
+    let mut diff_image = compare.diff.as_ref().map(|_| image::RgbaImage::new(actual.width(), actual.height()));
+    let mut differing: u64 = 0;
+
+    for (x, y, expected) in baseline.enumerate_pixels() {
+        let got = actual.get_pixel(x, y);
+        let differs = expected.0.iter().zip(got.0.iter()).any(|(a, b)| a.abs_diff(*b) > PIXEL_TOLERANCE);
+        if differs {
+            differing += 1;
+        }
+
+        if let Some(diff_image) = diff_image.as_mut() {
+            let pixel = if differs {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                // Dimmed grayscale baseline so the red highlights stand out
+                let [r, g, b, _] = expected.0;
+                let gray = ((r as u32 * 30 + g as u32 * 59 + b as u32 * 11) / 100 / 3) as u8;
+                image::Rgba([gray, gray, gray, 255])
+            };
+            diff_image.put_pixel(x, y, pixel);
+        }
+    }
+
+    if let (Some(path), Some(diff_image)) = (&compare.diff, diff_image) {
+        diff_image
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(|e| anyhow!("Failed to write diff image {}: {}", path.display(), e))?;
+        println!("Diff image saved to: {}", path.display());
+    }
+
+    let total = u64::from(actual.width()) * u64::from(actual.height());
+    let fraction = if total == 0 { 0.0 } else { differing as f64 / total as f64 };
+    println!(
+        "Difference: {:.2}% ({} of {} pixels, threshold {:.2}%)",
+        fraction * 100.0,
+        differing,
+        total,
+        compare.threshold * 100.0
+    );
+
+    if fraction > compare.threshold {
+        return Err(anyhow!("Screenshot differs from baseline {}", compare.baseline.display()));
+    }
+
+    println!("\x1b[32m✓\x1b[0m Screenshot matches baseline");
+    Ok(())
+}
+
+/// Execute Screenshot command on every enabled device concurrently
+async fn execute_screenshot_all_command(output_dir: Option<PathBuf>, display: Option<u32>) -> Result<()> {
+    let devices = audb_core::features::config::device_store::DeviceStore::list_enabled()?;
+    if devices.is_empty() {
+        return Err(anyhow!("No enabled devices configured. Use 'audb device add' to add a device."));
+    }
+
+    // Start the server once up front rather than racing from every task
+    ensure_server_running().await?;
+
+    let output_dir = resolve_output_dir(output_dir)?.unwrap_or_default();
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let mut join_set = tokio::task::JoinSet::new();
+
+    for device in devices {
+        let filename = output_dir.join(format!("screenshot_{}_{}.png", device.host, timestamp));
+        join_set.spawn(async move {
+            let result = capture_screenshot(device.host.clone(), display)
+                .await
+                .and_then(|data| std::fs::write(&filename, data).map_err(Into::into))
+                .map(|_| filename);
+            (device.display_name(), result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = join_set.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut failed = 0;
+    for (name, result) in &results {
+        match result {
+            Ok(filename) => println!("\x1b[32m✓\x1b[0m {}: saved to {}", name, filename.display()),
+            Err(e) => {
+                failed += 1;
+                println!("\x1b[31m✗\x1b[0m {}: {}", name, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} of {} screenshots failed", failed, results.len()));
+    }
+    Ok(())
+}
+
+/// Capture a screenshot through the server and return the PNG data
+async fn capture_screenshot(device: String, display: Option<u32>) -> Result<Vec<u8>> {
+    // Ensure server is running
+    ensure_server_running().await?;
+
+    // Send screenshot command
+    let request = Request {
+        id: generate_request_id(),
+        command: Command::Screenshot {
+            device,
+            display,
+        },
+    };
+
+    let response = send_request(request).await?;
+
+    // Handle screenshot response specially
+    match response.result {
+        CommandResult::Success { output: CommandOutput::Binary(data) } => Ok(data),
+        CommandResult::Success { output } => {
+            Err(unexpected_output("screenshot", &output))
+        }
+        CommandResult::Error { message, kind } => {
+            Err(ServerError { kind, message }.into())
+        }
+    }
+}
+
+/// Parts of a bug report, in the order they are collected
+const BUGREPORT_COMPONENTS: [&str; 6] = ["info", "config", "packages", "logs", "dmesg", "screenshot"];
+
+/// Execute Bugreport command
+///
+/// Components that fail are listed in errors.txt inside the archive instead
+/// of aborting the report.
+async fn execute_bugreport_command(
+    device_override: Option<String>,
+    output: Option<String>,
+    output_dir: Option<PathBuf>,
+    only: Vec<String>,
+    skip: Vec<String>,
+    log_lines: usize,
+) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    let selected: Vec<&str> = BUGREPORT_COMPONENTS
+        .into_iter()
+        .filter(|c| only.is_empty() || only.iter().any(|o| o == c))
+        .filter(|c| !skip.iter().any(|s| s == c))
+        .collect();
+    if selected.is_empty() {
+        return Err(anyhow!("Nothing to collect: every component was skipped"));
+    }
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let path = output_path(
+        output,
+        output_dir,
+        format!("bugreport_{}_{}.zip", device.replace([':', '/'], "-"), timestamp),
+    )?;
+
+    let mut files = Vec::new();
+    let mut failures = Vec::new();
+    for component in selected {
+        println!("Collecting {}...", component);
+        match collect_bugreport_component(&device, component, log_lines).await {
+            Ok(file) => files.push(file),
+            Err(e) => {
+                print_warning(format!("Skipping {}: {}", component, e));
+                failures.push(format!("{}: {}", component, e));
+            }
+        }
+    }
+    if !failures.is_empty() {
+        files.push(("errors.txt".to_string(), failures.join("\n").into_bytes()));
+    }
+
+    write_zip(&path, &files)?;
+    println!("Bug report saved to: {} ({} files)", path.display(), files.len());
+    Ok(())
+}
+
+/// Collect one bug report component as an archive entry (name, contents)
+async fn collect_bugreport_component(device: &str, component: &str, log_lines: usize) -> Result<(String, Vec<u8>)> {
+    let device = device.to_string();
+    match component {
+        "info" => {
+            let info = request_device_info(device).await?;
+            Ok(("info.json".to_string(), serde_json::to_vec_pretty(&info)?))
+        }
+        "config" => {
+            use audb_core::features::config::device_store::DeviceStore;
+            use audb_core::tools::types::DeviceIdentifier;
 
-    execute_command(Command::Key {
-        device,
-        key_name,
-    }).await
+            let mut config = DeviceStore::find(&DeviceIdentifier::Host(device))?;
+            if !config.root_password.is_empty() {
+                config.root_password = "<redacted>".to_string();
+            }
+            Ok(("device.json".to_string(), serde_json::to_vec_pretty(&config)?))
+        }
+        "packages" => {
+            let lines = request_lines(Command::Packages { device, filter: None }).await?;
+            Ok(("packages.txt".to_string(), lines.join("\n").into_bytes()))
+        }
+        "logs" | "dmesg" => {
+            // dmesg is the kernel part of the journal
+            let args = audb_protocol::LogsArgs {
+                lines: log_lines,
+                priority: None,
+                unit: None,
+                grep: None,
+                since: None,
+                clear: false,
+                force: false,
+                kernel: component == "dmesg",
+                show_cursor: false,
+                after_cursor: None,
+                wait_for: None,
+                regex: false,
+                since_boot: false,
+                output: None,
+            };
+            let lines = request_lines(Command::Logs { device, args }).await?;
+            Ok((format!("{}.txt", component), lines.join("\n").into_bytes()))
+        }
+        "screenshot" => Ok(("screenshot.png".to_string(), capture_screenshot(device, None).await?)),
+        other => Err(anyhow!("Unknown bug report component: {}", other)),
+    }
 }
 
-/// Execute Screenshot command with special binary handling
-async fn execute_screenshot_command(device_override: Option<String>, output: Option<String>) -> Result<()> {
-    let device = get_device(device_override)?;
+/// Write files into a new deflate-compressed zip archive
+fn write_zip(path: &std::path::Path, files: &[(String, Vec<u8>)]) -> Result<()> {
+    use std::io::Write;
 
-    // Ensure server is running
-    ensure_server_running().await?;
+    let file = std::fs::File::create(path)
+        .map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, data) in files {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(data)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
 
-    // Connect to server
-    let mut stream = connect_to_server().await?;
+/// Fetch device info through the server
+async fn request_device_info(device: String) -> Result<Box<audb_protocol::DeviceInfo>> {
+    ensure_server_running().await?;
 
-    // Send screenshot command
     let request = Request {
         id: generate_request_id(),
-        command: Command::Screenshot {
-            device,
-        },
+        command: Command::Info { device, category: None },
     };
+    let response = send_request(request).await?;
 
-    send_message(&mut stream, &request).await?;
-
-    // Receive response
-    let response: Response = recv_message(&mut stream).await?;
-
-    // Handle screenshot response specially
     match response.result {
-        CommandResult::Success { output: CommandOutput::Binary(data) } => {
-            // Generate output filename
-            let filename = output.unwrap_or_else(|| {
-                let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-                format!("screenshot_{}.png", timestamp)
-            });
-
-            // Write to file
-            std::fs::write(&filename, data)?;
-            println!("Screenshot saved to: {}", filename);
-            Ok(())
-        }
-        CommandResult::Success { output: _ } => {
-            Err(anyhow!("Unexpected output format for screenshot"))
+        CommandResult::Success { output: CommandOutput::DeviceInfo(info) } => Ok(info),
+        CommandResult::Success { output } => {
+            Err(unexpected_output("info", &output))
         }
         CommandResult::Error { message, kind } => {
-            Err(anyhow!("{:?}: {}", kind, message))
+            Err(ServerError { kind, message }.into())
         }
     }
 }
 
 /// Execute Launch command
-async fn execute_launch_command(device_override: Option<String>, app_name: String) -> Result<()> {
+async fn execute_launch_command(
+    device_override: Option<String>,
+    app_name: String,
+    args: Vec<String>,
+    uri: Option<String>,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
     execute_command(Command::Launch {
         device,
         app_name,
+        args,
+        uri,
     }).await
 }
 
 /// Execute Stop command
-async fn execute_stop_command(device_override: Option<String>, app_name: String) -> Result<()> {
+async fn execute_stop_command(
+    device_override: Option<String>,
+    app_name: String,
+    force: bool,
+    timeout_secs: Option<u64>,
+) -> Result<()> {
     let device = get_device(device_override)?;
 
     execute_command(Command::Stop {
         device,
         app_name,
+        force,
+        timeout_secs,
     }).await
 }
 
 /// Execute Logs command
 async fn execute_logs_command(
     device_override: Option<String>,
-    lines: usize,
-    priority: Option<String>,
-    unit: Option<String>,
-    grep: Option<String>,
-    since: Option<String>,
-    clear: bool,
-    force: bool,
-    kernel: bool,
+    args: audb_protocol::LogsArgs,
+    output: LogsOutput,
 ) -> Result<()> {
     let device = get_device(device_override)?;
 
-    let args = audb_protocol::LogsArgs {
-        lines,
-        priority,
-        unit,
-        grep,
-        since,
-        clear,
-        force,
-        kernel,
-    };
+    if args.clear {
+        return execute_command(Command::Logs {
+            device,
+            args,
+        }).await;
+    }
+
+    if output.follow {
+        return follow_logs(device, args, output).await;
+    }
 
-    execute_command(Command::Logs {
+    if args.wait_for.is_some() {
+        return wait_for_log(device, args, output.timeout).await;
+    }
+
+    let lines = request_lines(Command::Logs {
         device,
         args,
-    }).await
+    }).await?;
+
+    if let Some(path) = output.output_file {
+        let text = lines.join("\n");
+        LogFile::create(path.clone(), None)?.write_lines(&text)?;
+        println!("Saved {} lines to {}", text.lines().count(), path.display());
+        return Ok(());
+    }
+
+    if !lines.is_empty() {
+        print_paged(&lines.join("\n"), output.paging);
+    }
+    Ok(())
+}
+
+/// Where and how `audb logs` output goes
+struct LogsOutput {
+    paging: Paging,
+    follow: bool,
+    output_file: Option<PathBuf>,
+    rotate_size: Option<u64>,
+    /// Seconds `--wait-for` waits before giving up
+    timeout: Option<u64>,
+}
+
+/// How often `logs --follow` asks for new entries
+const FOLLOW_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Upper bound on entries fetched per `logs --follow` poll
+const FOLLOW_MAX_LINES: usize = 10000;
+
+/// Poll for new journal entries until Ctrl+C, resuming after the last cursor seen
+async fn follow_logs(device: String, mut args: audb_protocol::LogsArgs, output: LogsOutput) -> Result<()> {
+    let mut file = match output.output_file {
+        Some(path) => {
+            println!("Writing logs to {} (Ctrl+C to stop)", path.display());
+            Some(LogFile::create(path, output.rotate_size)?)
+        }
+        None => None,
+    };
+
+    args.show_cursor = true;
+    CTRL_C_HANDLED.store(true, Ordering::Relaxed);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        let poll = request_lines(Command::Logs {
+            device: device.clone(),
+            args: args.clone(),
+        });
+
+        let lines = tokio::select! {
+            lines = poll => lines?,
+            _ = &mut ctrl_c => return Ok(()),
+        };
+
+        let (text, cursor) = split_cursor(lines);
+        if let Some(cursor) = cursor {
+            // Later polls only ask for what came after the last entry seen
+            args.after_cursor = Some(cursor);
+            args.since = None;
+            args.lines = FOLLOW_MAX_LINES;
+        }
+
+        match file {
+            Some(ref mut file) => file.write_lines(&text)?,
+            None => {
+                for line in text.lines() {
+                    println!("{}", line);
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_millis(FOLLOW_POLL_INTERVAL_MS)) => {}
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
+
+/// Poll new journal entries until one matches `args.wait_for`, printing it
+///
+/// Without `--since` only entries logged after the command starts count, so a
+/// message left over from an earlier run does not end the wait.
+async fn wait_for_log(device: String, mut args: audb_protocol::LogsArgs, timeout: Option<u64>) -> Result<()> {
+    let pattern = args.wait_for.clone().unwrap_or_default();
+    let deadline = timeout.map(|secs| tokio::time::Instant::now() + tokio::time::Duration::from_secs(secs));
+
+    args.show_cursor = true;
+    // The first poll only fetches a starting cursor unless older entries were asked for
+    let mut baseline = args.since.is_none();
+    if baseline {
+        args.lines = 1;
+    }
+
+    let wait = async {
+        loop {
+            let lines = request_lines(Command::Logs {
+                device: device.clone(),
+                args: args.clone(),
+            }).await?;
+
+            let (text, cursor) = split_cursor(lines);
+            if let Some(cursor) = cursor {
+                args.after_cursor = Some(cursor);
+                args.since = None;
+            }
+            args.lines = FOLLOW_MAX_LINES;
+
+            if !baseline {
+                if let Some(line) = text.lines().find(|line| !line.is_empty()) {
+                    println!("{}", line);
+                    return Ok(());
+                }
+            }
+            baseline = false;
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(FOLLOW_POLL_INTERVAL_MS)).await;
+        }
+    };
+
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, wait).await.unwrap_or_else(|_| {
+            Err(anyhow!(
+                "Timed out after {}s waiting for a log entry matching '{}'",
+                timeout.unwrap_or_default(),
+                pattern
+            ))
+        }),
+        None => wait.await,
+    }
+}
+
+/// Split a `--show-cursor` poll into the entries and the trailing journal cursor
+fn split_cursor(lines: Vec<String>) -> (String, Option<String>) {
+    let mut text = lines.join("\n");
+    match text.rfind("-- cursor: ") {
+        Some(start) => {
+            let cursor = text[start + "-- cursor: ".len()..].trim().to_string();
+            text.truncate(start);
+            (text, Some(cursor))
+        }
+        None => (text, None),
+    }
+}
+
+/// Log file written by `audb logs -o`, optionally rolled over by size
+///
+/// On rotation FILE becomes FILE.1, FILE.1 becomes FILE.2 and so on; the
+/// oldest beyond `LOG_ROTATE_KEEP` is dropped.
+struct LogFile {
+    path: PathBuf,
+    file: std::fs::File,
+    written: u64,
+    rotate_size: Option<u64>,
+}
+
+/// Rotated log files kept next to the current one
+const LOG_ROTATE_KEEP: usize = 5;
+
+impl LogFile {
+    fn create(path: PathBuf, rotate_size: Option<u64>) -> Result<Self> {
+        let file = std::fs::File::create(&path)
+            .map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))?;
+        Ok(Self { path, file, written: 0, rotate_size })
+    }
+
+    fn write_lines(&mut self, text: &str) -> Result<()> {
+        use std::io::Write;
+
+        for line in text.lines() {
+            let len = line.len() as u64 + 1;
+            if let Some(limit) = self.rotate_size {
+                if self.written > 0 && self.written + len > limit {
+                    self.rotate()?;
+                }
+            }
+            writeln!(self.file, "{}", line)?;
+            self.written += len;
+        }
+        self.file.flush()?;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let numbered = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+
+        std::fs::remove_file(numbered(LOG_ROTATE_KEEP)).ok();
+        for n in (1..LOG_ROTATE_KEEP).rev() {
+            std::fs::rename(numbered(n), numbered(n + 1)).ok();
+        }
+        std::fs::rename(&self.path, numbered(1))?;
+
+        self.file = std::fs::File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Parse a size like `50M`, `512K`, `1G` or plain bytes
+fn parse_size(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let (number, multiplier) = match text.char_indices().last() {
+        Some((idx, 'K' | 'k')) => (&text[..idx], 1024),
+        Some((idx, 'M' | 'm')) => (&text[..idx], 1024 * 1024),
+        Some((idx, 'G' | 'g')) => (&text[..idx], 1024 * 1024 * 1024),
+        _ => (text, 1),
+    };
+
+    match number.trim().parse::<u64>() {
+        Ok(n) if n > 0 => n.checked_mul(multiplier).ok_or_else(|| anyhow!("Size too large: {}", text)),
+        _ => Err(anyhow!("Invalid size: {}. Use e.g. 50M, 512K, 1G or a byte count", text)),
+    }
+}
+
+/// When to send output through a pager
+#[derive(Clone, Copy)]
+enum Paging {
+    Always,
+    Auto,
+    Never,
+}
+
+/// Print text, through `$PAGER` (default `less -R`) when requested or when it would not fit the terminal
+fn print_paged(text: &str, paging: Paging) {
+    use std::io::{IsTerminal, Write};
+
+    let use_pager = match paging {
+        Paging::Always => true,
+        Paging::Never => false,
+        Paging::Auto => {
+            !REPEATING.load(Ordering::Relaxed)
+                && std::io::stdout().is_terminal()
+                && text.lines().count() >= terminal_height()
+        }
+    };
+
+    if use_pager {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or_else(|| "less -R".to_string());
+        let mut parts = pager.split_whitespace();
+
+        if let Some(program) = parts.next() {
+            let child = std::process::Command::new(program)
+                .args(parts)
+                .stdin(std::process::Stdio::piped())
+                .spawn();
+
+            if let Ok(mut child) = child {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // The user may quit the pager before reading everything
+                    writeln!(stdin, "{}", text).ok();
+                }
+                child.wait().ok();
+                return;
+            }
+        }
+    }
+
+    println!("{}", text);
+}
+
+/// Rows of the terminal attached to stdout (24 if unknown)
+fn terminal_height() -> usize {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    if ok && size.ws_row > 0 {
+        size.ws_row as usize
+    } else {
+        24
+    }
 }
 
 /// Kill the server daemon
 async fn kill_server() -> Result<()> {
     // Get PID file path
-    let pid_file = PathBuf::from(shellexpand::tilde("~/.config/audb/server.pid").to_string());
+    let pid_file = audb_core::features::config::instance::ServerInstance::pid_file_path()?;
 
     if !pid_file.exists() {
         // Check if server is actually running via socket
@@ -1009,6 +3478,107 @@ async fn kill_server() -> Result<()> {
     }
 }
 
+/// Print one doctor check result; returns whether it passed
+fn report_check(name: &str, result: Result<String>, fix: &str) -> bool {
+    match result {
+        Ok(detail) => {
+            println!("\x1b[32m[ OK ]\x1b[0m {}: {}", name, detail);
+            true
+        }
+        Err(e) => {
+            println!("\x1b[31m[FAIL]\x1b[0m {}: {}", name, e);
+            println!("       fix: {}", fix);
+            false
+        }
+    }
+}
+
+/// Execute Doctor command - diagnose local setup
+async fn execute_doctor_command(device_override: Option<String>) -> Result<()> {
+    use audb_core::features::config::device_store::DeviceStore;
+    use audb_core::tools::ssh::SshClient;
+    use audb_core::tools::types::DeviceIdentifier;
+
+    let mut failed = 0;
+
+    let server_binary = find_server_binary().map(|path| path.display().to_string());
+    if !report_check(
+        "Server binary",
+        server_binary,
+        "install audb-server into PATH or next to the audb binary",
+    ) {
+        failed += 1;
+    }
+
+    let config_dir = DeviceStore::config_path().and_then(|path| {
+        let dir = path.parent().map(PathBuf::from).unwrap_or_default();
+        let probe = dir.join(".doctor-probe");
+        std::fs::write(&probe, b"")
+            .and_then(|_| std::fs::remove_file(&probe))
+            .map_err(|e| anyhow!("{} is not writable: {}", dir.display(), e))?;
+        Ok(dir.display().to_string())
+    });
+    if !report_check(
+        "Config directory",
+        config_dir,
+        "check permissions of ~/.config/audb",
+    ) {
+        failed += 1;
+    }
+
+    let devices = DeviceStore::list().and_then(|devices| match devices.len() {
+        0 => Err(anyhow!("no devices configured")),
+        n => Ok(format!("{} configured", n)),
+    });
+    if !report_check("Devices", devices, "run 'audb device add'") {
+        failed += 1;
+    }
+
+    let reachable = get_device(device_override).and_then(|identifier| {
+        let device = DeviceStore::find(&DeviceIdentifier::parse(&identifier))
+            .or_else(|_| DeviceStore::find(&DeviceIdentifier::Host(identifier.clone())))?;
+        let key_path = device.auth_path();
+        if !key_path.exists() {
+            return Err(anyhow!("SSH key {} does not exist", key_path.display()));
+        }
+        if !SshClient::test_device_connection(&device) {
+            let via = device.jump_host.as_ref().map(|jump| format!(" via {}", jump)).unwrap_or_default();
+            return Err(anyhow!("cannot connect to {}:{}{}", device.host, device.port, via));
+        }
+        DeviceStore::set_last_connected(&device.host).ok();
+        Ok(format!("{} ({}:{})", device.display_name(), device.host, device.port))
+    });
+    if !report_check(
+        "Current device",
+        reachable,
+        "check the device is on the network with SSH enabled, or 'audb select' another device",
+    ) {
+        failed += 1;
+    }
+
+    let docker = find_aurora_docker_image(&SdkImageOptions::default())
+        .map(|choice| format!("{} ({})", choice.image, choice.source));
+    if !report_check(
+        "Docker (sign/validate)",
+        docker,
+        "install Docker and the Aurora SDK build-tools image",
+    ) {
+        failed += 1;
+    }
+
+    if is_server_running().await {
+        println!("\x1b[32m[ OK ]\x1b[0m Server: running at {}", socket_path().display());
+    } else {
+        println!("\x1b[33m[INFO]\x1b[0m Server: not running (starts automatically on first command)");
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} check(s) failed", failed));
+    }
+
+    println!("\nAll checks passed");
+    Ok(())
+}
 
 /// Execute Open command
 async fn execute_open_command(device_override: Option<String>, url: String) -> Result<()> {
@@ -1020,6 +3590,30 @@ async fn execute_open_command(device_override: Option<String>, url: String) -> R
     }).await
 }
 
+/// Execute a read-only builtin check
+async fn execute_builtin_command(device_override: Option<String>, builtin: audb_protocol::Builtin) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    execute_command(Command::Builtin {
+        device,
+        builtin,
+    }).await
+}
+
+/// Execute OpenFile command
+async fn execute_open_file_command(device_override: Option<String>, local: String) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    let data = std::fs::read(&local)
+        .map_err(|e| anyhow!("Failed to read local file {}: {}", local, e))?;
+
+    execute_command(Command::OpenFile {
+        device,
+        local_path: local,
+        data,
+    }).await
+}
+
 /// URLs for Aurora OS signing keys
 const KEY_URL: &str = "https://developer.auroraos.ru/content-images/dev-doc/regular_key.pem";
 const CERT_URL: &str = "https://developer.auroraos.ru/content-images/dev-doc/regular_cert.pem";
@@ -1053,45 +3647,53 @@ fn download_file(url: &str, path: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// Signing profile whose keys are downloaded on first use
+const DOWNLOADABLE_PROFILE: &str = "regular";
+
 /// Get signing keys (from custom path, cache, or download)
-fn get_signing_keys(custom_key: Option<String>, custom_cert: Option<String>) -> Result<(PathBuf, PathBuf)> {
-    let key_path = if let Some(key) = custom_key {
-        let path = PathBuf::from(&key);
-        if !path.exists() {
-            return Err(anyhow!("Key file not found: {}", key));
-        }
-        path
-    } else {
-        let cache_dir = get_cache_dir()?;
-        let cached_key = cache_dir.join("regular_key.pem");
-        
-        if !cached_key.exists() {
-            download_file(KEY_URL, &cached_key)?;
-        }
-        cached_key
-    };
-    
-    let cert_path = if let Some(cert) = custom_cert {
-        let path = PathBuf::from(&cert);
-        if !path.exists() {
-            return Err(anyhow!("Certificate file not found: {}", cert));
+///
+/// Keys of `profile` are cached as `<profile>_key.pem`/`<profile>_cert.pem`;
+/// only the public `regular` keys can be downloaded, others must be placed in
+/// the cache directory or given with `--key`/`--cert`.
+fn get_signing_keys(profile: &str, custom_key: Option<String>, custom_cert: Option<String>) -> Result<(PathBuf, PathBuf)> {
+    let signing_file = |custom: Option<String>, kind: &str, label: &str, url: &str| -> Result<PathBuf> {
+        if let Some(custom) = custom {
+            let path = PathBuf::from(shellexpand::tilde(&custom).to_string());
+            if !path.is_file() {
+                return Err(anyhow!("{} file not found: {}", label, custom));
+            }
+            return Ok(path);
         }
-        path
-    } else {
-        let cache_dir = get_cache_dir()?;
-        let cached_cert = cache_dir.join("regular_cert.pem");
-        
-        if !cached_cert.exists() {
-            download_file(CERT_URL, &cached_cert)?;
+
+        let cached = get_cache_dir()?.join(format!("{}_{}.pem", profile, kind));
+        if !cached.exists() {
+            if profile != DOWNLOADABLE_PROFILE {
+                return Err(anyhow!(
+                    "No {} for the '{}' profile: pass --{} or place it at {}",
+                    label.to_lowercase(),
+                    profile,
+                    kind,
+                    cached.display()
+                ));
+            }
+            download_file(url, &cached)?;
         }
-        cached_cert
+        Ok(cached)
     };
-    
+
+    let key_path = signing_file(custom_key, "key", "Key", KEY_URL)?;
+    let cert_path = signing_file(custom_cert, "cert", "Certificate", CERT_URL)?;
     Ok((key_path, cert_path))
 }
 
 /// Execute Sign command (local, uses Docker)
-async fn execute_sign_command(rpm_path: String, custom_key: Option<String>, custom_cert: Option<String>) -> Result<()> {
+async fn execute_sign_command(
+    rpm_path: String,
+    custom_key: Option<String>,
+    custom_cert: Option<String>,
+    profile: String,
+    image: SdkImageOptions,
+) -> Result<()> {
     use std::process::Command as ProcessCommand;
     use std::path::Path;
 
@@ -1117,22 +3719,46 @@ async fn execute_sign_command(rpm_path: String, custom_key: Option<String>, cust
     let project_dir = rpm_path.parent()
         .ok_or_else(|| anyhow!("Invalid RPM path"))?;
 
+    if let Some(info) = warn_about_rpm(&rpm_path) {
+        if info.signed {
+            print_warning(format!("{} is already signed; signing again replaces the signature", rpm_name));
+        }
+    }
+
     // Get signing keys (auto-download if needed)
-    let (key_path, cert_path) = get_signing_keys(custom_key, custom_cert)?;
+    let (key_path, cert_path) = get_signing_keys(&profile, custom_key, custom_cert)?;
+    println!("Signing profile: {} (key {}, cert {})", profile, key_path.display(), cert_path.display());
+
+    // Sign with a local rpmsign-external when available, without Docker
+    if let Some(rpmsign) = which(RPMSIGN_EXTERNAL) {
+        println!("Signing {} with local {}...", rpm_name, rpmsign.display());
+        let output = ProcessCommand::new(&rpmsign)
+            .arg("sign")
+            .arg("--force")
+            .arg(format!("--key={}", key_path.display()))
+            .arg(format!("--cert={}", cert_path.display()))
+            .arg(&rpm_path)
+            .output()
+            .map_err(|e| anyhow!("Failed to run {}: {}", rpmsign.display(), e))?;
+        return sign_result(&rpm_path, &output, "local rpmsign-external");
+    }
+
+    // Find Aurora SDK Docker image
+    let docker_image = select_aurora_docker_image(&image)
+        .map_err(|e| anyhow!("{}\n(or install {} on PATH to sign without Docker)", e, RPMSIGN_EXTERNAL))?;
 
     // Copy keys to project directory temporarily
-    let temp_cert = project_dir.join("regular_cert.pem");
-    let temp_key = project_dir.join("regular_key.pem");
+    let temp_key_name = format!(".audb-{}-{}_key.pem", std::process::id(), profile);
+    let temp_cert_name = format!(".audb-{}-{}_cert.pem", std::process::id(), profile);
+    let temp_cert = project_dir.join(&temp_cert_name);
+    let temp_key = project_dir.join(&temp_key_name);
     
     std::fs::copy(&cert_path, &temp_cert)
         .map_err(|e| anyhow!("Failed to copy certificate: {}", e))?;
     std::fs::copy(&key_path, &temp_key)
         .map_err(|e| anyhow!("Failed to copy key: {}", e))?;
 
-    // Find Aurora SDK Docker image
-    let docker_image = find_aurora_docker_image()?;
-    
-    println!("Signing {} with Aurora SDK...", rpm_name);
+    println!("Signing {} with Aurora SDK (Docker)...", rpm_name);
 
     // Generate unique container name
     let container_name = format!("audb-sign-{}", std::process::id());
@@ -1147,8 +3773,8 @@ async fn execute_sign_command(rpm_path: String, custom_key: Option<String>, cust
             &docker_image,
             "/bin/bash", "-c",
             &format!(
-                "rpmsign-external sign --force --key=/project/regular_key.pem --cert=/project/regular_cert.pem /project/{}",
-                rpm_name
+                "{} sign --force --key=/project/{} --cert=/project/{} /project/{}",
+                RPMSIGN_EXTERNAL, temp_key_name, temp_cert_name, rpm_name
             ),
         ])
         .output();
@@ -1158,28 +3784,71 @@ async fn execute_sign_command(rpm_path: String, custom_key: Option<String>, cust
     let _ = std::fs::remove_file(&temp_key);
 
     match docker_run {
-        Ok(output) => {
-            if output.status.success() {
-                println!("Successfully signed: {}", rpm_path.display());
-                Ok(())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                Err(anyhow!(
-                    "Failed to sign package:\n{}\n{}",
-                    stdout.trim(),
-                    stderr.trim()
-                ))
-            }
-        }
+        Ok(output) => sign_result(&rpm_path, &output, "Docker"),
         Err(e) => Err(anyhow!("Failed to run Docker: {}", e)),
     }
 }
 
+/// Signing tool shipped with the Aurora SDK
+const RPMSIGN_EXTERNAL: &str = "rpmsign-external";
+
+/// Report the outcome of an rpmsign-external run
+fn sign_result(rpm_path: &std::path::Path, output: &std::process::Output, method: &str) -> Result<()> {
+    if output.status.success() {
+        println!("Successfully signed: {} (via {})", rpm_path.display(), method);
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Err(anyhow!(
+            "Failed to sign package:\n{}\n{}",
+            stdout.trim(),
+            stderr.trim()
+        ))
+    }
+}
+
+/// Environment variable naming the Aurora SDK Docker image (like `--docker-image`)
+const SDK_IMAGE_ENV: &str = "AUDB_SDK_IMAGE";
+
+/// Environment variable narrowing the Docker image search (like `--image-match`)
+const SDK_IMAGE_MATCH_ENV: &str = "AUDB_SDK_IMAGE_MATCH";
+
+/// Read a non-empty environment variable
+fn env_option(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+/// Aurora SDK Docker image picked for signing or validation
+struct DockerImageChoice {
+    image: String,
+    /// Where the image came from, for the "Using Docker image" line
+    source: String,
+    /// Other matching images that were not picked
+    others: Vec<String>,
+}
+
 /// Find Aurora SDK Docker image
-fn find_aurora_docker_image() -> Result<String> {
+///
+/// `--docker-image` is used as is. Otherwise local images are searched for
+/// `--image-match` if given. Without either flag, `AUDB_SDK_IMAGE` and then
+/// `AUDB_SDK_IMAGE_MATCH` apply the same way, and the fallback is a search for
+/// Aurora build/sdk/engine images, preferring build-tools.
+fn find_aurora_docker_image(options: &SdkImageOptions) -> Result<DockerImageChoice> {
     use std::process::Command as ProcessCommand;
 
+    if let Some(image) = &options.docker_image {
+        return Ok(DockerImageChoice { image: image.clone(), source: "--docker-image".to_string(), others: Vec::new() });
+    }
+    // An explicit --image-match wins over the environment
+    if options.image_match.is_none() {
+        if let Some(image) = env_option(SDK_IMAGE_ENV) {
+            return Ok(DockerImageChoice { image, source: SDK_IMAGE_ENV.to_string(), others: Vec::new() });
+        }
+    }
+
+    let pattern = options.image_match.clone().or_else(|| env_option(SDK_IMAGE_MATCH_ENV));
+
     // List Docker images and find Aurora SDK
     let output = ProcessCommand::new("docker")
         .args(["images", "--format", "{{.Repository}}:{{.Tag}}"])
@@ -1193,37 +3862,133 @@ fn find_aurora_docker_image() -> Result<String> {
     let images = String::from_utf8_lossy(&output.stdout);
     
     // Look for Aurora SDK image patterns (prioritize build-tools)
-    let mut candidates: Vec<&str> = Vec::new();
+    let mut candidates: Vec<String> = Vec::new();
     
     for line in images.lines() {
         let lower = line.to_lowercase();
-        if lower.contains("aurora") && (lower.contains("build") || lower.contains("sdk") || lower.contains("engine")) {
-            candidates.push(line);
+        let matches = match &pattern {
+            Some(pattern) => lower.contains(&pattern.to_lowercase()),
+            None => lower.contains("aurora") && (lower.contains("build") || lower.contains("sdk") || lower.contains("engine")),
+        };
+        if matches && !candidates.iter().any(|c| c == line) {
+            candidates.push(line.to_string());
         }
     }
 
-    // Prefer build-tools over build-engine
-    for candidate in &candidates {
-        if candidate.to_lowercase().contains("build-tools") {
-            return Ok(candidate.to_string());
-        }
+    // Prefer build-tools over build-engine, then fall back to any Aurora image
+    let Some(index) = candidates
+        .iter()
+        .position(|c| c.to_lowercase().contains("build-tools"))
+        .or_else(|| (!candidates.is_empty()).then_some(0))
+    else {
+        let searched = match &pattern {
+            Some(pattern) => format!("No Docker image matching '{}' found.", pattern),
+            None => "Aurora SDK Docker image not found.".to_string(),
+        };
+        return Err(anyhow!(
+            "{}\n\
+            Please ensure you have the Aurora SDK Docker image installed.\n\
+            You can pull it from the Aurora OS developer portal, or name the image\n\
+            with --docker-image NAME:TAG (or {}).",
+            searched,
+            SDK_IMAGE_ENV
+        ));
+    };
+
+    let image = candidates.remove(index);
+    let source = match pattern {
+        Some(pattern) => format!("matching '{}'", pattern),
+        None => "auto-detected".to_string(),
+    };
+    Ok(DockerImageChoice { image, source, others: candidates })
+}
+
+/// Pick the Aurora SDK Docker image and tell the user which one it is
+fn select_aurora_docker_image(options: &SdkImageOptions) -> Result<String> {
+    let choice = find_aurora_docker_image(options)?;
+    println!("Using Docker image: {} ({})", choice.image, choice.source);
+    if !choice.others.is_empty() {
+        println!(
+            "  Other candidates: {} (choose with --docker-image or --image-match)",
+            choice.others.join(", ")
+        );
     }
-    
-    // Fall back to any Aurora image
-    if let Some(candidate) = candidates.first() {
-        return Ok(candidate.to_string());
+    Ok(choice.image)
+}
+
+
+/// Execute package show command - print RPM metadata read locally
+fn execute_package_show_command(rpm_path: String) -> Result<()> {
+    use audb_core::features::install::header::read_rpm_info;
+
+    let info = read_rpm_info(std::path::Path::new(&rpm_path))?;
+    println!("Name:    {}", info.name);
+    println!("Version: {}-{}", info.version, info.release);
+    println!("Arch:    {}", info.arch);
+    println!("Signed:  {}", if info.signed { "yes" } else { "no" });
+
+    warn_on_arch_mismatch(&info);
+    Ok(())
+}
+
+/// Warn if the package cannot run on the selected device's architecture
+fn warn_on_arch_mismatch(info: &audb_core::features::install::header::RpmInfo) {
+    use audb_core::features::config::{device_store::DeviceStore, state::DeviceState};
+    use audb_core::tools::types::DeviceIdentifier;
+
+    let Some(device) = DeviceState::get_current()
+        .ok()
+        .and_then(|host| DeviceStore::find(&DeviceIdentifier::Host(host)).ok())
+    else {
+        return;
+    };
+
+    let expected = device.platform.rpm_arch();
+    if info.arch != expected && info.arch != "noarch" {
+        print_warning(format!(
+            "{} is built for {}, but {} needs {}",
+            info.name,
+            info.arch,
+            device.display_name(),
+            expected
+        ));
     }
+}
 
-    Err(anyhow!(
-        "Aurora SDK Docker image not found.\n\
-        Please ensure you have the Aurora SDK Docker image installed.\n\
-        You can pull it from the Aurora OS developer portal."
-    ))
+/// Read an RPM's headers before a Docker run and warn about an architecture mismatch
+///
+/// Returns the metadata for further checks; unreadable headers only warn,
+/// leaving the verdict to the SDK tools.
+fn warn_about_rpm(rpm_path: &std::path::Path) -> Option<audb_core::features::install::header::RpmInfo> {
+    let info = match audb_core::features::install::header::read_rpm_info(rpm_path) {
+        Ok(info) => info,
+        Err(e) => {
+            print_warning(format!("Could not read RPM header: {}", e));
+            return None;
+        }
+    };
+
+    warn_on_arch_mismatch(&info);
+    Some(info)
 }
 
+/// Execute Validate command on the device (dry-run install, no Docker)
+async fn execute_validate_on_device_command(device_override: Option<String>, rpm_path: String) -> Result<()> {
+    let device = get_device(device_override)?;
+
+    let rpm_data = std::fs::read(&rpm_path)
+        .map_err(|e| anyhow!("Failed to read RPM file {}: {}", rpm_path, e))?;
+    warn_about_rpm(std::path::Path::new(&rpm_path));
+
+    execute_command(Command::Validate {
+        device,
+        rpm_path,
+        rpm_data,
+    }).await
+}
 
 /// Execute Validate command (local, uses Docker)
-async fn execute_validate_command(rpm_path: String) -> Result<()> {
+async fn execute_validate_command(rpm_path: String, image: SdkImageOptions) -> Result<()> {
     use std::process::Command as ProcessCommand;
     use std::path::Path;
 
@@ -1249,8 +4014,14 @@ async fn execute_validate_command(rpm_path: String) -> Result<()> {
     let project_dir = rpm_path.parent()
         .ok_or_else(|| anyhow!("Invalid RPM path"))?;
 
+    if let Some(info) = warn_about_rpm(&rpm_path) {
+        if !info.signed {
+            print_warning(format!("{} is not signed; run 'audb package sign' before installing", rpm_name));
+        }
+    }
+
     // Find Aurora SDK Docker image
-    let docker_image = find_aurora_docker_image()?;
+    let docker_image = select_aurora_docker_image(&image)?;
     
     println!("Validating {} with Aurora SDK...", rpm_name);
 
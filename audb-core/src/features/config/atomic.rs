@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Distinguishes temp files of concurrent writers within one process
+static WRITE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Replace a file's contents so readers see either the old or the new file
+///
+/// The data goes to a temp file in the same directory, which is then renamed
+/// over `path` (atomic on POSIX). Concurrent `audb` processes therefore never
+/// read a partially written file. Existing permissions are kept.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = path.parent().ok_or_else(|| anyhow!("Invalid path: {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid path: {}", path.display()))?
+        .to_string_lossy();
+    let temp_path = dir.join(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        WRITE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&temp_path).ok();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("audb-atomic-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_contents() {
+        let dir = test_dir("replace");
+        let path = dir.join("current_device");
+
+        write_atomic(&path, "192.168.2.15").unwrap();
+        write_atomic(&path, "10.0.0.1").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "10.0.0.1");
+        // Only the target is left behind, no temp files
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = test_dir("perms");
+        let path = dir.join("devices.json");
+        write_atomic(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, "{\"aurora-devices\": []}").unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,8 +1,10 @@
+use super::atomic::write_atomic;
+use crate::tools::errors::ConfigError;
 use crate::tools::types::{Device, DeviceIdentifier, DevicesConfig};
 use anyhow::{anyhow, Result};
 use directories::BaseDirs;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct DeviceStore;
 
@@ -23,14 +25,81 @@ impl DeviceStore {
         }
 
         let content = fs::read_to_string(&path)?;
-        let config: DevicesConfig = serde_json::from_str(&content)?;
-        Ok(config)
+        Ok(Self::parse(&path, &content)?)
+    }
+
+    /// Parse the devices config, reporting where in the file it is malformed
+    fn parse(path: &Path, content: &str) -> Result<DevicesConfig, ConfigError> {
+        serde_json::from_str(content).map_err(|e| {
+            // serde_json appends " at line L column C", which is reported separately
+            let message = e.to_string();
+            let message = message.split(" at line ").next().unwrap_or(&message).to_string();
+            ConfigError::ParseError {
+                path: path.display().to_string(),
+                line: e.line(),
+                column: e.column(),
+                message,
+            }
+        })
+    }
+
+    /// Load the config and check it for problems parsing cannot catch
+    ///
+    /// Returns the number of configured devices.
+    pub fn validate() -> Result<usize> {
+        let config = Self::load()?;
+        let problems = Self::problems(&config);
+        if !problems.is_empty() {
+            return Err(ConfigError::ValidationError(problems.join("; ")).into());
+        }
+        Ok(config.aurora_devices.len())
+    }
+
+    /// Duplicate hosts or names, and values no connection could use
+    fn problems(config: &DevicesConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (index, device) in config.aurora_devices.iter().enumerate() {
+            let label = format!("device {} ({})", index, device.display_name());
+            if device.host.trim().is_empty() {
+                problems.push(format!("{} has an empty host", label));
+            }
+            if device.port == 0 {
+                problems.push(format!("{} has port 0", label));
+            }
+            let earlier = &config.aurora_devices[..index];
+            if earlier.iter().any(|d| d.host == device.host) {
+                problems.push(format!("{} repeats host {}", label, device.host));
+            }
+            if let Some(name) = &device.name {
+                if earlier.iter().any(|d| d.name.as_ref() == Some(name)) {
+                    problems.push(format!("{} repeats name '{}'", label, name));
+                }
+            }
+            if let Some(jump) = &device.jump_host {
+                if let Err(e) = crate::tools::ssh::JumpHost::parse(jump) {
+                    problems.push(format!("{} has an invalid jump host: {}", label, e));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Move the config file aside as `devices.json.bak-<timestamp>`, leaving
+    /// an empty device list; returns the backup path
+    pub fn backup_config() -> Result<PathBuf> {
+        let path = Self::config_path()?;
+        let backup = path.with_file_name(format!(
+            "devices.json.bak-{}",
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        ));
+        fs::rename(&path, &backup)?;
+        Ok(backup)
     }
 
     pub fn save(config: &DevicesConfig) -> Result<()> {
         let path = Self::config_path()?;
         let content = serde_json::to_string_pretty(config)?;
-        fs::write(&path, content)?;
+        write_atomic(&path, content)?;
         Ok(())
     }
 
@@ -42,6 +111,16 @@ impl DeviceStore {
             return Err(anyhow!("Device with host {} already exists", device.host));
         }
 
+        // Names must stay unique or name lookups become ambiguous
+        if let Some(name) = &device.name {
+            if let Some(existing) = config.aurora_devices.iter().find(|d| d.name.as_ref() == Some(name)) {
+                return Err(anyhow!(
+                    "Device name '{}' is already used by {}",
+                    name, existing.host
+                ));
+            }
+        }
+
         config.aurora_devices.push(device);
         Self::save(&config)?;
         Ok(())
@@ -57,6 +136,37 @@ impl DeviceStore {
         Ok(removed_device)
     }
 
+    /// Remember the hardware model reported by the device with the given host
+    ///
+    /// Does nothing if the host is not configured or the model is unchanged.
+    pub fn set_model(host: &str, model: &str) -> Result<()> {
+        let mut config = Self::load()?;
+        let Some(device) = config.aurora_devices.iter_mut().find(|d| d.host == host) else {
+            return Ok(());
+        };
+
+        if device.model.as_deref() == Some(model) {
+            return Ok(());
+        }
+
+        device.model = Some(model.to_string());
+        Self::save(&config)
+    }
+
+    /// Record that a connection to the device with the given host just succeeded
+    ///
+    /// Rewrites the config, so callers update this once per established
+    /// connection rather than per command. Does nothing if the host is not configured.
+    pub fn set_last_connected(host: &str) -> Result<()> {
+        let mut config = Self::load()?;
+        let Some(device) = config.aurora_devices.iter_mut().find(|d| d.host == host) else {
+            return Ok(());
+        };
+
+        device.last_connected = Some(chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false));
+        Self::save(&config)
+    }
+
     pub fn find(identifier: &DeviceIdentifier) -> Result<Device> {
         let config = Self::load()?;
         Self::find_device(&config.aurora_devices, identifier)
@@ -86,11 +196,62 @@ impl DeviceStore {
                     .ok_or_else(|| anyhow!("Device with host {} not found", host))
             }
             DeviceIdentifier::Name(name) => {
-                devices.iter()
-                    .find(|d| d.name.as_ref() == Some(name))
-                    .cloned()
-                    .ok_or_else(|| anyhow!("Device with name '{}' not found", name))
+                let matches: Vec<&Device> = devices.iter()
+                    .filter(|d| d.name.as_ref() == Some(name))
+                    .collect();
+
+                // Configs written before names were unique may still hold duplicates
+                match matches.as_slice() {
+                    [] => Err(anyhow!("Device with name '{}' not found", name)),
+                    [device] => Ok((*device).clone()),
+                    several => Err(anyhow!(
+                        "Several devices are named '{}' ({}); use the host or index instead",
+                        name,
+                        several.iter().map(|d| d.host.as_str()).collect::<Vec<_>>().join(", ")
+                    )),
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(host: &str, name: Option<&str>) -> String {
+        let name = name.map(|n| format!("\"name\": \"{}\", ", n)).unwrap_or_default();
+        format!(
+            "{{{}\"host\": \"{}\", \"auth\": \"/k\", \"platform\": \"aurora-arm\"}}",
+            name, host
+        )
+    }
+
+    #[test]
+    fn test_parse_error_reports_location() {
+        let content = "{\n  \"aurora-devices\": [\n    {\"host\": \"10.0.0.1\",}\n  ]\n}";
+        match DeviceStore::parse(Path::new("/tmp/devices.json"), content) {
+            Err(ConfigError::ParseError { path, line, message, .. }) => {
+                assert_eq!(path, "/tmp/devices.json");
+                assert_eq!(line, 3);
+                assert!(!message.contains(" at line "));
+            }
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_problems_finds_duplicates() {
+        let content = format!(
+            "{{\"aurora-devices\": [{}, {}, {}]}}",
+            device("10.0.0.1", Some("phone")),
+            device("10.0.0.1", None),
+            device("10.0.0.2", Some("phone"))
+        );
+        let config = DeviceStore::parse(Path::new("devices.json"), &content).unwrap();
+        let problems = DeviceStore::problems(&config);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("repeats host 10.0.0.1"));
+        assert!(problems[1].contains("repeats name 'phone'"));
+    }
+}
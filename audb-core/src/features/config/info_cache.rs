@@ -0,0 +1,43 @@
+use super::atomic::write_atomic;
+use anyhow::{anyhow, Result};
+use audb_protocol::DeviceInfo;
+use directories::BaseDirs;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Last successfully fetched device info, kept per host for offline reference
+pub struct InfoCache;
+
+impl InfoCache {
+    pub fn cache_path(host: &str) -> Result<PathBuf> {
+        let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        let cache_dir = base_dirs.config_dir().join("audb").join("cache");
+        fs::create_dir_all(&cache_dir)?;
+        let file_name: String = host
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+            .collect();
+        Ok(cache_dir.join(format!("{}-info.json", file_name)))
+    }
+
+    pub fn save(host: &str, info: &DeviceInfo) -> Result<()> {
+        let path = Self::cache_path(host)?;
+        let content = serde_json::to_string_pretty(info)?;
+        write_atomic(&path, content)?;
+        Ok(())
+    }
+
+    /// Load cached info and the time it was written (None if never cached)
+    pub fn load(host: &str) -> Result<Option<(DeviceInfo, SystemTime)>> {
+        let path = Self::cache_path(host)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let info: DeviceInfo = serde_json::from_str(&content)?;
+        let modified = fs::metadata(&path)?.modified()?;
+        Ok(Some((info, modified)))
+    }
+}
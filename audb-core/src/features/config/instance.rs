@@ -0,0 +1,50 @@
+use anyhow::{anyhow, Result};
+use directories::BaseDirs;
+use std::fs;
+use std::path::PathBuf;
+
+/// Environment variable overriding the server socket path
+pub const SOCKET_ENV: &str = "AUDB_SOCKET";
+
+/// Locations of a server instance's socket, PID file and log
+///
+/// By default there is one server per user. Setting `AUDB_SOCKET` (or passing
+/// `--socket`) selects a separate instance whose PID file and log sit next to
+/// its socket, so several servers can run side by side.
+pub struct ServerInstance;
+
+impl ServerInstance {
+    /// Socket path overridden through `AUDB_SOCKET`, if any
+    fn socket_override() -> Option<PathBuf> {
+        std::env::var_os(SOCKET_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
+
+    pub fn socket_path() -> PathBuf {
+        Self::socket_override().unwrap_or_else(|| {
+            let uid = unsafe { libc::getuid() };
+            PathBuf::from(format!("/tmp/audb-server-{}.sock", uid))
+        })
+    }
+
+    pub fn pid_file_path() -> Result<PathBuf> {
+        Self::instance_file("pid", "server.pid")
+    }
+
+    pub fn log_file_path() -> Result<PathBuf> {
+        Self::instance_file("log", "server.log")
+    }
+
+    /// Next to the socket for overridden instances, in ~/.config/audb otherwise
+    fn instance_file(extension: &str, default_name: &str) -> Result<PathBuf> {
+        if let Some(socket) = Self::socket_override() {
+            return Ok(socket.with_extension(extension));
+        }
+
+        let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        let config_dir = base_dirs.config_dir().join("audb");
+        fs::create_dir_all(&config_dir)?;
+        Ok(config_dir.join(default_name))
+    }
+}
@@ -1,2 +1,5 @@
+pub mod atomic;
 pub mod device_store;
+pub mod info_cache;
+pub mod instance;
 pub mod state;
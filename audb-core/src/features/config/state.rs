@@ -1,3 +1,4 @@
+use super::atomic::write_atomic;
 use anyhow::{anyhow, Result};
 use directories::BaseDirs;
 use std::fs;
@@ -29,7 +30,7 @@ impl DeviceState {
 
     pub fn set_current(host: &str) -> Result<()> {
         let path = Self::state_path()?;
-        fs::write(&path, host)?;
+        write_atomic(&path, host)?;
         Ok(())
     }
 
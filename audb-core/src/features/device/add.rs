@@ -5,15 +5,34 @@ use crate::tools::types::{Device, Platform};
 use crate::tools::validation::{validate_ip_address, validate_port, validate_ssh_key_exists};
 use anyhow::{anyhow, Result};
 use dialoguer::{Confirm, Input, Password, Select};
+use russh::client::Handle;
+use std::io::Read;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
 use std::path::PathBuf;
+use std::time::Duration;
 
-pub async fn execute() -> Result<()> {
+/// Ports tried, in order, when detecting where the device's SSH server listens
+const PROBE_PORTS: [u16; 3] = [22, 2222, 2223];
+
+/// How long to wait for each probed port to accept and send its banner
+const PROBE_TIMEOUT: Duration = Duration::from_millis(800);
+
+pub async fn execute(retries: u32, retry_delay_secs: u64, probe_port: bool) -> Result<()> {
     println!("\x1b[1m\x1b[36mAdd Aurora OS Device\x1b[0m\n");
 
     // Device name (optional)
+    let existing = DeviceStore::list().unwrap_or_default();
     let name: String = Input::new()
         .with_prompt("Device name (optional, press Enter to skip)")
         .allow_empty(true)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            let input = input.trim();
+            if !input.is_empty() && existing.iter().any(|d| d.name.as_deref() == Some(input)) {
+                Err("Another device already uses this name")
+            } else {
+                Ok(())
+            }
+        })
         .interact_text()?;
 
     let name = if name.trim().is_empty() {
@@ -34,10 +53,23 @@ pub async fn execute() -> Result<()> {
         })
         .interact_text()?;
 
-    // SSH port
+    // SSH port (defaults to the first common port answering with an SSH banner)
+    let default_port = if probe_port {
+        let host = host.trim().to_string();
+        let detected = tokio::task::spawn_blocking(move || detect_ssh_port(&host))
+            .await
+            .ok()
+            .flatten();
+        if let Some(port) = detected {
+            print_info(format!("Detected SSH server on port {}", port));
+        }
+        detected.unwrap_or(22)
+    } else {
+        22
+    };
     let port: u16 = Input::new()
         .with_prompt("SSH port")
-        .default(22)
+        .default(default_port)
         .validate_with(|input: &u16| -> Result<(), &str> {
             if validate_port(*input).is_ok() {
                 Ok(())
@@ -68,19 +100,35 @@ pub async fn execute() -> Result<()> {
         .allow_empty_password(true)
         .interact()?;
 
-    // Platform selection
-    let platforms = vec!["aurora-arm", "aurora-arm64"];
+    // Test defaultuser SSH connection
+    print_info("Testing SSH connection as defaultuser...");
+    let key_path = PathBuf::from(shellexpand::tilde(&auth).to_string());
+    let connection_ok = test_connection_with_retries(&host, port, &key_path, retries, retry_delay_secs).await;
+
+    // Read platform and model while the device is reachable
+    let (detected_platform, model) = if connection_ok {
+        println!("\x1b[1m\x1b[32msuccess\x1b[0m: defaultuser SSH connection verified");
+        probe_device(&host, port, &key_path)
+    } else {
+        println!("\x1b[1m\x1b[93mwarning\x1b[0m: Could not establish SSH connection to the device");
+        (None, None)
+    };
+
+    // Platform selection (defaults to the detected platform)
+    let platforms: Vec<String> = Platform::ALL.iter().map(|p| p.to_string()).collect();
+    let default_platform = detected_platform
+        .and_then(|detected| Platform::ALL.iter().position(|p| *p == detected))
+        .unwrap_or(0);
     let selection = Select::new()
         .with_prompt("Platform")
         .items(&platforms)
-        .default(0)
+        .default(default_platform)
         .interact()?;
 
-    let platform = match selection {
-        0 => Platform::AuroraArm,
-        1 => Platform::AuroraArm64,
-        _ => return Err(anyhow!("Invalid platform selection")),
-    };
+    let platform = Platform::ALL
+        .get(selection)
+        .cloned()
+        .ok_or_else(|| anyhow!("Invalid platform selection"))?;
 
     // Create device
     let device = Device {
@@ -91,16 +139,14 @@ pub async fn execute() -> Result<()> {
         root_password: root_password.clone(),
         platform,
         enabled: true,
+        model,
+        last_connected: connection_ok
+            .then(|| chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)),
+        jump_host: None,
+        jump_auth: None,
     };
 
-    // Test defaultuser SSH connection
-    print_info("Testing SSH connection as defaultuser...");
-    let key_path = device.auth_path();
-    let connection_ok = SshClient::test_connection(&host, port, &key_path);
-
     if !connection_ok {
-        println!("\x1b[1m\x1b[93mwarning\x1b[0m: Could not establish SSH connection to the device");
-
         let add_anyway = Confirm::new()
             .with_prompt("Add device anyway?")
             .default(false)
@@ -109,8 +155,6 @@ pub async fn execute() -> Result<()> {
         if !add_anyway {
             return Err(anyhow!("Device not added"));
         }
-    } else {
-        println!("\x1b[1m\x1b[32msuccess\x1b[0m: defaultuser SSH connection verified");
     }
 
     // Save device
@@ -118,7 +162,93 @@ pub async fn execute() -> Result<()> {
 
     println!("\n\x1b[1m\x1b[32msuccess\x1b[0m: Device added successfully");
     if root_password.is_empty() {
-        println!("\x1b[1m\x1b[90mnote\x1b[0m: Tap/swipe/screenshot commands require root password to be configured");
+        println!("\x1b[1m\x1b[90mnote\x1b[0m: Tap/swipe commands require root password to be configured (screenshot may too, depending on the device)");
     }
     Ok(())
 }
+
+/// Test the SSH connection, retrying on failure (useful for devices that are still booting)
+async fn test_connection_with_retries(
+    host: &str,
+    port: u16,
+    key_path: &std::path::Path,
+    retries: u32,
+    retry_delay_secs: u64,
+) -> bool {
+    let attempts = retries.saturating_add(1);
+
+    for attempt in 1..=attempts {
+        if SshClient::test_connection(host, port, key_path) {
+            return true;
+        }
+
+        if attempt < attempts {
+            print_info(format!(
+                "Connection attempt {}/{} failed, retrying in {}s...",
+                attempt, attempts, retry_delay_secs
+            ));
+            tokio::time::sleep(Duration::from_secs(retry_delay_secs)).await;
+        }
+    }
+
+    false
+}
+
+/// First of the common SSH ports on `host` that answers with an SSH version banner
+fn detect_ssh_port(host: &str) -> Option<u16> {
+    PROBE_PORTS.into_iter().find(|&port| {
+        (host, port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .is_some_and(has_ssh_banner)
+    })
+}
+
+/// Connect and check that the server greets with `SSH-` (RFC 4253 version exchange)
+fn has_ssh_banner(addr: SocketAddr) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) else {
+        return false;
+    };
+    if stream.set_read_timeout(Some(PROBE_TIMEOUT)).is_err() {
+        return false;
+    }
+
+    let mut banner = [0u8; 4];
+    stream.read_exact(&mut banner).is_ok() && &banner == b"SSH-"
+}
+
+/// Read the platform (from `uname -m`) and hardware model from the device
+fn probe_device(host: &str, port: u16, key_path: &std::path::Path) -> (Option<Platform>, Option<String>) {
+    let Ok(mut session) = SshClient::connect(host, port, key_path) else {
+        return (None, None);
+    };
+
+    let platform = SshClient::exec(&mut session, "uname -m")
+        .ok()
+        .and_then(|output| output.first().and_then(|machine| Platform::from_machine(machine)));
+
+    (platform, fetch_device_model(&mut session))
+}
+
+/// Ask the device for its hardware model (None if it cannot be read)
+fn fetch_device_model(session: &mut Handle<SshClient>) -> Option<String> {
+    let output = SshClient::exec(
+        session,
+        "gdbus call --system --dest ru.omp.deviceinfo --object-path /ru/omp/deviceinfo/Features --method ru.omp.deviceinfo.Features.getDeviceModel",
+    ).ok()?;
+
+    // Response looks like "('model',)"
+    let model = output
+        .first()?
+        .trim_matches(|c| c == '(' || c == ')' || c == ',')
+        .trim()
+        .trim_matches('\'')
+        .to_string();
+
+    if model.is_empty() {
+        None
+    } else {
+        Some(model)
+    }
+}
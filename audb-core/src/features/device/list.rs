@@ -1,25 +1,53 @@
 use crate::features::config::{device_store::DeviceStore, state::DeviceState};
 use crate::tools::ssh::SshClient;
-use anyhow::Result;
+use crate::tools::types::Device;
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
-pub async fn execute(active_only: bool) -> Result<()> {
+/// Default number of devices `device list --active` probes at once
+pub const DEFAULT_PARALLEL_PROBES: usize = 8;
+
+/// Output format of `audb device list`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListFormat {
+    /// Colored table with a header (default)
+    Table,
+    /// JSON array of device objects annotated with their status
+    Json,
+    /// Tab-separated rows, no color and no header
+    Plain,
+}
+
+impl ListFormat {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(ListFormat::Table),
+            "json" => Ok(ListFormat::Json),
+            "plain" => Ok(ListFormat::Plain),
+            _ => Err(anyhow!("Unknown format: {}. Use table, json or plain", s)),
+        }
+    }
+}
+
+/// List devices; with `active_only`, probe at most `parallel` devices at once
+pub async fn execute(active_only: bool, parallel: usize, format: ListFormat) -> Result<()> {
     if active_only {
-        list_active_devices().await
+        list_active_devices(parallel, format).await
     } else {
-        list_all_devices().await
+        list_all_devices(format).await
     }
 }
 
 /// Try to get live status from server
 async fn get_server_status() -> Option<HashMap<String, String>> {
-    use std::path::PathBuf;
+    use crate::features::config::instance::ServerInstance;
     use tokio::net::UnixStream;
     
-    let uid = unsafe { libc::getuid() };
-    let socket_path = PathBuf::from(format!("/tmp/audb-server-{}.sock", uid));
+    let socket_path = ServerInstance::socket_path();
     
     if !socket_path.exists() {
         return None;
@@ -33,7 +61,7 @@ async fn get_server_status() -> Option<HashMap<String, String>> {
         command: audb_protocol::Command::ServerStatus,
     };
     
-    audb_protocol::send_message(&mut stream, &request).await.ok()?;
+    audb_protocol::send_message(&mut stream, request).await.ok()?;
     let response: audb_protocol::Response = audb_protocol::recv_message(&mut stream).await.ok()?;
     
     if let audb_protocol::CommandResult::Success { output: audb_protocol::CommandOutput::Status(status) } = response.result {
@@ -58,10 +86,87 @@ async fn get_server_status() -> Option<HashMap<String, String>> {
     }
 }
 
-async fn list_all_devices() -> Result<()> {
+/// Status shown for a device: the live server state, or its configured state
+fn device_status(device: &Device, live_status: Option<&HashMap<String, String>>) -> String {
+    match live_status {
+        Some(live) => match live.get(&device.host) {
+            Some(state) => state.clone(),
+            None if device.enabled => "not in server".to_string(),
+            None => "disabled".to_string(),
+        },
+        None if device.enabled => "enabled".to_string(),
+        None => "disabled".to_string(),
+    }
+}
+
+/// Color a status for the table: green when usable, red on errors, gray when inactive
+fn colorize_status(status: &str) -> String {
+    let color = if status.starts_with("connected") || status == "enabled" {
+        32
+    } else if status.starts_with("error") || status == "disconnected" {
+        31
+    } else if status == "not in server" || status == "disabled" {
+        90
+    } else {
+        33
+    };
+    format!("\x1b[{}m{}\x1b[0m", color, status)
+}
+
+/// Last successful connection in local time, "never" if there was none
+fn format_last_connected(device: &Device) -> String {
+    match &device.last_connected {
+        Some(at) => chrono::DateTime::parse_from_rfc3339(at)
+            .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| at.clone()),
+        None => "never".to_string(),
+    }
+}
+
+/// Print devices with their status as JSON or tab-separated rows
+fn print_machine_readable(
+    rows: &[(usize, &Device, String)],
+    current_host: Option<&String>,
+    format: ListFormat,
+) -> Result<()> {
+    if format == ListFormat::Json {
+        let mut entries = Vec::new();
+        for (idx, device, status) in rows {
+            let mut entry = serde_json::to_value(device)?;
+            if let Some(object) = entry.as_object_mut() {
+                // The root password stays in the device store
+                object.remove("rootPassword");
+                object.insert("index".to_string(), (*idx).into());
+                object.insert("status".to_string(), status.clone().into());
+                object.insert("current".to_string(), (current_host == Some(&device.host)).into());
+            }
+            entries.push(entry);
+        }
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    for (idx, device, status) in rows {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            idx,
+            device.name.as_deref().unwrap_or("-"),
+            device.model.as_deref().unwrap_or("-"),
+            device.host,
+            device.port,
+            device.platform,
+            status,
+            if current_host == Some(&device.host) { "*" } else { "-" },
+            device.last_connected.as_deref().unwrap_or("-")
+        );
+    }
+    Ok(())
+}
+
+async fn list_all_devices(format: ListFormat) -> Result<()> {
     let devices = DeviceStore::list()?;
 
-    if devices.is_empty() {
+    if devices.is_empty() && format == ListFormat::Table {
         println!("No devices configured. Use 'audb device add' to add a device.");
         return Ok(());
     }
@@ -71,44 +176,38 @@ async fn list_all_devices() -> Result<()> {
     // Try to get live status from server
     let live_status = get_server_status().await;
 
+    if format != ListFormat::Table {
+        let rows: Vec<_> = devices
+            .iter()
+            .enumerate()
+            .map(|(idx, device)| (idx, device, device_status(device, live_status.as_ref())))
+            .collect();
+        return print_machine_readable(&rows, current_host.as_ref(), format);
+    }
+
     // Header
-    println!("\x1b[1m{:<5} {:<20} {:<18} {:<6} {:<15} {:<10}\x1b[0m",
-        "Index", "Name", "Host", "Port", "Platform", "Status");
-    println!("{}", "-".repeat(80));
+    println!("\x1b[1m{:<5} {:<20} {:<20} {:<18} {:<6} {:<15} {:<17} {:<10}\x1b[0m",
+        "Index", "Name", "Model", "Host", "Port", "Platform", "Last Connected", "Status");
+    println!("{}", "-".repeat(119));
 
     for (idx, device) in devices.iter().enumerate() {
         let name = device.name.as_deref().unwrap_or("-");
+        let model = device.model.as_deref().unwrap_or("-");
         
         // Use live status if available, otherwise show config status
-        let status = if let Some(ref live) = live_status {
-            if let Some(state) = live.get(&device.host) {
-                if state.starts_with("connected") {
-                    format!("\x1b[32m{}\x1b[0m", state)
-                } else if state.starts_with("error") || state == "disconnected" {
-                    format!("\x1b[31m{}\x1b[0m", state)
-                } else {
-                    format!("\x1b[33m{}\x1b[0m", state)
-                }
-            } else if device.enabled {
-                "\x1b[90mnot in server\x1b[0m".to_string()
-            } else {
-                "\x1b[90mdisabled\x1b[0m".to_string()
-            }
-        } else if device.enabled {
-            "\x1b[32menabled\x1b[0m".to_string()
-        } else {
-            "\x1b[90mdisabled\x1b[0m".to_string()
-        };
+        let status = colorize_status(&device_status(device, live_status.as_ref()));
 
         let is_current = current_host.as_ref() == Some(&device.host);
         let marker = if is_current { " *" } else { "" };
 
-        println!("{:<5} {:<20} {:<18} {:<6} {:<15} {}{}",
+        println!("{:<5} {:<20} {:<20} {:<18} {:<6} {:<15} {:<17} {}{}",
             idx,
             name,
+            model,
             device.host,
             device.port,
             device.platform,
+            format_last_connected(device),
             status,
             marker
         );
@@ -125,39 +224,72 @@ async fn list_all_devices() -> Result<()> {
     Ok(())
 }
 
-async fn list_active_devices() -> Result<()> {
+async fn list_active_devices(parallel: usize, format: ListFormat) -> Result<()> {
     let devices = DeviceStore::list_enabled()?;
 
-    if devices.is_empty() {
+    if devices.is_empty() && format == ListFormat::Table {
         println!("No enabled devices configured.");
         return Ok(());
     }
 
-    println!("Testing connections to {} device(s)...\n", devices.len());
-
     let current_host = DeviceState::get_current().ok();
 
-    // Test connections concurrently
-    let mut join_set = JoinSet::new();
+    // Devices the server already holds a live connection to need no probe
+    let live_status = get_server_status().await;
+    let is_live = |host: &str| {
+        live_status
+            .as_ref()
+            .and_then(|live| live.get(host))
+            .is_some_and(|state| state.starts_with("connected"))
+    };
 
+    let mut results = Vec::new();
+    let mut to_probe = Vec::new();
     for (idx, device) in devices.iter().enumerate() {
+        if is_live(&device.host) {
+            results.push((idx, Arc::new(device.clone()), true));
+        } else {
+            to_probe.push((idx, device));
+        }
+    }
+
+    if !to_probe.is_empty() && format == ListFormat::Table {
+        println!("Testing connections to {} device(s)...\n", to_probe.len());
+    }
+
+    // Test remaining connections concurrently, at most `parallel` at a time
+    // so large inventories don't open dozens of SSH connections at once
+    let total = to_probe.len();
+    let semaphore = Arc::new(Semaphore::new(parallel.max(1)));
+    let mut join_set = JoinSet::new();
+
+    for (idx, device) in to_probe {
         let device = Arc::new(device.clone());
+        let semaphore = semaphore.clone();
         join_set.spawn(async move {
-            let is_online = SshClient::test_connection(
-                &device.host,
-                device.port,
-                &device.auth_path(),
-            );
+            let _permit = semaphore.acquire_owned().await;
+            let is_online = SshClient::test_device_connection(&device);
             (idx, device, is_online)
         });
     }
 
-    let mut results = Vec::new();
+    let mut done = 0;
     while let Some(result) = join_set.join_next().await {
-        if let Ok(data) = result {
-            results.push(data);
+        done += 1;
+        if format == ListFormat::Table {
+            eprint!("\r\x1b[90mProbed {}/{}\x1b[0m", done, total);
+            std::io::stderr().flush().ok();
+        }
+        if let Ok((idx, device, is_online)) = result {
+            if is_online {
+                DeviceStore::set_last_connected(&device.host).ok();
+            }
+            results.push((idx, device, is_online));
         }
     }
+    if total > 0 && format == ListFormat::Table {
+        eprintln!();
+    }
 
     // Sort by index to maintain order
     results.sort_by_key(|(idx, _, _)| *idx);
@@ -165,27 +297,38 @@ async fn list_active_devices() -> Result<()> {
     // Display only active devices
     let active_results: Vec<_> = results.iter().filter(|(_, _, is_online)| *is_online).collect();
 
+    if format != ListFormat::Table {
+        let rows: Vec<_> = active_results
+            .iter()
+            .map(|(idx, device, _)| (*idx, device.as_ref(), "online".to_string()))
+            .collect();
+        return print_machine_readable(&rows, current_host.as_ref(), format);
+    }
+
     if active_results.is_empty() {
         println!("No devices are currently reachable.");
         return Ok(());
     }
 
     // Header
-    println!("\x1b[1m{:<5} {:<20} {:<18} {:<6} {:<15} {:<10}\x1b[0m",
-        "Index", "Name", "Host", "Port", "Platform", "Status");
-    println!("{}", "-".repeat(80));
+    println!("\x1b[1m{:<5} {:<20} {:<20} {:<18} {:<6} {:<15} {:<17} {:<10}\x1b[0m",
+        "Index", "Name", "Model", "Host", "Port", "Platform", "Last Connected", "Status");
+    println!("{}", "-".repeat(119));
 
     for (idx, device, _) in active_results {
         let name = device.name.as_deref().unwrap_or("-");
+        let model = device.model.as_deref().unwrap_or("-");
         let is_current = current_host.as_ref() == Some(&device.host);
         let marker = if is_current { " *" } else { "" };
 
-        println!("{:<5} {:<20} {:<18} {:<6} {:<15} \x1b[32monline\x1b[0m{}",
+        println!("{:<5} {:<20} {:<20} {:<18} {:<6} {:<15} {:<17} \x1b[32monline\x1b[0m{}",
             idx,
             name,
+            model,
             device.host,
             device.port,
             device.platform,
+            format_last_connected(device),
             marker
         );
     }
@@ -194,5 +337,9 @@ async fn list_active_devices() -> Result<()> {
         println!("\n\x1b[36m*\x1b[0m Currently selected device: {}", host);
     }
 
+    if live_status.is_some() {
+        println!("\x1b[90m(connected devices from server, others probed directly)\x1b[0m");
+    }
+
     Ok(())
 }
@@ -2,3 +2,4 @@ pub mod add;
 pub mod list;
 pub mod remove;
 pub mod select;
+pub mod validate;
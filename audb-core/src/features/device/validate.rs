@@ -0,0 +1,51 @@
+use crate::features::config::device_store::DeviceStore;
+use crate::tools::errors::ConfigError;
+use crate::tools::macros::{print_error, print_info, print_success};
+use anyhow::{anyhow, Result};
+use dialoguer::Confirm;
+use std::io::IsTerminal;
+
+/// Check devices.json, offering to move it aside if it cannot be parsed
+pub async fn execute() -> Result<()> {
+    let path = DeviceStore::config_path()?;
+    if !path.exists() {
+        print_info(format!("No device config at {} yet", path.display()));
+        return Ok(());
+    }
+
+    let error = match DeviceStore::validate() {
+        Ok(count) => {
+            print_success(format!("{} is valid ({} devices)", path.display(), count));
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+
+    let Some(ConfigError::ParseError { line, column, message, .. }) = error.downcast_ref::<ConfigError>() else {
+        // Parsed fine but inconsistent; fixable with `audb device remove` or an edit
+        return Err(error);
+    };
+
+    print_error(format!("{} is not valid: {}", path.display(), message));
+    if let Some(text) = std::fs::read_to_string(&path).ok().and_then(|c| c.lines().nth(line.saturating_sub(1)).map(String::from)) {
+        println!("  {:>4} | {}", line, text);
+        println!("       | {}^", " ".repeat(column.saturating_sub(1)));
+    }
+
+    // Only offer the backup when someone can answer
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow!("Device config is invalid"));
+    }
+    let backup = Confirm::new()
+        .with_prompt("Move the broken file aside and start with an empty device list?")
+        .default(false)
+        .interact()?;
+    if !backup {
+        return Err(anyhow!("Device config is invalid"));
+    }
+
+    let backup_path = DeviceStore::backup_config()?;
+    print_success(format!("Saved the broken config as {}", backup_path.display()));
+    println!("Re-add devices with 'audb device add', or fix the backup and move it back");
+    Ok(())
+}
@@ -2,3 +2,4 @@ pub mod scripts;
 pub mod tap;
 pub mod swipe;
 pub mod screenshot;
+pub mod ui;
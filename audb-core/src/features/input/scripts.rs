@@ -6,9 +6,13 @@ use std::path::Path;
 // Embed scripts at compile time
 const TAP_SCRIPT: &str = include_str!("../../scripts/tap.py");
 const SWIPE_SCRIPT: &str = include_str!("../../scripts/swipe.py");
+const PINCH_SCRIPT: &str = include_str!("../../scripts/pinch.py");
+const UI_DUMP_SCRIPT: &str = include_str!("../../scripts/ui_dump.py");
 
 const REMOTE_TAP_PATH: &str = "/tmp/audb_tap.py";
 const REMOTE_SWIPE_PATH: &str = "/tmp/audb_swipe.py";
+const REMOTE_PINCH_PATH: &str = "/tmp/audb_pinch.py";
+const REMOTE_UI_DUMP_PATH: &str = "/tmp/audb_ui_dump.py";
 
 pub struct ScriptManager;
 
@@ -23,6 +27,11 @@ impl ScriptManager {
         Self::ensure_script_with_session(session, REMOTE_SWIPE_PATH, SWIPE_SCRIPT)
     }
 
+    /// Ensure pinch script is present on device (using DeviceSession)
+    pub fn ensure_pinch_script_with_session(session: &mut DeviceSession) -> Result<()> {
+        Self::ensure_script_with_session(session, REMOTE_PINCH_PATH, PINCH_SCRIPT)
+    }
+
     /// Ensure script is present on device with correct content (using DeviceSession)
     fn ensure_script_with_session(
         session: &mut DeviceSession,
@@ -118,6 +127,14 @@ impl ScriptManager {
         REMOTE_SWIPE_PATH
     }
 
+    pub fn pinch_script_path() -> &'static str {
+        REMOTE_PINCH_PATH
+    }
+
+    pub fn ui_dump_script_path() -> &'static str {
+        REMOTE_UI_DUMP_PATH
+    }
+
     /// Get tap script content
     pub const fn tap_script_content() -> &'static str {
         TAP_SCRIPT
@@ -127,4 +144,14 @@ impl ScriptManager {
     pub const fn swipe_script_content() -> &'static str {
         SWIPE_SCRIPT
     }
+
+    /// Get pinch script content
+    pub const fn pinch_script_content() -> &'static str {
+        PINCH_SCRIPT
+    }
+
+    /// Get accessibility tree dump script content
+    pub const fn ui_dump_script_content() -> &'static str {
+        UI_DUMP_SCRIPT
+    }
 }
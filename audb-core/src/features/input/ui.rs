@@ -0,0 +1,81 @@
+// UI elements from the device's accessibility tree.
+//
+// The device-side ui_dump.py script walks AT-SPI and prints one tab-separated
+// line per element; this module parses that output and finds elements by name.
+
+use audb_protocol::{ElementBounds, UiElement};
+
+/// Parse `ui_dump.py` output (`DEPTH ROLE X Y WIDTH HEIGHT ID NAME`, tab-separated)
+///
+/// Malformed lines are skipped. Elements reporting no geometry (all -1) or an
+/// empty rectangle get no bounds.
+pub fn parse_ui_dump(output: &str) -> Vec<UiElement> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(8, '\t');
+            let depth = fields.next()?.trim().parse().ok()?;
+            let role = fields.next()?.to_string();
+            let mut number = || fields.next()?.trim().parse::<i32>().ok();
+            let (x, y, width, height) = (number()?, number()?, number()?, number()?);
+            let id = fields.next()?.to_string();
+            let name = fields.next().unwrap_or("").to_string();
+
+            let bounds = (width > 0 && height > 0).then_some(ElementBounds { x, y, width, height });
+            Some(UiElement { id, depth, role, name, bounds })
+        })
+        .collect()
+}
+
+/// Elements whose name matches `query`, best matches first
+///
+/// Exact (case-insensitive) name matches come before names that merely
+/// contain the query; tree order is kept within each group.
+pub fn find_elements<'a>(elements: &'a [UiElement], query: &str) -> Vec<&'a UiElement> {
+    let query = query.to_lowercase();
+    let (mut exact, partial): (Vec<&UiElement>, Vec<&UiElement>) = elements
+        .iter()
+        .filter(|element| element.name.to_lowercase().contains(&query))
+        .partition(|element| element.name.to_lowercase() == query);
+    exact.extend(partial);
+    exact
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = "0\tapplication\t-1\t-1\t-1\t-1\t:1.40:/root\tru.example.App\n\
+                        1\tframe\t0\t0\t720\t1440\t:1.40:/a/1\tExample\n\
+                        2\tpush button\t40\t1200\t300\t100\t:1.40:/a/2\tOK, got it\n\
+                        2\tpush button\t380\t1200\t300\t100\t:1.40:/a/3\tok\n\
+                        garbage line\n\
+                        2\tlabel\t40\t100\t640\t60\t:1.40:/a/4\tName\twith tab\n";
+
+    #[test]
+    fn test_parse_ui_dump() {
+        let elements = parse_ui_dump(DUMP);
+        assert_eq!(elements.len(), 5);
+        assert_eq!(elements[0].bounds, None);
+        assert_eq!(elements[0].name, "ru.example.App");
+        assert_eq!(
+            elements[2].bounds,
+            Some(ElementBounds { x: 40, y: 1200, width: 300, height: 100 })
+        );
+        assert_eq!(elements[2].role, "push button");
+        assert_eq!(elements[2].id, ":1.40:/a/2");
+        // Only the first seven tabs separate fields
+        assert_eq!(elements[4].name, "Name\twith tab");
+    }
+
+    #[test]
+    fn test_find_elements_prefers_exact_match() {
+        let elements = parse_ui_dump(DUMP);
+        let found = find_elements(&elements, "OK");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].name, "ok");
+        assert_eq!(found[0].bounds.unwrap().center(), (530, 1250));
+        assert_eq!(found[1].name, "OK, got it");
+        assert!(find_elements(&elements, "Cancel").is_empty());
+    }
+}
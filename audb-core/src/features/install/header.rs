@@ -0,0 +1,253 @@
+// RPM metadata read locally from the package file.
+//
+// Parses just enough of the RPM format (lead, signature header, main header)
+// to report name, version, architecture and whether the package is signed,
+// without rpm tools or a Docker container.
+
+use anyhow::{anyhow, Result};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Size of the legacy lead that starts every RPM file
+const LEAD_SIZE: usize = 96;
+const LEAD_MAGIC: [u8; 4] = [0xed, 0xab, 0xee, 0xdb];
+const HEADER_MAGIC: [u8; 3] = [0x8e, 0xad, 0xe8];
+/// Largest header structure read from a package file
+const MAX_HEADER_SIZE: usize = 64 * 1024 * 1024;
+
+const TAG_NAME: u32 = 1000;
+const TAG_VERSION: u32 = 1001;
+const TAG_RELEASE: u32 = 1002;
+const TAG_ARCH: u32 = 1022;
+
+/// Signature header tags holding an actual signature (not just digests)
+const SIGNATURE_TAGS: [u32; 5] = [
+    267,  // DSA header signature
+    268,  // RSA header signature
+    1002, // PGP
+    1005, // GPG
+    1006, // PGP5
+];
+
+const TYPE_STRING: u32 = 6;
+
+/// Package metadata from an RPM file's headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpmInfo {
+    pub name: String,
+    pub version: String,
+    pub release: String,
+    pub arch: String,
+    /// The signature header carries an RSA/DSA/PGP signature
+    pub signed: bool,
+}
+
+/// One header structure: its index entries and data store
+struct Header<'a> {
+    entries: Vec<(u32, u32, usize)>,
+    store: &'a [u8],
+}
+
+impl Header<'_> {
+    fn has_tag(&self, tag: u32) -> bool {
+        self.entries.iter().any(|(t, _, _)| *t == tag)
+    }
+
+    fn string(&self, tag: u32) -> Option<String> {
+        let (_, kind, offset) = self.entries.iter().find(|(t, _, _)| *t == tag)?;
+        if *kind != TYPE_STRING {
+            return None;
+        }
+        let data = self.store.get(*offset..)?;
+        let end = data.iter().position(|b| *b == 0)?;
+        Some(String::from_utf8_lossy(&data[..end]).into_owned())
+    }
+}
+
+/// Parse the header structure at `pos`, returning it and the position after it
+fn parse_header(data: &[u8], pos: usize) -> Result<(Header<'_>, usize)> {
+    let truncated = || anyhow!("Truncated RPM header");
+    let intro = data.get(pos..pos + 16).ok_or_else(truncated)?;
+    if intro[..3] != HEADER_MAGIC {
+        return Err(anyhow!("Bad RPM header magic"));
+    }
+
+    let be32 = |b: &[u8]| u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+    let count = be32(&intro[8..12]) as usize;
+    let store_size = be32(&intro[12..16]) as usize;
+
+    let index_start = pos + 16;
+    let store_start = index_start + count * 16;
+    let end = store_start + store_size;
+    let index = data.get(index_start..store_start).ok_or_else(truncated)?;
+    let store = data.get(store_start..end).ok_or_else(truncated)?;
+
+    let entries = index
+        .chunks_exact(16)
+        .map(|entry| (be32(&entry[0..4]), be32(&entry[4..8]), be32(&entry[8..12]) as usize))
+        .collect();
+
+    Ok((Header { entries, store }, end))
+}
+
+/// Parse RPM metadata from the start of a package file
+///
+/// Only the lead and both headers are needed, not the payload.
+pub fn parse_rpm_info(data: &[u8]) -> Result<RpmInfo> {
+    if data.len() < LEAD_SIZE || data[..4] != LEAD_MAGIC {
+        return Err(anyhow!("Not an RPM package"));
+    }
+
+    let (signature, end) = parse_header(data, LEAD_SIZE)?;
+    // The main header starts on the next 8-byte boundary
+    let (header, _) = parse_header(data, end.div_ceil(8) * 8)?;
+
+    let field = |tag: u32, label: &str| {
+        header
+            .string(tag)
+            .ok_or_else(|| anyhow!("RPM header has no {}", label))
+    };
+
+    Ok(RpmInfo {
+        name: field(TAG_NAME, "name")?,
+        version: field(TAG_VERSION, "version")?,
+        release: field(TAG_RELEASE, "release")?,
+        arch: field(TAG_ARCH, "architecture")?,
+        signed: SIGNATURE_TAGS.iter().any(|tag| signature.has_tag(*tag)),
+    })
+}
+
+/// Read the lead and both headers of a package, leaving the payload unread
+///
+/// The bytes keep their layout in the file, so `parse_rpm_info` can parse them.
+fn read_rpm_headers<R: Read + Seek>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut data = vec![0u8; LEAD_SIZE];
+    if reader.read_exact(&mut data).is_err() || data[..4] != LEAD_MAGIC {
+        return Err(anyhow!("Not an RPM package"));
+    }
+
+    read_header(reader, &mut data)?;
+    // The main header starts on the next 8-byte boundary
+    let padding = data.len().div_ceil(8) * 8 - data.len();
+    reader.seek(SeekFrom::Current(padding as i64))?;
+    data.resize(data.len() + padding, 0);
+    read_header(reader, &mut data)?;
+
+    Ok(data)
+}
+
+/// Read one header structure and append it to `data`
+fn read_header<R: Read>(reader: &mut R, data: &mut Vec<u8>) -> Result<()> {
+    let truncated = |_: std::io::Error| anyhow!("Truncated RPM header");
+    let mut intro = [0u8; 16];
+    reader.read_exact(&mut intro).map_err(truncated)?;
+    if intro[..3] != HEADER_MAGIC {
+        return Err(anyhow!("Bad RPM header magic"));
+    }
+
+    let be32 = |b: &[u8]| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize;
+    let size = be32(&intro[8..12])
+        .checked_mul(16)
+        .and_then(|index_size| index_size.checked_add(be32(&intro[12..16])))
+        .filter(|size| *size <= MAX_HEADER_SIZE)
+        .ok_or_else(|| anyhow!("RPM header is too large"))?;
+
+    data.extend_from_slice(&intro);
+    let start = data.len();
+    data.resize(start + size, 0);
+    reader.read_exact(&mut data[start..]).map_err(truncated)?;
+    Ok(())
+}
+
+/// Read RPM metadata from a local package file
+pub fn read_rpm_info(path: &Path) -> Result<RpmInfo> {
+    let mut file = std::fs::File::open(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    read_rpm_headers(&mut file)
+        .and_then(|data| parse_rpm_info(&data))
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialize a header structure with the given (tag, type, data) entries
+    fn header(entries: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let mut index = Vec::new();
+        let mut store = Vec::new();
+        for (tag, kind, data) in entries {
+            for value in [*tag, *kind, store.len() as u32, 1] {
+                index.extend_from_slice(&value.to_be_bytes());
+            }
+            store.extend_from_slice(data);
+        }
+
+        let mut out = vec![0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0];
+        out.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        out.extend_from_slice(&(store.len() as u32).to_be_bytes());
+        out.extend(index);
+        out.extend(store);
+        out
+    }
+
+    fn rpm(signature_entries: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let mut data = LEAD_MAGIC.to_vec();
+        data.resize(LEAD_SIZE, 0);
+        data.extend(header(signature_entries));
+        data.resize(data.len().div_ceil(8) * 8, 0);
+        data.extend(header(&[
+            (TAG_NAME, TYPE_STRING, b"ru.example.App\0"),
+            (TAG_VERSION, TYPE_STRING, b"1.2.0\0"),
+            (TAG_RELEASE, TYPE_STRING, b"3\0"),
+            (TAG_ARCH, TYPE_STRING, b"aarch64\0"),
+        ]));
+        data
+    }
+
+    #[test]
+    fn test_parse_unsigned_rpm() {
+        // A 3-byte digest entry forces padding before the main header
+        let info = parse_rpm_info(&rpm(&[(1000, 4, b"abc")])).unwrap();
+        assert_eq!(
+            info,
+            RpmInfo {
+                name: "ru.example.App".to_string(),
+                version: "1.2.0".to_string(),
+                release: "3".to_string(),
+                arch: "aarch64".to_string(),
+                signed: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_signed_rpm() {
+        let info = parse_rpm_info(&rpm(&[(268, 7, b"sig")])).unwrap();
+        assert!(info.signed);
+    }
+
+    #[test]
+    fn test_read_headers_skips_payload() {
+        let mut data = rpm(&[(1000, 4, b"abc")]);
+        let headers_len = data.len();
+        data.extend(vec![0xff; 4096]);
+
+        let mut reader = std::io::Cursor::new(data);
+        let headers = read_rpm_headers(&mut reader).unwrap();
+        assert_eq!(headers.len(), headers_len);
+        assert_eq!(reader.position() as usize, headers_len);
+        assert_eq!(parse_rpm_info(&headers).unwrap().name, "ru.example.App");
+
+        let mut truncated = rpm(&[]);
+        truncated.truncate(LEAD_SIZE + 20);
+        assert!(read_rpm_headers(&mut std::io::Cursor::new(truncated)).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_non_rpm() {
+        assert!(parse_rpm_info(b"not an rpm").is_err());
+        let mut truncated = rpm(&[]);
+        truncated.truncate(LEAD_SIZE + 20);
+        assert!(parse_rpm_info(&truncated).is_err());
+    }
+}
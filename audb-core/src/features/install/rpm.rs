@@ -27,7 +27,7 @@ pub async fn execute(rpm_path: &str) -> Result<()> {
 
     // Connect to device
     print_info(format!("Connecting to {}:{}...", device.host, device.port));
-    let mut session = SshClient::connect(&device.host, device.port, &device.auth_path())?;
+    let mut session = SshClient::connect_device(&device)?;
 
     // Upload RPM to Downloads directory (APM requires access to user's Downloads)
     let remote_path = PathBuf::from(format!("/home/defaultuser/Downloads/{}", file_name));
@@ -10,6 +10,7 @@ use crate::tools::{
     types::DeviceIdentifier,
 };
 use anyhow::{anyhow, Context, Result};
+use serde::{de::IntoDeserializer, Deserialize};
 
 pub struct LogsArgs {
     pub lines: usize,
@@ -20,6 +21,7 @@ pub struct LogsArgs {
     pub clear: bool,
     pub force: bool,
     pub kernel: bool,
+    pub output: Option<String>,
 }
 
 pub async fn execute(args: LogsArgs) -> Result<()> {
@@ -105,6 +107,75 @@ fn validate_args(args: &LogsArgs) -> Result<()> {
     Ok(())
 }
 
+/// journalctl priority names, indexed by their number
+const PRIORITY_NAMES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+
+/// Validate a `--priority` value and return it as a journalctl `-p` argument
+///
+/// Accepts one level or a `FROM..TO` range (e.g. `warning..err`). Levels are
+/// numbers 0-7, journalctl names, or the Android-style letters v/d/i/w/e/f.
+pub fn journalctl_priority(spec: &str) -> Result<String> {
+    let level = |value: &str| -> Result<&'static str> {
+        let value = value.trim().to_lowercase();
+        if let Ok(number) = value.parse::<usize>() {
+            return PRIORITY_NAMES
+                .get(number)
+                .copied()
+                .ok_or_else(|| anyhow!("Priority {} is out of range (0-7)", number));
+        }
+        // Names and letters are the `LogLevel` spellings
+        let parsed: Result<crate::LogLevel, serde::de::value::Error> =
+            crate::LogLevel::deserialize(value.as_str().into_deserializer());
+        parsed
+            .map(|level| level.to_journalctl_priority())
+            .map_err(|_| anyhow!(
+                "Unknown priority '{}'. Use 0-7 or one of: {}",
+                value,
+                PRIORITY_NAMES.join(", ")
+            ))
+    };
+
+    match spec.split_once("..") {
+        Some((from, to)) => Ok(format!("{}..{}", level(from)?, level(to)?)),
+        None => Ok(level(spec)?.to_string()),
+    }
+}
+
+/// journalctl `-o` formats accepted by `--format`
+const OUTPUT_FORMATS: [&str; 8] = [
+    "short",
+    "short-iso",
+    "short-precise",
+    "short-monotonic",
+    "short-unix",
+    "cat",
+    "verbose",
+    "json",
+];
+
+/// Validate a `--format` value and return it as a journalctl `-o` argument
+///
+/// `filtered` says the output goes through grep. That is refused for
+/// `verbose`, which spreads each entry over many lines, so grep would keep
+/// only the matching field lines. With `cat` grep still works, but only sees
+/// the message text (no timestamp or unit).
+pub fn journalctl_output(format: &str, filtered: bool) -> Result<&'static str> {
+    let format = OUTPUT_FORMATS
+        .iter()
+        .find(|known| **known == format.trim())
+        .copied()
+        .ok_or_else(|| anyhow!(
+            "Unknown output format '{}'. Use one of: {}",
+            format,
+            OUTPUT_FORMATS.join(", ")
+        ))?;
+
+    if format == "verbose" && filtered {
+        return Err(anyhow!("--format verbose prints one field per line and cannot be combined with a grep filter"));
+    }
+    Ok(format)
+}
+
 fn build_journalctl_command(args: &LogsArgs) -> Result<String> {
     let mut cmd = String::from("journalctl");
 
@@ -135,6 +206,9 @@ fn build_journalctl_command(args: &LogsArgs) -> Result<String> {
 
     // Output options
     cmd.push_str(" --no-pager --no-hostname");
+    if let Some(ref output) = args.output {
+        cmd.push_str(&format!(" -o {}", journalctl_output(output, args.grep.is_some())?));
+    }
 
     // Grep filter (as pipe, with escaping)
     if let Some(ref grep_pattern) = args.grep {
@@ -160,6 +234,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
@@ -180,12 +255,32 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
         assert!(cmd.contains("-p err"));
     }
 
+    #[test]
+    fn test_journalctl_priority_range() {
+        assert_eq!(journalctl_priority("warning..err").unwrap(), "warning..err");
+        assert_eq!(journalctl_priority("4..3").unwrap(), "warning..err");
+        assert_eq!(journalctl_priority("W..E").unwrap(), "warning..err");
+        assert_eq!(journalctl_priority("info..f").unwrap(), "info..crit");
+        // Single values keep working
+        assert_eq!(journalctl_priority("err").unwrap(), "err");
+        assert_eq!(journalctl_priority("7").unwrap(), "debug");
+    }
+
+    #[test]
+    fn test_journalctl_priority_rejects_invalid() {
+        assert!(journalctl_priority("8").is_err());
+        assert!(journalctl_priority("warning..loud").is_err());
+        assert!(journalctl_priority("..err").is_err());
+        assert!(journalctl_priority("err; reboot").is_err());
+    }
+
     #[test]
     fn test_build_command_with_unit() {
         let args = LogsArgs {
@@ -197,6 +292,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
@@ -214,6 +310,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: true,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
@@ -231,12 +328,42 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
         assert!(cmd.contains("| grep 'ERROR'"));
     }
 
+    #[test]
+    fn test_build_command_with_output() {
+        let args = LogsArgs {
+            lines: 100,
+            priority: None,
+            unit: None,
+            since: None,
+            grep: Some("ERROR".to_string()),
+            clear: false,
+            force: false,
+            kernel: false,
+            output: Some("cat".to_string()),
+        };
+
+        let cmd = build_journalctl_command(&args).unwrap();
+        assert!(cmd.contains(" -o cat"));
+        // The format applies to journalctl, before the grep pipe
+        assert!(cmd.find(" -o cat").unwrap() < cmd.find("| grep").unwrap());
+    }
+
+    #[test]
+    fn test_journalctl_output_rejects_invalid() {
+        assert_eq!(journalctl_output("short-monotonic", false).unwrap(), "short-monotonic");
+        assert_eq!(journalctl_output("verbose", false).unwrap(), "verbose");
+        assert!(journalctl_output("verbose", true).is_err());
+        assert!(journalctl_output("fancy", false).is_err());
+        assert!(journalctl_output("cat; reboot", false).is_err());
+    }
+
     #[test]
     fn test_shell_injection_protection() {
         let args = LogsArgs {
@@ -248,6 +375,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         let cmd = build_journalctl_command(&args).unwrap();
@@ -266,6 +394,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: true,
+            output: None,
         };
 
         assert!(validate_args(&args).is_err());
@@ -282,6 +411,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         assert!(validate_args(&args).is_err());
@@ -298,6 +428,7 @@ mod tests {
             clear: false,
             force: false,
             kernel: false,
+            output: None,
         };
 
         assert!(validate_args(&args).is_ok());
@@ -4,4 +4,5 @@ pub mod device;
 pub mod install;
 pub mod input;
 pub mod logs;
+pub mod service;
 pub mod shell;
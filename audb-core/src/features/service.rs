@@ -0,0 +1,110 @@
+// systemd unit control for Aurora OS devices.
+//
+// The server runs `systemctl` on the device; this module checks unit names
+// before they reach the shell and parses `systemctl show` output.
+
+use anyhow::{anyhow, Result};
+
+/// Properties requested from `systemctl show` for a status report
+pub const STATUS_PROPERTIES: [&str; 5] = ["Description", "LoadState", "ActiveState", "SubState", "UnitFileState"];
+
+/// State of a unit as reported by `systemctl show`
+#[derive(Debug, Default, PartialEq)]
+pub struct UnitStatus {
+    pub description: String,
+    /// `loaded`, `not-found`, `masked`, ...
+    pub load_state: String,
+    /// `active`, `inactive`, `failed`, ...
+    pub active_state: String,
+    /// `running`, `exited`, `dead`, ...
+    pub sub_state: String,
+    /// `enabled`, `disabled`, `static`, ... (empty for transient units)
+    pub unit_file_state: String,
+}
+
+impl UnitStatus {
+    /// Whether systemd knows the unit at all
+    pub fn exists(&self) -> bool {
+        !self.load_state.is_empty() && self.load_state != "not-found"
+    }
+
+    /// Active state with its sub-state, e.g. `active (running)`
+    pub fn active_summary(&self) -> String {
+        if self.sub_state.is_empty() {
+            self.active_state.clone()
+        } else {
+            format!("{} ({})", self.active_state, self.sub_state)
+        }
+    }
+}
+
+/// Check a unit name before it is passed to `systemctl`
+///
+/// Allows the characters systemd itself accepts in unit names (plus `\` for
+/// its escapes); anything else is rejected rather than quoted.
+pub fn validate_unit_name(unit: &str) -> Result<()> {
+    if unit.is_empty() {
+        return Err(anyhow!("Unit name cannot be empty"));
+    }
+    if unit.len() > 255 {
+        return Err(anyhow!("Unit name exceeds the systemd limit of 255 characters"));
+    }
+    if unit.starts_with('-') {
+        return Err(anyhow!("Invalid unit name: '{}'", unit));
+    }
+    if let Some(c) = unit.chars().find(|c| !(c.is_ascii_alphanumeric() || ":-_.@\\".contains(*c))) {
+        return Err(anyhow!("Invalid character '{}' in unit name '{}'", c, unit));
+    }
+    Ok(())
+}
+
+/// Parse `systemctl show -p ...` output (`Key=value` lines)
+pub fn parse_unit_status(output: &str) -> UnitStatus {
+    let mut status = UnitStatus::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "Description" => status.description = value,
+            "LoadState" => status.load_state = value,
+            "ActiveState" => status.active_state = value,
+            "SubState" => status.sub_state = value,
+            "UnitFileState" => status.unit_file_state = value,
+            _ => {}
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_unit_name() {
+        assert!(validate_unit_name("sshd.service").is_ok());
+        assert!(validate_unit_name("getty@tty1.service").is_ok());
+        assert!(validate_unit_name("dev-disk-by\\x2dlabel-home.mount").is_ok());
+        assert!(validate_unit_name("").is_err());
+        assert!(validate_unit_name("--now").is_err());
+        assert!(validate_unit_name("sshd; reboot").is_err());
+        assert!(validate_unit_name("a b").is_err());
+    }
+
+    #[test]
+    fn test_parse_unit_status() {
+        let status = parse_unit_status(
+            "Description=OpenSSH Daemon\nLoadState=loaded\nActiveState=active\nSubState=running\nUnitFileState=enabled\n",
+        );
+        assert!(status.exists());
+        assert_eq!(status.description, "OpenSSH Daemon");
+        assert_eq!(status.active_summary(), "active (running)");
+        assert_eq!(status.unit_file_state, "enabled");
+
+        let missing = parse_unit_status("Description=nope.service\nLoadState=not-found\nActiveState=inactive\nSubState=dead\nUnitFileState=\n");
+        assert!(!missing.exists());
+        assert!(!parse_unit_status("").exists());
+    }
+}
@@ -36,8 +36,13 @@ pub enum ConfigError {
     ReadError(#[from] std::io::Error),
 
     /// Failed to parse configuration file
-    #[error("Invalid config format: {0}")]
-    ParseError(#[from] serde_json::Error),
+    #[error("Invalid config format in {path} at line {line}, column {column}: {message} (fix the file, or run 'audb device validate-config' to back it up)")]
+    ParseError {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
 
     /// Configuration validation failed
     #[error("Config validation failed: {0}")]
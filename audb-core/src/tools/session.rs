@@ -11,6 +11,26 @@ use crate::tools::{
 use anyhow::{Context, Result};
 use russh::client::Handle;
 use std::path::Path;
+use std::sync::Mutex;
+
+/// Idle SSH sessions kept for reuse within this process, keyed by `host:port`
+///
+/// A `DeviceSession` checks a session out on connect and returns it when
+/// dropped, unless an operation on it failed.
+static SESSION_CACHE: Mutex<Vec<(String, Handle<SshClient>)>> = Mutex::new(Vec::new());
+
+fn cache_key(device: &Device) -> String {
+    format!("{}:{}", device.host, device.port)
+}
+
+/// Take a still-open cached session for the device, if there is one
+fn take_cached_session(device: &Device) -> Option<Handle<SshClient>> {
+    let key = cache_key(device);
+    let mut cache = SESSION_CACHE.lock().ok()?;
+    let idx = cache.iter().position(|(k, _)| *k == key)?;
+    let (_, session) = cache.swap_remove(idx);
+    (!session.is_closed()).then_some(session)
+}
 
 /// Manages an active SSH session to an Aurora device
 ///
@@ -31,12 +51,18 @@ use std::path::Path;
 /// ```
 pub struct DeviceSession {
     device: Device,
-    session: Handle<SshClient>,
+    /// Always `Some` until the session is returned to the cache on drop
+    session: Option<Handle<SshClient>>,
+    /// Cleared when an operation fails, so the session is not reused
+    healthy: bool,
 }
 
 impl DeviceSession {
     /// Connect to a device and return an active session
     ///
+    /// Reuses a session left by an earlier `DeviceSession` for the same device
+    /// in this process, so back-to-back commands skip the SSH handshake.
+    ///
     /// # Arguments
     /// * `device` - The device configuration to connect to
     ///
@@ -46,15 +72,31 @@ impl DeviceSession {
     /// # Errors
     /// Returns `DeviceError::ConnectionFailed` if the SSH connection cannot be established
     pub fn connect(device: &Device) -> Result<Self, DeviceError> {
-        let session = SshClient::connect(&device.host, device.port, &device.auth_path())
-            .map_err(|e| DeviceError::ConnectionFailed(format!("{}", e)))?;
+        let session = match take_cached_session(device) {
+            Some(session) => session,
+            None => SshClient::connect_device(device)
+                .map_err(|e| DeviceError::ConnectionFailed(format!("{}", e)))?,
+        };
 
         Ok(Self {
             device: device.clone(),
-            session,
+            session: Some(session),
+            healthy: true,
         })
     }
 
+    fn handle(&mut self) -> &mut Handle<SshClient> {
+        self.session.as_mut().expect("session is present until drop")
+    }
+
+    /// Note a failed operation; the session is then closed instead of reused
+    fn track<T, E>(&mut self, result: Result<T, E>) -> Result<T, E> {
+        if result.is_err() {
+            self.healthy = false;
+        }
+        result
+    }
+
     /// Execute command as regular user
     ///
     /// # Arguments
@@ -66,8 +108,9 @@ impl DeviceSession {
     /// # Errors
     /// Returns an error if command execution fails or returns non-zero exit code
     pub fn exec(&mut self, command: &str) -> Result<Vec<String>> {
-        SshClient::exec(&mut self.session, command)
-            .with_context(|| format!("Failed to execute: {}", command))
+        let result = SshClient::exec(self.handle(), command)
+            .with_context(|| format!("Failed to execute: {}", command));
+        self.track(result)
     }
 
     /// Execute command as root via devel-su
@@ -94,10 +137,12 @@ impl DeviceSession {
             ));
         }
 
-        SshClient::exec_as_devel_su(&mut self.session, command, &self.device.root_password)
+        let root_password = self.device.root_password.clone();
+        let result = SshClient::exec_as_devel_su(self.handle(), command, &root_password)
             .map_err(DeviceError::SshError)
             .with_context(|| format!("Failed to execute as root: {}", command))
-            .map_err(DeviceError::SshError)
+            .map_err(DeviceError::SshError);
+        self.track(result)
     }
 
     /// Upload file to device via SFTP
@@ -109,14 +154,15 @@ impl DeviceSession {
     /// # Errors
     /// Returns an error if file upload fails
     pub fn upload_file(&mut self, local_path: &Path, remote_path: &Path) -> Result<()> {
-        SshClient::upload(&mut self.session, local_path, remote_path)
+        let result = SshClient::upload(self.handle(), local_path, remote_path)
             .with_context(|| {
                 format!(
                     "Failed to upload {} to {}",
                     local_path.display(),
                     remote_path.display()
                 )
-            })
+            });
+        self.track(result)
     }
 
     /// Read remote file contents as base64 string
@@ -140,8 +186,10 @@ impl DeviceSession {
             ));
         }
 
-        SshClient::read_file_base64(&mut self.session, remote_path, &self.device.root_password)
-            .map_err(DeviceError::SshError)
+        let root_password = self.device.root_password.clone();
+        let result = SshClient::read_file_base64(self.handle(), remote_path, &root_password)
+            .map_err(DeviceError::SshError);
+        self.track(result)
     }
 
     /// Get a reference to the underlying device configuration
@@ -154,3 +202,18 @@ impl DeviceSession {
         self.device.display_name()
     }
 }
+
+impl Drop for DeviceSession {
+    /// Return a healthy session to the cache for the next `connect`
+    fn drop(&mut self) {
+        let Some(session) = self.session.take() else {
+            return;
+        };
+        if !self.healthy || session.is_closed() {
+            return;
+        }
+        if let Ok(mut cache) = SESSION_CACHE.lock() {
+            cache.push((cache_key(&self.device), session));
+        }
+    }
+}
@@ -25,6 +25,28 @@ pub fn escape_single_quote(s: &str) -> String {
     s.replace('\'', r"'\''")
 }
 
+/// Quote a single argument so the shell passes it through unchanged.
+///
+/// Arguments made only of characters the shell never interprets are left
+/// bare, so simple commands stay readable.
+pub fn quote_arg(s: &str) -> String {
+    let is_plain = !s.is_empty()
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || "_-./=:,+@%".contains(c));
+    if is_plain {
+        s.to_string()
+    } else {
+        format!("'{}'", escape_single_quote(s))
+    }
+}
+
+/// Build a command line that preserves each argument's boundaries.
+pub fn join_args<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(|arg| quote_arg(arg.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Wrapper type for shell-escaped strings
 ///
 /// This newtype pattern ensures that strings used in shell contexts
@@ -83,6 +105,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg("ls"), "ls");
+        assert_eq!(quote_arg("--color=auto"), "--color=auto");
+        assert_eq!(quote_arg("a b"), "'a b'");
+        assert_eq!(quote_arg("$HOME"), "'$HOME'");
+        assert_eq!(quote_arg("it's"), "'it'\\''s'");
+        assert_eq!(quote_arg(""), "''");
+    }
+
+    #[test]
+    fn test_join_args_keeps_boundaries() {
+        assert_eq!(join_args(&["echo", "a b"]), "echo 'a b'");
+        assert_eq!(
+            join_args(&["ls", "/home/defaultuser/My Documents"]),
+            "ls '/home/defaultuser/My Documents'"
+        );
+    }
+
     #[test]
     fn test_shell_escaped_wrapper() {
         let escaped = ShellEscaped::single_quote("test'value");
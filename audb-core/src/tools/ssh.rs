@@ -9,16 +9,84 @@ use std::borrow::Cow;
 use std::fs::File;
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use anyhow::{anyhow, Result};
 
 use crate::tools::shell_escape::escape_single_quote;
+use crate::tools::types::Device;
 
 const DEFAULT_USER: &str = "defaultuser";
 
-pub struct SshClient {}
+/// Default cap on output collected by a single exec (32 MiB)
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Prefix of the line appended to exec output that hit the size cap
+pub const OUTPUT_TRUNCATED_MARKER: &str = "[audb: output truncated at";
+
+/// Current cap on exec output, shared by all sessions
+static MAX_OUTPUT_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_OUTPUT_BYTES);
+
+/// How long an exec keeps reading after EOF or the exit status arrives
+///
+/// Covers commands that background a process inheriting the channel: the
+/// remote side never closes it, so waiting for `Close` alone would hang.
+const EXEC_CLOSE_GRACE: Duration = Duration::from_secs(2);
+
+/// How long the TCP connect and SSH handshake may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct SshClient {
+    /// Bastion session a tunneled connection runs over; owned by the device
+    /// session's handler so the tunnel lives exactly as long as it does
+    _jump: Option<Handle<SshClient>>,
+}
+
+/// A bastion host from a device's `jump_host` (`[user@]host[:port]`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumpHost {
+    pub user: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl JumpHost {
+    /// Parse `[user@]host[:port]`; the user defaults to the local `$USER`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (user, address) = match spec.rsplit_once('@') {
+            Some((user, address)) => (user.to_string(), address),
+            None => (std::env::var("USER").unwrap_or_else(|_| "root".to_string()), spec),
+        };
+
+        // Bracketed IPv6 ([::1]:2222), else host[:port] with a single colon
+        let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| anyhow!("Invalid jump host: {}", spec))?;
+            (host, rest.strip_prefix(':'))
+        } else {
+            match address.split_once(':') {
+                Some((host, port)) if !port.contains(':') => (host, Some(port)),
+                _ => (address, None),
+            }
+        };
+
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| anyhow!("Invalid jump host port in {}", spec))?,
+            None => 22,
+        };
+        if host.is_empty() || user.is_empty() {
+            return Err(anyhow!("Invalid jump host: {}", spec));
+        }
+
+        Ok(Self { user, host: host.to_string(), port })
+    }
+}
 
 impl client::Handler for SshClient {
     type Error = russh::Error;
@@ -29,6 +97,14 @@ impl client::Handler for SshClient {
 }
 
 impl SshClient {
+    /// Limit how much output a single exec collects before it is cut off
+    ///
+    /// Protects long-running processes (e.g. the server) from commands that
+    /// produce unbounded output such as `cat /dev/zero`.
+    pub fn set_max_output_bytes(bytes: usize) {
+        MAX_OUTPUT_BYTES.store(bytes, Ordering::Relaxed);
+    }
+
     pub fn connect(
         host: &str,
         port: u16,
@@ -39,6 +115,23 @@ impl SshClient {
         })
     }
 
+    /// Connect to a configured device, through its jump host if it has one
+    pub fn connect_device(device: &Device) -> Result<Handle<SshClient>> {
+        let Some(jump) = &device.jump_host else {
+            return Self::connect(&device.host, device.port, &device.auth_path());
+        };
+        let jump = JumpHost::parse(jump)?;
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(Self::_connect_via_jump(
+                &jump,
+                &device.jump_auth_path(),
+                &device.host,
+                device.port,
+                &device.auth_path(),
+            ))
+        })
+    }
+
     pub fn exec(
         session: &mut Handle<SshClient>,
         command: &str,
@@ -48,6 +141,19 @@ impl SshClient {
         })
     }
 
+    /// Execute command with a pseudo-terminal allocated for it
+    ///
+    /// Lets tools that check for a TTY emit their interactive output (colors,
+    /// progress bars). As on a real terminal, stderr is merged into stdout.
+    pub fn exec_pty(
+        session: &mut Handle<SshClient>,
+        command: &str,
+    ) -> Result<Vec<String>> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(Self::_exec_channel(session, command, true))
+        })
+    }
+
     /// Execute command as root using devel-su (Aurora OS)
     ///
     /// Uses the `echo 'password' | devel-su sh -c 'command'` pattern to automate
@@ -67,17 +173,35 @@ impl SshClient {
             ));
         }
 
+        Self::exec(session, &Self::devel_su_command(command, password))
+    }
+
+    /// Execute command as root using devel-su with a pseudo-terminal (see `exec_pty`)
+    pub fn exec_as_devel_su_pty(
+        session: &mut Handle<SshClient>,
+        command: &str,
+        password: &str,
+    ) -> Result<Vec<String>> {
+        if password.is_empty() {
+            return Err(anyhow!(
+                "Root password not configured. Use 'audb device add' to set the root password."
+            ));
+        }
+
+        Self::exec_pty(session, &Self::devel_su_command(command, password))
+    }
+
+    /// Build the devel-su wrapper for a command
+    fn devel_su_command(command: &str, password: &str) -> String {
         // Escape password and command for single-quote context to prevent shell injection
         let password_escaped = escape_single_quote(password);
         let command_escaped = escape_single_quote(command);
 
         // Use echo pipe pattern: echo 'password' | devel-su sh -c 'command'
-        let devel_su_command = format!(
+        format!(
             "echo '{}' | devel-su sh -c '{}'",
             password_escaped, command_escaped
-        );
-
-        Self::exec(session, &devel_su_command)
+        )
     }
 
     /// Read file contents as base64 string via SSH exec
@@ -118,6 +242,51 @@ impl SshClient {
         })
     }
 
+    /// Async form of `exec`/`exec_pty`
+    ///
+    /// For callers that may abandon a command: dropping the future closes its channel.
+    pub async fn exec_async(
+        session: &mut Handle<SshClient>,
+        command: &str,
+        pty: bool,
+    ) -> Result<Vec<String>> {
+        Self::_exec_channel(session, command, pty).await
+    }
+
+    /// Async form of `exec_as_devel_su`/`exec_as_devel_su_pty`
+    pub async fn exec_as_devel_su_async(
+        session: &mut Handle<SshClient>,
+        command: &str,
+        password: &str,
+        pty: bool,
+    ) -> Result<Vec<String>> {
+        if password.is_empty() {
+            return Err(anyhow!(
+                "Root password not configured. Use 'audb device add' to set the root password."
+            ));
+        }
+
+        Self::_exec_channel(session, &Self::devel_su_command(command, password), pty).await
+    }
+
+    /// Async form of `upload`
+    pub async fn upload_async(
+        session: &mut Handle<SshClient>,
+        local_path: &Path,
+        remote_path: &Path,
+    ) -> Result<()> {
+        Self::_upload(session, local_path, remote_path).await
+    }
+
+    /// Async form of `download`
+    pub async fn download_async(
+        session: &mut Handle<SshClient>,
+        remote_path: &Path,
+        local_path: &Path,
+    ) -> Result<()> {
+        Self::_download(session, remote_path, local_path).await
+    }
+
     pub fn test_connection(
         host: &str,
         port: u16,
@@ -131,6 +300,14 @@ impl SshClient {
         }
     }
 
+    /// Like `test_connection`, for a configured device (honors its jump host)
+    pub fn test_device_connection(device: &Device) -> bool {
+        match Self::connect_device(device) {
+            Ok(mut session) => Self::exec(&mut session, "echo test").is_ok(),
+            Err(_) => false,
+        }
+    }
+
     async fn _connect(
         host: &str,
         port: u16,
@@ -139,14 +316,35 @@ impl SshClient {
         Self::_connect_with_user(DEFAULT_USER, host, port, key_path).await
     }
 
-    async fn _connect_with_user(
-        user: &str,
+    /// Two-hop connect: open a direct-tcpip channel on the bastion and run
+    /// the device's SSH session over it
+    async fn _connect_via_jump(
+        jump: &JumpHost,
+        jump_key_path: &Path,
         host: &str,
         port: u16,
         key_path: &Path,
     ) -> Result<Handle<SshClient>> {
+        let bastion = Self::_connect_with_user(&jump.user, &jump.host, jump.port, jump_key_path)
+            .await
+            .map_err(|e| anyhow!("Jump host {}: {}", jump.host, e))?;
+        let channel = bastion
+            .channel_open_direct_tcpip(host, port as u32, "127.0.0.1", 0)
+            .await
+            .map_err(|e| anyhow!("Jump host {} cannot reach {}:{}: {}", jump.host, host, port, e))?;
+
+        let handler = SshClient { _jump: Some(bastion) };
+        let session = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            client::connect_stream(Self::client_config(), channel.into_stream(), handler),
+        )
+        .await?
+        .map_err(|e| anyhow!("Connection error via {}: {}", jump.host, e))?;
+        Self::_authenticate(session, DEFAULT_USER, key_path).await
+    }
+
+    fn client_config() -> Arc<client::Config> {
         let timeout_session = Duration::from_secs(30);
-        let timeout_connect = Duration::from_secs(5);
         let config = client::Config {
             inactivity_timeout: Some(timeout_session),
             preferred: Preferred {
@@ -158,13 +356,29 @@ impl SshClient {
             },
             ..<_>::default()
         };
-        let config = Arc::new(config);
-        let sh = SshClient {};
-        let mut session = match tokio::time::timeout(timeout_connect, client::connect(config, (host, port), sh)).await?
+        Arc::new(config)
+    }
+
+    async fn _connect_with_user(
+        user: &str,
+        host: &str,
+        port: u16,
+        key_path: &Path,
+    ) -> Result<Handle<SshClient>> {
+        let sh = SshClient { _jump: None };
+        let session = match tokio::time::timeout(CONNECT_TIMEOUT, client::connect(Self::client_config(), (host, port), sh)).await?
         {
             Ok(session) => session,
             Err(err) => return Err(anyhow!("Connection error: {}", err)),
         };
+        Self::_authenticate(session, user, key_path).await
+    }
+
+    async fn _authenticate(
+        mut session: Handle<SshClient>,
+        user: &str,
+        key_path: &Path,
+    ) -> Result<Handle<SshClient>> {
         let secret_key = Arc::new(russh::keys::load_secret_key(key_path, None)?);
         let key_pair = PrivateKeyWithHashAlg::new(secret_key, session.best_supported_rsa_hash().await?.flatten());
         let result = session.authenticate_publickey(user, key_pair).await?;
@@ -178,43 +392,31 @@ impl SshClient {
         session: &mut Handle<SshClient>,
         command: &str,
     ) -> Result<Vec<String>> {
-        let mut code = None;
-        let mut stdout: Vec<String> = vec![];
-        let mut stderr: Vec<String> = vec![];
+        Self::_exec_channel(session, command, false).await
+    }
+
+    async fn _exec_channel(
+        session: &mut Handle<SshClient>,
+        command: &str,
+        pty: bool,
+    ) -> Result<Vec<String>> {
+        let max_output = MAX_OUTPUT_BYTES.load(Ordering::Relaxed);
         let mut channel = session.channel_open_session().await?;
+        if pty {
+            channel.request_pty(false, "xterm", 80, 24, 0, 0, &[]).await?;
+        }
         channel.exec(true, command).await?;
-        loop {
-            let Some(msg) = channel.wait().await else {
-                break;
-            };
-            match msg {
-                ChannelMsg::Data { ref data } => {
-                    match str::from_utf8(data.as_ref()) {
-                        Ok(out_line) => {
-                            let line = out_line.trim().to_string();
-                            stdout.push(line)
-                        },
-                        Err(_) => return Err(anyhow!("Failed to process SSH connection data")),
-                    };
-                }
-                ChannelMsg::ExtendedData { ref data, ext } => {
-                    // ext == 1 means stderr
-                    if ext == 1 {
-                        match str::from_utf8(data.as_ref()) {
-                            Ok(err_line) => {
-                                let line = err_line.trim().to_string();
-                                stderr.push(line)
-                            },
-                            Err(_) => return Err(anyhow!("Failed to process SSH stderr data")),
-                        };
-                    }
-                }
-                ChannelMsg::ExitStatus { exit_status } => {
-                    code = Some(exit_status);
-                }
-                _ => {}
-            }
+
+        let output = collect_exec_output(&mut channel, max_output, EXEC_CLOSE_GRACE).await;
+        if output.truncated {
+            // Stop reading and return what was captured so far
+            channel.close().await.ok();
+            let mut stdout = output.stdout;
+            stdout.push(format!("{} {} bytes]", OUTPUT_TRUNCATED_MARKER, max_output));
+            return Ok(stdout);
         }
+
+        let ExecOutput { stdout, stderr, code, .. } = output;
         if let Some(code) = code {
             if code != 0 {
                 let error_msg = if !stderr.is_empty() {
@@ -292,3 +494,227 @@ impl SshClient {
         Ok(SftpSession::new(channel.into_stream()).await?)
     }
 }
+
+/// Source of exec channel messages (a mock stands in for SSH in tests)
+trait ChannelMessages {
+    async fn next_msg(&mut self) -> Option<ChannelMsg>;
+}
+
+impl ChannelMessages for russh::Channel<client::Msg> {
+    async fn next_msg(&mut self) -> Option<ChannelMsg> {
+        self.wait().await
+    }
+}
+
+/// Everything an exec produced
+struct ExecOutput {
+    stdout: Vec<String>,
+    stderr: Vec<String>,
+    /// None if the channel ended without reporting one (treated as success)
+    code: Option<u32>,
+    /// Output passed `max_output` bytes and reading stopped early
+    truncated: bool,
+}
+
+/// Read an exec channel until it is done
+///
+/// Done means `Close`, the end of the message stream, or both `Eof` and the
+/// exit status. Once either of the last two has arrived, the rest must follow
+/// within `grace`, otherwise whatever was collected is returned.
+async fn collect_exec_output(
+    channel: &mut impl ChannelMessages,
+    max_output: usize,
+    grace: Duration,
+) -> ExecOutput {
+    let mut output = ExecOutput {
+        stdout: vec![],
+        stderr: vec![],
+        code: None,
+        truncated: false,
+    };
+    let mut output_bytes: usize = 0;
+    let mut eof = false;
+    // Bytes of a UTF-8 sequence split across data chunks
+    let mut stdout_pending: Vec<u8> = vec![];
+    let mut stderr_pending: Vec<u8> = vec![];
+
+    loop {
+        let msg = if eof || output.code.is_some() {
+            match tokio::time::timeout(grace, channel.next_msg()).await {
+                Ok(msg) => msg,
+                Err(_) => break,
+            }
+        } else {
+            channel.next_msg().await
+        };
+        let Some(msg) = msg else {
+            break;
+        };
+
+        if let ChannelMsg::Data { ref data } | ChannelMsg::ExtendedData { ref data, .. } = msg {
+            output_bytes += data.len();
+            if output_bytes > max_output {
+                output.truncated = true;
+                return output;
+            }
+        }
+
+        match msg {
+            ChannelMsg::Data { ref data } => {
+                output.stdout.push(decode_chunk(&mut stdout_pending, data.as_ref()).trim().to_string());
+            }
+            ChannelMsg::ExtendedData { ref data, ext } => {
+                // ext == 1 means stderr
+                if ext == 1 {
+                    output.stderr.push(decode_chunk(&mut stderr_pending, data.as_ref()).trim().to_string());
+                }
+            }
+            ChannelMsg::ExitStatus { exit_status } => {
+                output.code = Some(exit_status);
+                if eof {
+                    break;
+                }
+            }
+            ChannelMsg::Eof => {
+                eof = true;
+                if output.code.is_some() {
+                    break;
+                }
+            }
+            ChannelMsg::Close => break,
+            _ => {}
+        }
+    }
+
+    // Flush a multi-byte sequence the command never completed
+    if !stdout_pending.is_empty() {
+        output.stdout.push(String::from_utf8_lossy(&stdout_pending).trim().to_string());
+    }
+    if !stderr_pending.is_empty() {
+        output.stderr.push(String::from_utf8_lossy(&stderr_pending).trim().to_string());
+    }
+    output
+}
+
+/// Decode a chunk of command output, replacing invalid UTF-8 instead of failing
+///
+/// A multi-byte character cut off at the end of the chunk is held back in
+/// `pending` and decoded together with the next chunk.
+fn decode_chunk(pending: &mut Vec<u8>, data: &[u8]) -> String {
+    pending.extend_from_slice(data);
+    let tail = pending.split_off(pending.len() - incomplete_utf8_tail(pending));
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = tail;
+    text
+}
+
+/// Length of an unfinished UTF-8 sequence at the end of `bytes` (0 if none)
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - len];
+        if byte & 0xC0 == 0x80 {
+            // Continuation byte, keep looking for the lead byte
+            continue;
+        }
+        let expected = match byte {
+            0xF0..=0xF7 => 4,
+            0xE0..=0xEF => 3,
+            0xC0..=0xDF => 2,
+            _ => 1,
+        };
+        return if expected > len { len } else { 0 };
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_host_parse() {
+        assert_eq!(
+            JumpHost::parse("admin@gw.lab:2222").unwrap(),
+            JumpHost { user: "admin".to_string(), host: "gw.lab".to_string(), port: 2222 }
+        );
+        let jump = JumpHost::parse("ops@10.0.0.1").unwrap();
+        assert_eq!((jump.host.as_str(), jump.port), ("10.0.0.1", 22));
+        let jump = JumpHost::parse("ops@[fe80::1]:2200").unwrap();
+        assert_eq!((jump.host.as_str(), jump.port), ("fe80::1", 2200));
+        assert!(JumpHost::parse("ops@gw:notaport").is_err());
+        assert!(JumpHost::parse("ops@").is_err());
+    }
+
+    #[test]
+    fn test_decode_chunk_replaces_invalid_bytes() {
+        let mut pending = vec![];
+        assert_eq!(decode_chunk(&mut pending, b"caf\xe9 ok\n"), "caf\u{FFFD} ok\n");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_decode_chunk_joins_split_character() {
+        let mut pending = vec![];
+        let bytes = "привет".as_bytes();
+        let first = decode_chunk(&mut pending, &bytes[..3]);
+        let second = decode_chunk(&mut pending, &bytes[3..]);
+        assert_eq!(format!("{}{}", first, second), "привет");
+        assert!(pending.is_empty());
+    }
+
+    /// Replays queued messages, then stays open without sending anything
+    struct MockChannel(std::collections::VecDeque<ChannelMsg>);
+
+    impl ChannelMessages for MockChannel {
+        async fn next_msg(&mut self) -> Option<ChannelMsg> {
+            match self.0.pop_front() {
+                Some(msg) => Some(msg),
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    fn data(text: &str) -> ChannelMsg {
+        ChannelMsg::Data { data: text.into() }
+    }
+
+    #[tokio::test]
+    async fn test_collect_returns_after_eof_without_exit_status() {
+        let mut channel = MockChannel([data("hello\n"), ChannelMsg::Eof].into());
+        let output = collect_exec_output(&mut channel, usize::MAX, Duration::from_millis(10)).await;
+        assert_eq!(output.stdout, vec!["hello"]);
+        assert_eq!(output.code, None);
+        assert!(!output.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_collect_keeps_exit_status_sent_after_eof() {
+        let mut channel = MockChannel([data("oops\n"), ChannelMsg::Eof, ChannelMsg::ExitStatus { exit_status: 3 }].into());
+        let output = collect_exec_output(&mut channel, usize::MAX, Duration::from_secs(3600)).await;
+        assert_eq!(output.code, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_collect_returns_when_background_process_holds_channel() {
+        let mut channel = MockChannel([data("started\n"), ChannelMsg::ExitStatus { exit_status: 0 }].into());
+        let output = collect_exec_output(&mut channel, usize::MAX, Duration::from_millis(10)).await;
+        assert_eq!(output.stdout, vec!["started"]);
+        assert_eq!(output.code, Some(0));
+    }
+
+    #[test]
+    fn test_devel_su_command_escapes_password() {
+        assert_eq!(
+            SshClient::devel_su_command("id", "pa'ss; reboot"),
+            r"echo 'pa'\''ss; reboot' | devel-su sh -c 'id'"
+        );
+    }
+
+    #[test]
+    fn test_devel_su_command_escapes_command() {
+        assert_eq!(
+            SshClient::devel_su_command("echo 'hi'", "secret"),
+            r"echo 'secret' | devel-su sh -c 'echo '\''hi'\'''"
+        );
+    }
+}
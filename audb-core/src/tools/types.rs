@@ -10,13 +10,25 @@ pub struct Device {
     #[serde(default = "default_port")]
     pub port: u16,
     pub auth: String,
-    /// Root password for devel-su (stored for potential future use)
-    /// NOTE: Root automation is not yet implemented - see ssh.rs::exec_as_devel_su
+    /// Root password piped to devel-su for root commands
+    /// (always shell-escaped, see `SshClient::exec_as_devel_su`)
     #[serde(default = "default_root_password")]
     pub root_password: String,
     pub platform: Platform,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Hardware model reported by the device, cached on add or first `info`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// When a connection to the device last succeeded (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_connected: Option<String>,
+    /// Bastion the SSH connection is tunneled through, as `[user@]host[:port]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_host: Option<String>,
+    /// Private key for the jump host (default: `auth`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_auth: Option<String>,
 }
 
 fn default_port() -> u16 {
@@ -31,11 +43,47 @@ fn default_root_password() -> String {
     String::new()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Platform {
     AuroraArm,
     AuroraArm64,
+    /// SDK emulator (32-bit)
+    AuroraX86,
+    /// SDK emulator (64-bit)
+    #[serde(rename = "aurora-x86_64")]
+    AuroraX86_64,
+}
+
+impl Platform {
+    /// All platforms, in the order offered by `device add`
+    pub const ALL: [Platform; 4] = [
+        Platform::AuroraArm,
+        Platform::AuroraArm64,
+        Platform::AuroraX86,
+        Platform::AuroraX86_64,
+    ];
+
+    /// RPM architecture built for this platform (the `RPMS/<arch>` directory name)
+    pub fn rpm_arch(&self) -> &'static str {
+        match self {
+            Platform::AuroraArm => "armv7hl",
+            Platform::AuroraArm64 => "aarch64",
+            Platform::AuroraX86 => "i486",
+            Platform::AuroraX86_64 => "x86_64",
+        }
+    }
+
+    /// Map a `uname -m` machine name to a platform
+    pub fn from_machine(machine: &str) -> Option<Self> {
+        match machine.trim() {
+            "aarch64" | "arm64" => Some(Platform::AuroraArm64),
+            "x86_64" => Some(Platform::AuroraX86_64),
+            "i386" | "i486" | "i586" | "i686" => Some(Platform::AuroraX86),
+            m if m.starts_with("arm") => Some(Platform::AuroraArm),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for Platform {
@@ -43,6 +91,8 @@ impl std::fmt::Display for Platform {
         match self {
             Platform::AuroraArm => write!(f, "aurora-arm"),
             Platform::AuroraArm64 => write!(f, "aurora-arm64"),
+            Platform::AuroraX86 => write!(f, "aurora-x86"),
+            Platform::AuroraX86_64 => write!(f, "aurora-x86_64"),
         }
     }
 }
@@ -81,6 +131,12 @@ impl Device {
     pub fn auth_path(&self) -> PathBuf {
         PathBuf::from(shellexpand::tilde(&self.auth).to_string())
     }
+
+    /// Key for the jump host, falling back to the device's own key
+    pub fn jump_auth_path(&self) -> PathBuf {
+        let auth = self.jump_auth.as_deref().unwrap_or(&self.auth);
+        PathBuf::from(shellexpand::tilde(auth).to_string())
+    }
 }
 
 /// Log level for journalctl filtering (Android/iOS style + journalctl native)
@@ -104,7 +160,7 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    pub fn to_journalctl_priority(&self) -> &str {
+    pub fn to_journalctl_priority(&self) -> &'static str {
         match self {
             Self::V | Self::D | Self::Debug => "debug",
             Self::I | Self::Info => "info",
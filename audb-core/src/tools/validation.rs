@@ -14,6 +14,17 @@ pub fn validate_port(port: u16) -> Result<()> {
     Ok(())
 }
 
+/// Check a device user name before it is put on a command line
+pub fn validate_user_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+    if !valid {
+        return Err(anyhow!("Invalid user name: {}", name));
+    }
+    Ok(())
+}
+
 pub fn validate_ssh_key_exists(path: &Path) -> Result<()> {
     if !path.exists() {
         return Err(anyhow!("SSH key file does not exist: {}", path.display()));
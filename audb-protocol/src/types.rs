@@ -23,13 +23,53 @@ pub enum Command {
     Shell {
         device: String,
         root: bool,
+        /// Allocate a pseudo-terminal (stdout and stderr are merged)
+        #[serde(default)]
+        pty: bool,
         command: String,
+        /// Argument vector; when non-empty it replaces `command`, with each
+        /// argument quoted so the device shell sees the same boundaries
+        #[serde(default)]
+        args: Vec<String>,
+        /// Run as this device user (gains root through devel-su, then switches)
+        #[serde(default)]
+        run_as: Option<String>,
+        /// Append a `real: X.XXs` line with the command's wall-clock duration
+        #[serde(default)]
+        time: bool,
+        /// Script to upload and run with `/bin/sh`, replacing `command` and
+        /// `args`; sent verbatim so newlines and quoting survive
+        #[serde(default)]
+        script: Option<String>,
+        /// Run through a login shell (`bash -lc`) so the profile sets up the
+        /// environment; slower to start than the default direct exec
+        #[serde(default)]
+        login: bool,
+        /// Keep only the first N output lines (`| head -n N` on the device)
+        #[serde(default)]
+        head: Option<usize>,
+        /// Keep only the last N output lines (`| tail -n N` on the device)
+        #[serde(default)]
+        tail: Option<usize>,
     },
     /// Install RPM package on device
     Install {
         device: String,
         rpm_path: String,
         rpm_data: Vec<u8>,
+        /// Check the package's requirements are installed before installing
+        #[serde(default)]
+        check_deps: bool,
+        /// Tool that performs the install
+        #[serde(default)]
+        backend: InstallBackend,
+    },
+    /// Dry-run install of an RPM on the device (`rpm -U --test` as root)
+    /// to catch dependency and conflict problems without changing anything
+    Validate {
+        device: String,
+        rpm_path: String,
+        rpm_data: Vec<u8>,
     },
     /// Tap at coordinates on device
     Tap {
@@ -40,6 +80,23 @@ pub enum Command {
         event_device: Option<String>,
         /// Optional: duration in milliseconds for long press (default: 30ms)
         duration_ms: Option<u32>,
+        /// Screen orientation the coordinates refer to (default: detect)
+        #[serde(default)]
+        orientation: ScreenOrientation,
+        /// Clamp out-of-screen coordinates (with a warning) instead of failing
+        #[serde(default)]
+        clamp: bool,
+    },
+    /// On-screen UI elements from the accessibility (AT-SPI) tree: all of
+    /// them without a query, else those whose name matches it
+    FindElement {
+        device: String,
+        /// Element name (label) to look for; exact case-insensitive matches
+        /// come first, then names containing it
+        query: Option<String>,
+        /// Only walk applications whose name contains this
+        #[serde(default)]
+        app: Option<String>,
     },
     /// Swipe gesture on device
     Swipe {
@@ -47,6 +104,29 @@ pub enum Command {
         mode: SwipeMode,
         /// Optional: direct evdev device path for fast mode
         event_device: Option<String>,
+        /// Easing of the finger movement (default: linear)
+        #[serde(default)]
+        profile: SwipeProfile,
+        /// Optional: total move duration in milliseconds
+        #[serde(default)]
+        duration_ms: Option<u32>,
+        /// Screen orientation the coordinates refer to (default: detect)
+        #[serde(default)]
+        orientation: ScreenOrientation,
+        /// Clamp out-of-screen coordinates (with a warning) instead of failing
+        #[serde(default)]
+        clamp: bool,
+    },
+    /// Two-finger pinch gesture on device
+    Pinch {
+        device: String,
+        direction: PinchDirection,
+        /// Optional: pinch center (default: screen center)
+        center: Option<(u16, u16)>,
+        /// Optional: finger travel span in pixels
+        distance: Option<u16>,
+        /// Optional: direct evdev device path for fast mode
+        event_device: Option<String>,
     },
     /// Send key event (back, home, power, volume, etc.)
     Key {
@@ -55,15 +135,40 @@ pub enum Command {
         key_name: String,
     },
     /// Take screenshot of device
-    Screenshot { device: String },
+    Screenshot {
+        device: String,
+        /// Display to capture, numbered from 0 (None = primary)
+        #[serde(default)]
+        display: Option<u32>,
+    },
     /// Launch application on device
-    Launch { device: String, app_name: String },
+    Launch {
+        device: String,
+        app_name: String,
+        /// Command-line arguments for the app
+        #[serde(default)]
+        args: Vec<String>,
+        /// URI to open in the app (deep link)
+        #[serde(default)]
+        uri: Option<String>,
+    },
     /// Stop application on device
-    Stop { device: String, app_name: String },
+    Stop {
+        device: String,
+        app_name: String,
+        /// SIGKILL the app if it is still running once the timeout has passed
+        #[serde(default)]
+        force: bool,
+        /// Seconds to wait for a graceful exit before force-killing
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
     /// Retrieve device logs
     Logs { device: String, args: LogsArgs },
     /// Uninstall package from device
     Uninstall { device: String, package_name: String },
+    /// Uninstall several packages, continuing past individual failures
+    UninstallMany { device: String, package_names: Vec<String> },
     /// List installed packages on device
     Packages {
         device: String,
@@ -77,30 +182,126 @@ pub enum Command {
         remote_path: String,
         /// File data (binary)
         data: Vec<u8>,
+        /// Create missing parent directories of `remote_path`
+        #[serde(default)]
+        mkdirs: bool,
     },
     /// Pull file from device
     Pull {
         device: String,
         remote_path: String,
+        /// Pull a whole directory as one gzip-compressed tarball
+        #[serde(default)]
+        tar: bool,
+    },
+    /// Read a remote text file and return its contents as lines
+    Cat {
+        device: String,
+        remote_path: String,
+        /// Read the file as root (devel-su)
+        as_root: bool,
     },
     /// Get device information
     Info {
         device: String,
-        /// Info category: device, cpu, memory, battery, storage, features, sim (None = all)
+        /// Info category: device, cpu, memory, battery, storage, features, thermal, system, sim (None = all)
         category: Option<String>,
     },
     /// Get server status
     ServerStatus,
+    /// Get aggregate command metrics since server start
+    Metrics,
     /// Shutdown server
     KillServer,
+    /// Re-read the device config, adding newly enabled devices to the pool
+    /// and removing disabled or deleted ones
+    ReloadDevices,
     /// Force reconnection to device(s)
     Reconnect { device: Option<String> },
+    /// Probe the device's existing connection right away
+    HealthCheck { device: String },
+    /// Abort an in-flight request (sent on a separate connection, e.g. on Ctrl-C)
+    Cancel { request_id: u64 },
+    /// Recent connection state transitions of a device
+    DeviceHistory { device: String },
     /// Open URL on device (browser, file, etc.)
     Open {
         device: String,
         /// URL to open (https://, file://, tel:, etc.)
         url: String,
     },
+    /// Read-only convenience check with formatted output
+    Builtin { device: String, builtin: Builtin },
+    /// Run a set of probes for a problem area and summarize the findings
+    Diagnose { device: String, topic: DiagTopic },
+    /// Switch a radio (or airplane mode) on or off through ConnMan
+    Radio {
+        device: String,
+        radio: Radio,
+        /// true = on
+        enabled: bool,
+        /// Proceed even if this cuts the connection audb is using
+        #[serde(default)]
+        force: bool,
+    },
+    /// Start, stop, restart or report on a systemd unit
+    Service {
+        device: String,
+        action: ServiceAction,
+        unit: String,
+        /// Use the session user's manager (`systemctl --user`) instead of the system one
+        #[serde(default)]
+        user: bool,
+    },
+    /// Push a local file to the device's Downloads folder and open it
+    OpenFile {
+        device: String,
+        local_path: String,
+        /// File data (binary)
+        data: Vec<u8>,
+    },
+}
+
+impl Command {
+    /// Short name of the command type (used for metrics)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Shell { .. } => "shell",
+            Command::Install { .. } => "install",
+            Command::Validate { .. } => "validate",
+            Command::Tap { .. } => "tap",
+            Command::FindElement { .. } => "find-element",
+            Command::Swipe { .. } => "swipe",
+            Command::Pinch { .. } => "pinch",
+            Command::Key { .. } => "key",
+            Command::Screenshot { .. } => "screenshot",
+            Command::Launch { .. } => "launch",
+            Command::Stop { .. } => "stop",
+            Command::Logs { .. } => "logs",
+            Command::Uninstall { .. } => "uninstall",
+            Command::UninstallMany { .. } => "uninstall-many",
+            Command::Packages { .. } => "packages",
+            Command::Push { .. } => "push",
+            Command::Pull { .. } => "pull",
+            Command::Cat { .. } => "cat",
+            Command::Info { .. } => "info",
+            Command::Ping => "ping",
+            Command::ServerStatus => "server-status",
+            Command::Metrics => "metrics",
+            Command::KillServer => "kill-server",
+            Command::ReloadDevices => "reload-devices",
+            Command::Reconnect { .. } => "reconnect",
+            Command::HealthCheck { .. } => "health-check",
+            Command::Cancel { .. } => "cancel",
+            Command::DeviceHistory { .. } => "device-history",
+            Command::Open { .. } => "open",
+            Command::OpenFile { .. } => "open-file",
+            Command::Builtin { builtin, .. } => builtin.name(),
+            Command::Diagnose { .. } => "diag",
+            Command::Service { .. } => "service",
+            Command::Radio { .. } => "radio",
+        }
+    }
 }
 
 /// Swipe mode (coordinates or direction)
@@ -119,6 +320,157 @@ pub enum SwipeDirection {
     Down,
 }
 
+/// Swipe velocity profile
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SwipeProfile {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starts slow, speeds up
+    EaseIn,
+    /// Starts fast, slows down before lifting
+    EaseOut,
+    /// Sharp acceleration, lifts at full speed (triggers momentum scrolling)
+    Fling,
+}
+
+/// Routine read-only checks, so they don't need `audb shell`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Builtin {
+    /// Memory and swap usage (like `free -h`)
+    Free,
+    /// Device clock and its offset from the host
+    Date,
+    /// Usage of mounted block-device filesystems (like `df -h`)
+    Df,
+}
+
+impl Builtin {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Free => "free",
+            Builtin::Date => "date",
+            Builtin::Df => "df",
+        }
+    }
+}
+
+/// Radio switched by `audb radio`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Radio {
+    Wifi,
+    Bluetooth,
+    /// ConnMan offline mode: every radio, cellular included
+    Airplane,
+}
+
+impl Radio {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Radio::Wifi => "wifi",
+            Radio::Bluetooth => "bluetooth",
+            Radio::Airplane => "airplane",
+        }
+    }
+}
+
+/// What `audb service` does with a systemd unit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    /// Active and enabled state plus recent journal lines (read-only)
+    Status,
+}
+
+impl ServiceAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+            ServiceAction::Status => "status",
+        }
+    }
+}
+
+/// How a package is installed on the device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum InstallBackend {
+    /// APM D-Bus `ru.omp.APM.Install` as the regular user
+    #[default]
+    Apm,
+    /// `pkcon install-local` as root
+    Pkcon,
+    /// `rpm -U` as root
+    Rpm,
+}
+
+impl InstallBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstallBackend::Apm => "apm",
+            InstallBackend::Pkcon => "pkcon",
+            InstallBackend::Rpm => "rpm",
+        }
+    }
+}
+
+/// Problem area checked by `audb diag`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum DiagTopic {
+    /// Input injection used by tap/swipe/pinch (uinput, touchscreen, permissions)
+    Input,
+}
+
+impl DiagTopic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagTopic::Input => "input",
+        }
+    }
+}
+
+/// Screen orientation used to map tap/swipe coordinates to the touchscreen
+///
+/// Coordinates are given as the user sees the screen. The touchscreen is
+/// portrait-native (`XMAX` x `YMAX`), so in landscape a point (x, y) is
+/// injected at (XMAX - y, x), in inverted portrait at (XMAX - x, YMAX - y) and
+/// in inverted landscape at (y, YMAX - x).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ScreenOrientation {
+    /// Ask the device; no transform if it cannot tell
+    #[default]
+    Auto,
+    Portrait,
+    Landscape,
+    InvertedPortrait,
+    InvertedLandscape,
+}
+
+impl ScreenOrientation {
+    /// Name understood by the tap/swipe scripts (None for `Auto`)
+    pub fn script_name(&self) -> Option<&'static str> {
+        match self {
+            ScreenOrientation::Auto => None,
+            ScreenOrientation::Portrait => Some("portrait"),
+            ScreenOrientation::Landscape => Some("landscape"),
+            ScreenOrientation::InvertedPortrait => Some("inverted-portrait"),
+            ScreenOrientation::InvertedLandscape => Some("inverted-landscape"),
+        }
+    }
+}
+
+/// Pinch direction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PinchDirection {
+    /// Fingers move together (zoom out)
+    In,
+    /// Fingers move apart (zoom in)
+    Out,
+}
+
 /// Log retrieval arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogsArgs {
@@ -130,6 +482,24 @@ pub struct LogsArgs {
     pub clear: bool,
     pub force: bool,
     pub kernel: bool,
+    /// End the output with a `-- cursor: ...` line (used to follow logs)
+    #[serde(default)]
+    pub show_cursor: bool,
+    /// Only return entries after this journal cursor
+    #[serde(default)]
+    pub after_cursor: Option<String>,
+    /// Only return entries containing this text (used by `logs --wait-for`)
+    #[serde(default)]
+    pub wait_for: Option<String>,
+    /// Treat `wait_for` as an extended regular expression instead of literal text
+    #[serde(default)]
+    pub regex: bool,
+    /// Only entries from the current boot (`journalctl -b 0`)
+    #[serde(default)]
+    pub since_boot: bool,
+    /// journalctl output format (`-o`), e.g. `short-monotonic` or `cat`
+    #[serde(default)]
+    pub output: Option<String>,
 }
 
 /// Result of command execution
@@ -145,7 +515,12 @@ pub enum CommandOutput {
     Lines(Vec<String>),
     Binary(Vec<u8>),
     Status(ServerStatus),
-    DeviceInfo(DeviceInfo),
+    Metrics(ServerMetrics),
+    DeviceInfo(Box<DeviceInfo>),
+    /// Connection state transitions, oldest first
+    History(Vec<StateTransition>),
+    /// Accessibility tree elements, in tree order
+    Elements(Vec<UiElement>),
     Unit,
 }
 
@@ -173,6 +548,77 @@ pub struct DeviceInfo {
     pub frontal_camera_mp: f64,
     pub internal_storage_total_mb: u64,
     pub internal_storage_free_mb: u64,
+    /// Hottest CPU thermal zone in °C (None if the device exposes no zones)
+    #[serde(default)]
+    pub cpu_temp_c: Option<f64>,
+    /// Battery temperature in °C (None if not reported by the power supply)
+    #[serde(default)]
+    pub battery_temp_c: Option<f64>,
+    /// Seconds since boot
+    #[serde(default)]
+    pub uptime_secs: u64,
+    /// 1, 5 and 15 minute load averages
+    #[serde(default)]
+    pub load_avg: [f64; 3],
+    /// Attached USB devices as "vendor:product description" (root hubs excluded)
+    #[serde(default)]
+    pub usb_devices: Vec<String>,
+    /// Paired or connected Bluetooth devices (empty without Bluetooth)
+    #[serde(default)]
+    pub bluetooth_devices: Vec<BluetoothDevice>,
+    /// Every writable disk filesystem (root, /home, SD card, ...)
+    #[serde(default)]
+    pub storage_mounts: Vec<StorageMount>,
+    /// Kernel release (`uname -r`)
+    #[serde(default)]
+    pub kernel_version: String,
+    /// OS build from /etc/os-release (`BUILD_ID`, else `VERSION_ID`)
+    #[serde(default)]
+    pub build_id: String,
+    /// SoC/board revision (empty if the device does not report one)
+    #[serde(default)]
+    pub hardware_revision: String,
+    /// Developer mode is active (`devel-su` is installed); None if unknown
+    #[serde(default)]
+    pub developer_mode: Option<bool>,
+    /// The SSH daemon (socket or service unit) starts at boot
+    #[serde(default)]
+    pub ssh_enabled: Option<bool>,
+    /// The SSH daemon (socket or service unit) is running
+    #[serde(default)]
+    pub ssh_running: Option<bool>,
+    /// Port the SSH daemon is configured to listen on
+    #[serde(default)]
+    pub ssh_port: Option<u16>,
+    /// Device wall-clock time when the info was read (`YYYY-MM-DD HH:MM:SS`)
+    #[serde(default)]
+    pub device_time: String,
+    /// Timezone name and UTC offset, e.g. `Europe/Moscow (MSK, +0300)`
+    #[serde(default)]
+    pub timezone: String,
+    /// Device clock minus host clock in milliseconds; None if it could not be measured
+    #[serde(default)]
+    pub clock_offset_ms: Option<i64>,
+    /// Round trip of the clock read; the offset is accurate to about half of it
+    #[serde(default)]
+    pub clock_rtt_ms: Option<u64>,
+}
+
+/// Size and free space of a mounted filesystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMount {
+    pub mount: String,
+    pub total_mb: u64,
+    pub free_mb: u64,
+}
+
+/// A Bluetooth device known to BlueZ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BluetoothDevice {
+    pub address: String,
+    pub name: String,
+    pub paired: bool,
+    pub connected: bool,
 }
 
 /// Server status information
@@ -184,6 +630,66 @@ pub struct ServerStatus {
     pub devices: Vec<DeviceStatus>,
 }
 
+/// Aggregate command metrics since server start
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMetrics {
+    pub uptime_secs: u64,
+    pub total_commands: u64,
+    pub failed_commands: u64,
+    /// Per-command-type counters, sorted by command name
+    pub commands: Vec<CommandMetrics>,
+}
+
+/// Counters for a single command type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub name: String,
+    pub count: u64,
+    pub failed: u64,
+}
+
+/// An on-screen element from the accessibility tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiElement {
+    /// D-Bus address of the element (`bus-name:object-path`), stable while
+    /// its application runs
+    pub id: String,
+    /// Nesting level; applications are at depth 0
+    pub depth: u32,
+    /// AT-SPI role name (e.g. "push button", "label")
+    pub role: String,
+    /// Accessible name, usually the visible label
+    pub name: String,
+    /// Screen rectangle; None if the element reports no geometry
+    pub bounds: Option<ElementBounds>,
+}
+
+/// Screen rectangle of a UI element
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ElementBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ElementBounds {
+    /// Center point, where a tap on the element lands
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+/// One recorded change of a device's connection state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+}
+
 /// Device connection status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceStatus {
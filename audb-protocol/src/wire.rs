@@ -1,52 +1,151 @@
+use crate::types::{Command, CommandOutput, CommandResult, Request, Response};
 use anyhow::{anyhow, Result};
 use serde::{de::DeserializeOwned, Serialize};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+/// Largest frame accepted from the peer (100MB)
+const MAX_FRAME_SIZE: usize = 100 * 1024 * 1024;
+
+/// Set in the length prefix when a raw binary body frame follows the JSON header
+///
+/// Frames never reach 2GB, so the bit is free and messages without a body keep
+/// the plain `[len][json]` layout.
+const BODY_FLAG: u32 = 1 << 31;
+
+/// Messages carrying bulk binary data that travels outside the JSON header
+///
+/// JSON encodes a `Vec<u8>` as an array of numbers (up to four bytes per byte),
+/// so the payload is moved into its own length-prefixed frame instead.
+pub trait BinaryBody {
+    /// The binary payload of this message, if it has one
+    fn body_mut(&mut self) -> Option<&mut Vec<u8>>;
+}
+
+impl BinaryBody for Request {
+    fn body_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match &mut self.command {
+            Command::Push { data, .. } | Command::OpenFile { data, .. } => Some(data),
+            Command::Install { rpm_data, .. } | Command::Validate { rpm_data, .. } => Some(rpm_data),
+            _ => None,
+        }
+    }
+}
+
+impl BinaryBody for Response {
+    fn body_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match &mut self.result {
+            CommandResult::Success { output: CommandOutput::Binary(data) } => Some(data),
+            _ => None,
+        }
+    }
+}
+
 /// Send a message over an async stream with length framing
-pub async fn send_message<T: Serialize, W: AsyncWriteExt + Unpin>(
+///
+/// Layout: `[len][json]`, followed by `[len][raw bytes]` when the length
+/// prefix carries `BODY_FLAG`.
+pub async fn send_message<T: Serialize + BinaryBody, W: AsyncWriteExt + Unpin>(
     writer: &mut W,
-    msg: &T,
+    msg: T,
 ) -> Result<()> {
-    let json = serde_json::to_vec(msg)?;
-    let len = json.len() as u32;
+    EncodedMessage::new(msg)?.write_to(writer).await
+}
 
-    // Write length prefix (4 bytes, little-endian)
-    writer.write_all(&len.to_le_bytes()).await?;
+/// A message serialized once, so it can be written again (e.g. on a retry)
+/// without copying its binary body
+pub struct EncodedMessage {
+    /// JSON header with its length prefix
+    header: Vec<u8>,
+    body: Option<Vec<u8>>,
+}
 
-    // Write JSON payload
-    writer.write_all(&json).await?;
-    writer.flush().await?;
+impl EncodedMessage {
+    pub fn new<T: Serialize + BinaryBody>(mut msg: T) -> Result<Self> {
+        let body = msg.body_mut().map(std::mem::take);
+        let json = serde_json::to_vec(&msg)?;
+        check_frame_size(json.len())?;
+        if let Some(body) = &body {
+            check_frame_size(body.len())?;
+        }
 
-    Ok(())
+        let mut len = json.len() as u32;
+        if body.is_some() {
+            len |= BODY_FLAG;
+        }
+
+        // Length prefix (4 bytes, little-endian) followed by the JSON header
+        let mut header = Vec::with_capacity(4 + json.len());
+        header.extend_from_slice(&len.to_le_bytes());
+        header.extend_from_slice(&json);
+
+        Ok(Self { header, body })
+    }
+
+    /// Write the message to a stream
+    pub async fn write_to<W: AsyncWriteExt + Unpin>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(&self.header).await?;
+
+        // Write the binary body as-is
+        if let Some(body) = &self.body {
+            writer.write_all(&(body.len() as u32).to_le_bytes()).await?;
+            writer.write_all(body).await?;
+        }
+        writer.flush().await?;
+
+        Ok(())
+    }
 }
 
 /// Receive a message from an async stream with length framing
-pub async fn recv_message<T: DeserializeOwned, R: AsyncReadExt + Unpin>(
+pub async fn recv_message<T: DeserializeOwned + BinaryBody, R: AsyncReadExt + Unpin>(
     reader: &mut R,
 ) -> Result<T> {
-    // Read length prefix (4 bytes, little-endian)
+    let len = read_len(reader).await?;
+    let has_body = len & BODY_FLAG != 0;
+
+    // Read and deserialize the JSON header
+    let buf = read_frame(reader, (len & !BODY_FLAG) as usize).await?;
+    let mut msg: T = serde_json::from_slice(&buf)?;
+
+    if has_body {
+        let body_len = read_len(reader).await? as usize;
+        let body = read_frame(reader, body_len).await?;
+        let slot = msg
+            .body_mut()
+            .ok_or_else(|| anyhow!("Binary body sent with a message that has no binary payload"))?;
+        *slot = body;
+    }
+
+    Ok(msg)
+}
+
+/// Read a length prefix (4 bytes, little-endian)
+async fn read_len<R: AsyncReadExt + Unpin>(reader: &mut R) -> Result<u32> {
     let mut len_bytes = [0u8; 4];
     reader.read_exact(&mut len_bytes).await?;
-    let len = u32::from_le_bytes(len_bytes) as usize;
+    Ok(u32::from_le_bytes(len_bytes))
+}
 
-    // Sanity check: reject unreasonably large messages (>100MB)
-    if len > 100 * 1024 * 1024 {
-        return Err(anyhow!("Message too large: {} bytes", len));
-    }
+/// Read a frame of the given length
+async fn read_frame<R: AsyncReadExt + Unpin>(reader: &mut R, len: usize) -> Result<Vec<u8>> {
+    // Sanity check: reject unreasonably large messages
+    check_frame_size(len)?;
 
-    // Read JSON payload
     let mut buf = vec![0u8; len];
     reader.read_exact(&mut buf).await?;
+    Ok(buf)
+}
 
-    // Deserialize
-    Ok(serde_json::from_slice(&buf)?)
+fn check_frame_size(len: usize) -> Result<()> {
+    if len > MAX_FRAME_SIZE {
+        return Err(anyhow!("Message too large: {} bytes", len));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Command, Request, Response, CommandResult, CommandOutput};
-    use tokio::io::DuplexStream;
 
     #[tokio::test]
     async fn test_send_recv_request() {
@@ -58,7 +157,7 @@ mod tests {
             command: Command::Ping,
         };
 
-        send_message(&mut client, &request).await.unwrap();
+        send_message(&mut client, request).await.unwrap();
 
         // Receive on server
         let received: Request = recv_message(&mut server).await.unwrap();
@@ -79,7 +178,7 @@ mod tests {
             },
         };
 
-        send_message(&mut server, &response).await.unwrap();
+        send_message(&mut server, response).await.unwrap();
 
         // Receive on client
         let received: Response = recv_message(&mut client).await.unwrap();
@@ -87,4 +186,120 @@ mod tests {
         assert_eq!(received.id, 42);
         matches!(received.result, CommandResult::Success { .. });
     }
+
+    #[tokio::test]
+    async fn test_binary_output_travels_as_raw_body() {
+        let (mut server, mut client) = tokio::io::duplex(64 * 1024);
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+
+        let response = Response {
+            id: 7,
+            result: CommandResult::Success {
+                output: CommandOutput::Binary(data.clone()),
+            },
+        };
+        send_message(&mut server, response).await.unwrap();
+        drop(server);
+
+        let mut raw = Vec::new();
+        client.read_to_end(&mut raw).await.unwrap();
+
+        // Small JSON header, then the bytes unencoded
+        let header_len = u32::from_le_bytes(raw[..4].try_into().unwrap());
+        assert_ne!(header_len & BODY_FLAG, 0);
+        assert!(((header_len & !BODY_FLAG) as usize) < 200);
+        assert_eq!(raw.len(), 4 + (header_len & !BODY_FLAG) as usize + 4 + data.len());
+
+        let received: Response = recv_message(&mut raw.as_slice()).await.unwrap();
+        match received.result {
+            CommandResult::Success { output: CommandOutput::Binary(received) } => assert_eq!(received, data),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_data_round_trips() {
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+
+        let request = Request {
+            id: 1,
+            command: Command::Push {
+                device: "192.168.1.10".to_string(),
+                local_path: "a.bin".to_string(),
+                remote_path: "/tmp/a.bin".to_string(),
+                data: vec![0, 1, 2, 255],
+                mkdirs: false,
+            },
+        };
+        send_message(&mut client, request).await.unwrap();
+
+        let received: Request = recv_message(&mut server).await.unwrap();
+        match received.command {
+            Command::Push { data, remote_path, .. } => {
+                assert_eq!(data, vec![0, 1, 2, 255]);
+                assert_eq!(remote_path, "/tmp/a.bin");
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rpm_data_travels_as_raw_body() {
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        let rpm_data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+
+        let request = Request {
+            id: 3,
+            command: Command::Validate {
+                device: "192.168.1.10".to_string(),
+                rpm_path: "app.rpm".to_string(),
+                rpm_data: rpm_data.clone(),
+            },
+        };
+        send_message(&mut client, request).await.unwrap();
+        drop(client);
+
+        let mut raw = Vec::new();
+        server.read_to_end(&mut raw).await.unwrap();
+
+        // The RPM bytes are not in the JSON header
+        let header_len = u32::from_le_bytes(raw[..4].try_into().unwrap());
+        assert_ne!(header_len & BODY_FLAG, 0);
+        assert!(((header_len & !BODY_FLAG) as usize) < 200);
+
+        let received: Request = recv_message(&mut raw.as_slice()).await.unwrap();
+        match received.command {
+            Command::Validate { rpm_data: received, rpm_path, .. } => {
+                assert_eq!(received, rpm_data);
+                assert_eq!(rpm_path, "app.rpm");
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encoded_message_can_be_resent() {
+        let encoded = EncodedMessage::new(Request {
+            id: 4,
+            command: Command::Push {
+                device: "192.168.1.10".to_string(),
+                local_path: "a.bin".to_string(),
+                remote_path: "/tmp/a.bin".to_string(),
+                data: vec![7; 1000],
+                mkdirs: false,
+            },
+        })
+        .unwrap();
+
+        for _ in 0..2 {
+            let mut raw = Vec::new();
+            encoded.write_to(&mut raw).await.unwrap();
+
+            let received: Request = recv_message(&mut raw.as_slice()).await.unwrap();
+            match received.command {
+                Command::Push { data, .. } => assert_eq!(data, vec![7; 1000]),
+                other => panic!("unexpected command: {:?}", other),
+            }
+        }
+    }
 }
@@ -12,6 +12,9 @@ Usage:
   python3 swipe.py lr --event /dev/input/event4  # direction, fast mode
   python3 swipe.py lr --event auto               # direction, auto-detect
   python3 swipe.py lr --no-rotate                # disable rotation handling
+  python3 swipe.py lr --orientation landscape    # skip detection, assume landscape
+  python3 swipe.py du --profile fling            # easing: linear, ease-in, ease-out, fling
+  python3 swipe.py du --duration 300             # total move time in milliseconds
 
 Run as root: devel-su -c "python3 swipe.py lr"
 
@@ -20,7 +23,22 @@ Env overrides:
   TOUCH_MAJOR=19 WIDTH_MAJOR=19
   SETTLE=0.15   # seconds to wait after creating uinput device
   STEPS=20      # number of move steps
-  STEP_DELAY=0.005  # seconds between move steps
+  STEP_DELAY=0.005  # seconds between move steps (linear, no --duration)
+
+Profiles change how the move time is spread over the (evenly spaced) steps:
+  linear    constant speed
+  ease-in   starts slow, speeds up
+  ease-out  starts fast, slows down before lifting
+  fling     sharp acceleration, lifts at full speed (triggers momentum scrolling)
+
+Rotation: coordinates are given as the user sees the screen and mapped to the
+portrait-native touchscreen (XMAX x YMAX):
+  portrait            hw = (x, y)
+  landscape           hw = (XMAX - y, x)
+  inverted-portrait   hw = (XMAX - x, YMAX - y)
+  inverted-landscape  hw = (y, YMAX - x)
+The orientation is read from dconf unless --orientation names it; if it cannot
+be read, portrait (no transform) is assumed.
 """
 
 import os
@@ -29,6 +47,7 @@ import time
 import ctypes
 import fcntl
 import glob
+import math
 import subprocess
 
 # ---------- Defaults ----------
@@ -48,12 +67,29 @@ MARGIN_Y = float(os.environ.get("MARGIN_Y", "0.15"))
 SWIPE_Y = float(os.environ.get("SWIPE_Y", "0.50"))
 SWIPE_X = float(os.environ.get("SWIPE_X", "0.50"))
 
+# ---------- Easing profiles ----------
+# Each maps progress along the path (0..1) to the fraction of the total
+# duration at which that progress is reached (the inverse of the easing curve).
+PROFILES = {
+    "linear": lambda p: p,
+    "ease-in": lambda p: math.sqrt(p),
+    "ease-out": lambda p: 1.0 - math.sqrt(1.0 - p),
+    "fling": lambda p: p ** (1.0 / 3.0),
+}
+
 # ---------- Orientation constants (Qt::ScreenOrientation) ----------
 ORIENTATION_PORTRAIT = 1
 ORIENTATION_LANDSCAPE = 2
 ORIENTATION_INVERTED_PORTRAIT = 4
 ORIENTATION_INVERTED_LANDSCAPE = 8
 
+ORIENTATION_NAMES = {
+    "portrait": ORIENTATION_PORTRAIT,
+    "landscape": ORIENTATION_LANDSCAPE,
+    "inverted-portrait": ORIENTATION_INVERTED_PORTRAIT,
+    "inverted-landscape": ORIENTATION_INVERTED_LANDSCAPE,
+}
+
 # ---------- Input constants ----------
 EV_SYN = 0x00
 EV_KEY = 0x01
@@ -205,7 +241,7 @@ def parse_direction(d, orientation):
         return cx, y_top, cx, y_bottom
 
 # ---------- UINPUT MODE ----------
-def swipe_uinput(x0, y0, x1, y1):
+def swipe_uinput(x0, y0, x1, y1, profile, duration):
     x0 = clamp(x0, 0, XMAX); y0 = clamp(y0, 0, YMAX)
     x1 = clamp(x1, 0, XMAX); y1 = clamp(y1, 0, YMAX)
 
@@ -246,24 +282,24 @@ def swipe_uinput(x0, y0, x1, y1):
     fcntl.ioctl(fd, UI_DEV_CREATE)
     time.sleep(SETTLE)
 
-    do_swipe(fd, x0, y0, x1, y1)
+    do_swipe(fd, x0, y0, x1, y1, profile, duration)
 
     time.sleep(0.02)
     fcntl.ioctl(fd, UI_DEV_DESTROY)
     os.close(fd)
 
 # ---------- EVDEV MODE ----------
-def swipe_evdev(x0, y0, x1, y1, device):
+def swipe_evdev(x0, y0, x1, y1, device, profile, duration):
     if device == "auto":
         device = find_touchscreen()
     
     fd = os.open(device, os.O_WRONLY)
-    do_swipe(fd, x0, y0, x1, y1)
+    do_swipe(fd, x0, y0, x1, y1, profile, duration)
     os.close(fd)
     print(f"swipe via {device}")
 
 # ---------- COMMON SWIPE LOGIC ----------
-def do_swipe(fd, x0, y0, x1, y1):
+def do_swipe(fd, x0, y0, x1, y1, profile, duration):
     tracking_id = int(time.time() * 1000) % 60000 + 1
 
     # DOWN
@@ -278,6 +314,8 @@ def do_swipe(fd, x0, y0, x1, y1):
 
     # MOVE
     steps = max(1, STEPS)
+    time_at = PROFILES[profile]
+    total = duration if duration is not None else STEP_DELAY * steps
     for i in range(1, steps + 1):
         t = i / steps
         xi = int(x0 + (x1 - x0) * t)
@@ -287,8 +325,9 @@ def do_swipe(fd, x0, y0, x1, y1):
         emit(fd, EV_ABS, ABS_MT_TOUCH_MAJOR, TOUCH_MAJOR)
         emit(fd, EV_ABS, ABS_MT_WIDTH_MAJOR, WIDTH_MAJOR)
         syn(fd)
-        if STEP_DELAY > 0:
-            time.sleep(STEP_DELAY)
+        delay = total * (time_at(t) - time_at((i - 1) / steps))
+        if delay > 0:
+            time.sleep(delay)
 
     # UP
     emit(fd, EV_KEY, BTN_TOUCH, 0)
@@ -315,10 +354,43 @@ def main():
         args.remove("--no-rotate")
         no_rotate = True
 
+    # Parse --orientation flag (overrides dconf detection)
+    forced_orientation = None
+    if "--orientation" in args:
+        idx = args.index("--orientation")
+        if idx + 1 < len(args) and args[idx + 1] in ORIENTATION_NAMES:
+            forced_orientation = ORIENTATION_NAMES[args[idx + 1]]
+            args = args[:idx] + args[idx+2:]
+        else:
+            print("ERROR: --orientation requires one of: " + ", ".join(ORIENTATION_NAMES), file=sys.stderr)
+            return 2
+
+    # Parse --profile flag
+    profile = "linear"
+    if "--profile" in args:
+        idx = args.index("--profile")
+        if idx + 1 < len(args) and args[idx + 1] in PROFILES:
+            profile = args[idx + 1]
+            args = args[:idx] + args[idx+2:]
+        else:
+            print("ERROR: --profile requires one of: " + ", ".join(PROFILES), file=sys.stderr)
+            return 2
+
+    # Parse --duration flag (milliseconds)
+    duration = None
+    if "--duration" in args:
+        idx = args.index("--duration")
+        try:
+            duration = int(args[idx + 1]) / 1000.0
+            args = args[:idx] + args[idx+2:]
+        except (IndexError, ValueError):
+            print("ERROR: --duration requires milliseconds", file=sys.stderr)
+            return 2
+
     # Get orientation
     orientation = ORIENTATION_PORTRAIT
     if not no_rotate:
-        orientation = get_screen_orientation()
+        orientation = forced_orientation or get_screen_orientation()
 
     # Parse swipe args
     if len(args) == 1 and args[0] in ("lr", "rl", "du", "ud"):
@@ -326,10 +398,13 @@ def main():
     elif len(args) == 4:
         x0, y0, x1, y1 = int(args[0]), int(args[1]), int(args[2]), int(args[3])
     else:
-        print("Usage: python3 swipe.py lr|rl|du|ud [--event DEV] [--no-rotate]", file=sys.stderr)
-        print("       python3 swipe.py x0 y0 x1 y1 [--event DEV] [--no-rotate]", file=sys.stderr)
+        print("Usage: python3 swipe.py lr|rl|du|ud [--event DEV] [--no-rotate] [--orientation O] [--profile P] [--duration MS]", file=sys.stderr)
+        print("       python3 swipe.py x0 y0 x1 y1 [--event DEV] [--no-rotate] [--orientation O] [--profile P] [--duration MS]", file=sys.stderr)
         return 2
 
+    # Resolved screen-space endpoints, reported back by audb as "injected: ..."
+    print(f"endpoints: {x0},{y0} {x1},{y1}")
+
     # Transform coordinates based on orientation
     if not no_rotate:
         x0, y0 = transform_coordinates(x0, y0, orientation)
@@ -343,9 +418,9 @@ def main():
             print(f"orientation: {orient_name}", file=sys.stderr)
 
     if event_device:
-        swipe_evdev(x0, y0, x1, y1, event_device)
+        swipe_evdev(x0, y0, x1, y1, event_device, profile, duration)
     else:
-        swipe_uinput(x0, y0, x1, y1)
+        swipe_uinput(x0, y0, x1, y1, profile, duration)
 
     return 0
 
@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use audb_protocol::{Command, CommandResult};
+use directories::BaseDirs;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Per-device audit trail of commands run through the server
+///
+/// Each device gets its own `~/.config/audb/audit/<host>.log` with one line per
+/// request: timestamp, request id, the command and its outcome.
+pub struct AuditLog {
+    dir: PathBuf,
+}
+
+impl AuditLog {
+    /// Create the audit log, making sure the audit directory exists
+    pub fn new() -> Result<Self> {
+        let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+        let dir = base_dirs.config_dir().join("audb").join("audit");
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Record a described request and its result
+    pub fn record(&self, request_id: u64, entry: &AuditEntry, result: &CommandResult) {
+        let outcome = match result {
+            CommandResult::Success { .. } => "ok".to_string(),
+            CommandResult::Error { message, .. } => format!("error: {}", message),
+        };
+
+        let line = format!(
+            "{} [request {}] {} -> {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            request_id,
+            entry.description,
+            outcome
+        );
+
+        let path = self.dir.join(format!("{}.log", sanitize_file_name(&entry.device)));
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+
+        if let Err(e) = written {
+            warn!("Failed to write audit log {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// A device command as it appears in the audit trail
+pub struct AuditEntry {
+    device: String,
+    description: String,
+}
+
+/// Describe a device command for the audit trail (None for server-only commands)
+///
+/// Root commands are recorded by their command text only; the devel-su
+/// password is added by the pool and never reaches this log.
+pub fn describe(command: &Command) -> Option<AuditEntry> {
+    let described = match command {
+        Command::Ping
+        | Command::ServerStatus
+        | Command::Metrics
+        | Command::KillServer
+        | Command::ReloadDevices
+        | Command::Reconnect { .. }
+        | Command::HealthCheck { .. }
+        | Command::Cancel { .. }
+        | Command::DeviceHistory { .. } => return None,
+        Command::Shell { device, root, command, args, run_as, script, .. } => {
+            let prefix = match run_as {
+                Some(user) => format!("shell (as {})", user),
+                None if *root => "shell (root)".to_string(),
+                None => "shell".to_string(),
+            };
+            let command = if let Some(script) = script {
+                format!("<script, {} lines>", script.lines().count())
+            } else if args.is_empty() {
+                command.clone()
+            } else {
+                audb_core::tools::shell_escape::join_args(args)
+            };
+            (device, format!("{}: {}", prefix, command))
+        }
+        Command::Install { device, rpm_path, rpm_data, backend, .. } => {
+            (device, format!("install {} ({} bytes, {})", rpm_path, rpm_data.len(), backend.name()))
+        }
+        Command::Validate { device, rpm_path, rpm_data } => {
+            (device, format!("validate {} ({} bytes)", rpm_path, rpm_data.len()))
+        }
+        Command::Tap { device, x, y, duration_ms, .. } => match duration_ms {
+            Some(ms) => (device, format!("tap {},{} for {}ms", x, y, ms)),
+            None => (device, format!("tap {},{}", x, y)),
+        },
+        Command::FindElement { device, query, .. } => match query {
+            Some(query) => (device, format!("find-element {}", query)),
+            None => (device, "ui-dump".to_string()),
+        },
+        Command::Swipe { device, mode, profile, duration_ms, .. } => match duration_ms {
+            Some(ms) => (device, format!("swipe {:?} {:?} over {}ms", mode, profile, ms)),
+            None => (device, format!("swipe {:?} {:?}", mode, profile)),
+        },
+        Command::Pinch { device, direction, center, distance, .. } => {
+            let mut description = format!("pinch {:?}", direction);
+            if let Some((x, y)) = center {
+                description.push_str(&format!(" at {},{}", x, y));
+            }
+            if let Some(distance) = distance {
+                description.push_str(&format!(" over {}px", distance));
+            }
+            (device, description)
+        }
+        Command::Key { device, key_name } => (device, format!("key {}", key_name)),
+        Command::Screenshot { device, display } => match display {
+            Some(display) => (device, format!("screenshot --display {}", display)),
+            None => (device, "screenshot".to_string()),
+        },
+        Command::Launch { device, app_name, args, uri } => {
+            let mut line = format!("launch {}", app_name);
+            if !args.is_empty() {
+                line.push_str(&format!(" {}", audb_core::tools::shell_escape::join_args(args)));
+            }
+            if let Some(uri) = uri {
+                line.push_str(&format!(" --uri {}", uri));
+            }
+            (device, line)
+        }
+        Command::Stop { device, app_name, force, .. } => {
+            let suffix = if *force { " --force" } else { "" };
+            (device, format!("stop {}{}", app_name, suffix))
+        }
+        Command::Logs { device, args } => {
+            if args.clear {
+                (device, "logs --clear".to_string())
+            } else {
+                (device, format!("logs -n {}", args.lines))
+            }
+        }
+        Command::Uninstall { device, package_name } => {
+            (device, format!("uninstall {}", package_name))
+        }
+        Command::UninstallMany { device, package_names } => {
+            (device, format!("uninstall {}", package_names.join(" ")))
+        }
+        Command::Packages { device, filter } => match filter {
+            Some(filter) => (device, format!("packages --filter {}", filter)),
+            None => (device, "packages".to_string()),
+        },
+        Command::Push { device, local_path, remote_path, data, .. } => (
+            device,
+            format!("push {} -> {} ({} bytes)", local_path, remote_path, data.len()),
+        ),
+        Command::Pull { device, remote_path, tar } => {
+            let prefix = if *tar { "pull --tar" } else { "pull" };
+            (device, format!("{} {}", prefix, remote_path))
+        }
+        Command::Cat { device, remote_path, as_root } => {
+            let prefix = if *as_root { "cat (root)" } else { "cat" };
+            (device, format!("{} {}", prefix, remote_path))
+        }
+        Command::Info { device, category } => (
+            device,
+            format!("info {}", category.as_deref().unwrap_or("all")),
+        ),
+        Command::Open { device, url } => (device, format!("open {}", url)),
+        Command::Builtin { device, builtin } => (device, builtin.name().to_string()),
+        Command::Diagnose { device, topic } => (device, format!("diag {}", topic.name())),
+        Command::Radio { device, radio, enabled, .. } => {
+            (device, format!("radio {} {}", radio.name(), if *enabled { "on" } else { "off" }))
+        }
+        Command::Service { device, action, unit, user } => {
+            let scope = if *user { " --user" } else { "" };
+            (device, format!("service{} {} {}", scope, action.name(), unit))
+        }
+        Command::OpenFile { device, local_path, data } => (
+            device,
+            format!("open-file {} ({} bytes)", local_path, data.len()),
+        ),
+    };
+
+    Some(AuditEntry {
+        device: described.0.clone(),
+        description: described.1,
+    })
+}
+
+/// Make a device identifier safe to use as a file name
+fn sanitize_file_name(device: &str) -> String {
+    device
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "._-".contains(c) { c } else { '_' })
+        .collect()
+}
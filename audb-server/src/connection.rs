@@ -1,5 +1,9 @@
 use audb_core::tools::types::Device;
-use std::time::Instant;
+use std::collections::VecDeque;
+use std::time::{Instant, SystemTime};
+
+/// State transitions kept per device for `audb device history`
+const HISTORY_CAPACITY: usize = 100;
 
 /// Connection state for a device
 #[derive(Debug, Clone)]
@@ -22,6 +26,28 @@ pub enum ConnectionState {
     Disabled,
 }
 
+impl ConnectionState {
+    /// Short name of the state, without its details
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionState::Disconnected => "disconnected",
+            ConnectionState::Connecting { .. } => "connecting",
+            ConnectionState::Connected { .. } => "connected",
+            ConnectionState::Errored { .. } => "errored",
+            ConnectionState::Disabled => "disabled",
+        }
+    }
+}
+
+/// A recorded change of connection state
+#[derive(Debug, Clone)]
+pub struct StateEvent {
+    pub at: SystemTime,
+    pub from: &'static str,
+    pub to: &'static str,
+    pub reason: String,
+}
+
 /// Connection statistics
 #[derive(Debug, Clone)]
 pub struct ConnectionStats {
@@ -48,6 +74,12 @@ pub struct DeviceConnection {
     pub device: Device,
     pub state: ConnectionState,
     pub stats: ConnectionStats,
+    /// Screen resolution (width, height), cached once resolved
+    pub screen_size: Option<(u32, u32)>,
+    /// Home directory of the SSH user on the device, cached once resolved
+    pub home_dir: Option<String>,
+    /// Recent state transitions, oldest first
+    pub history: VecDeque<StateEvent>,
 }
 
 impl DeviceConnection {
@@ -57,7 +89,33 @@ impl DeviceConnection {
             device,
             state: ConnectionState::Disconnected,
             stats: ConnectionStats::default(),
+            screen_size: None,
+            home_dir: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Change state, recording it in the history when it is a transition
+    ///
+    /// Refreshing a state (e.g. still connected) is not recorded, but every
+    /// error is, so repeated failures show up with their causes.
+    pub fn set_state(&mut self, state: ConnectionState, reason: impl Into<String>) {
+        let from = self.state.label();
+        let to = state.label();
+        self.state = state;
+
+        if from == to && to != "errored" {
+            return;
+        }
+        if self.history.len() == HISTORY_CAPACITY {
+            self.history.pop_front();
         }
+        self.history.push_back(StateEvent {
+            at: SystemTime::now(),
+            from,
+            to,
+            reason: reason.into(),
+        });
     }
 
     /// Get connection duration if connected
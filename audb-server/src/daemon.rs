@@ -1,28 +1,22 @@
 use anyhow::{anyhow, Result};
+use audb_core::features::config::instance::ServerInstance;
 use daemonize::Daemonize;
-use directories::BaseDirs;
 use std::fs;
 use std::path::PathBuf;
 use tracing::info;
 
 /// Get the path to the server's PID file
 pub fn pid_file_path() -> Result<PathBuf> {
-    let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))?;
-    let config_dir = base_dirs.config_dir().join("audb");
-    fs::create_dir_all(&config_dir)?;
-    Ok(config_dir.join("server.pid"))
+    ServerInstance::pid_file_path()
 }
 
 /// Get the path to the server's log file
 pub fn log_file_path() -> Result<PathBuf> {
-    let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("Could not determine home directory"))?;
-    let config_dir = base_dirs.config_dir().join("audb");
-    fs::create_dir_all(&config_dir)?;
-    Ok(config_dir.join("server.log"))
+    ServerInstance::log_file_path()
 }
 
 /// Daemonize the server process and run it in the background
-pub fn daemonize_and_run() -> Result<()> {
+pub fn daemonize_and_run(config: crate::ServerConfig) -> Result<()> {
     let pid_file = pid_file_path()?;
     let log_file = log_file_path()?;
 
@@ -69,7 +63,7 @@ pub fn daemonize_and_run() -> Result<()> {
                 .build()?;
 
             // Run the server
-            runtime.block_on(crate::run_server())
+            runtime.block_on(crate::run_server(config))
         }
         Err(e) => Err(anyhow!("Failed to daemonize: {}", e)),
     }
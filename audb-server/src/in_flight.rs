@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+/// Process ID of the client that sent a request
+///
+/// Request IDs are picked by clients, so they are only unique per client. The
+/// cancel for a request arrives on a new connection, but from the same process.
+pub type ClientId = i32;
+
+/// Requests currently being processed, by client and request ID
+///
+/// A client that is interrupted sends `Command::Cancel` on a new connection;
+/// the handler of the original request is waiting on the matching receiver
+/// and drops the command when it fires.
+pub struct InFlight {
+    requests: Mutex<HashMap<(ClientId, u64), oneshot::Sender<()>>>,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self {
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Track a request; the receiver completes when it is cancelled
+    pub fn register(&self, client: ClientId, id: u64) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.requests.lock().unwrap().insert((client, id), tx);
+        rx
+    }
+
+    /// Stop tracking a finished request
+    pub fn finish(&self, client: ClientId, id: u64) {
+        self.requests.lock().unwrap().remove(&(client, id));
+    }
+
+    /// Cancel a request, returning false if it is not (or no longer) running
+    pub fn cancel(&self, client: ClientId, id: u64) -> bool {
+        match self.requests.lock().unwrap().remove(&(client, id)) {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_request_id_from_two_clients() {
+        let in_flight = InFlight::new();
+        let mut first = in_flight.register(100, 42);
+        let mut second = in_flight.register(200, 42);
+
+        assert!(in_flight.cancel(200, 42));
+        assert!(second.try_recv().is_ok());
+        assert!(first.try_recv().is_err());
+
+        in_flight.finish(100, 42);
+        assert!(!in_flight.cancel(100, 42));
+    }
+}
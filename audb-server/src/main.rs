@@ -1,12 +1,18 @@
+mod audit;
 mod connection;
 mod daemon;
+mod in_flight;
+mod metrics;
 mod pool;
+mod rate_limit;
 mod socket_server;
+mod temp_files;
 
 use anyhow::Result;
 use clap::Parser;
 use pool::ConnectionPool;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::info;
 
 #[derive(Parser)]
@@ -16,10 +22,79 @@ struct Args {
     /// Run in foreground (don't daemonize)
     #[arg(short, long)]
     foreground: bool,
+
+    /// Close device connections unused for this many minutes (0 = never)
+    #[arg(long, default_value = "10")]
+    idle_timeout: u64,
+
+    /// Record every device command to ~/.config/audb/audit/<host>.log
+    #[arg(long)]
+    audit: bool,
+
+    /// Cut off command output larger than this many MiB
+    #[arg(long, default_value = "32", value_parser = clap::value_parser!(u64).range(1..))]
+    max_output_mb: u64,
+
+    /// Maximum number of clients served at once
+    #[arg(long, default_value = "64")]
+    max_clients: usize,
+
+    /// Reject requests beyond this many per second on one client connection (0 = no limit)
+    #[arg(long, default_value = "0")]
+    rate_limit: u32,
+
+    /// Keep the temp files written for installs, pushes, screenshots and
+    /// scripts, locally and on the device (also AUDB_KEEP_TEMP=1)
+    #[arg(long)]
+    keep_temp: bool,
+
+    /// Serve on this socket instead of the per-user default (also AUDB_SOCKET);
+    /// the PID file and log are kept next to it
+    #[arg(long)]
+    socket: Option<std::path::PathBuf>,
+}
+
+/// Runtime configuration derived from command-line arguments
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// How long an unused device session stays open
+    pub idle_timeout: Option<Duration>,
+    /// Whether to keep a per-device audit trail
+    pub audit: bool,
+    /// Cap on output collected from a single device command
+    pub max_output_bytes: usize,
+    /// Maximum number of concurrently served clients
+    pub max_clients: usize,
+    /// Requests per second allowed on each client connection
+    pub rate_limit: Option<u32>,
+    /// Leave temp files in place for debugging
+    pub keep_temp: bool,
+}
+
+impl ServerConfig {
+    fn from_args(args: &Args) -> Self {
+        Self {
+            idle_timeout: (args.idle_timeout > 0)
+                .then(|| Duration::from_secs(args.idle_timeout * 60)),
+            audit: args.audit,
+            max_output_bytes: usize::try_from(args.max_output_mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX),
+            max_clients: args.max_clients.max(1),
+            rate_limit: (args.rate_limit > 0).then_some(args.rate_limit),
+            keep_temp: args.keep_temp
+                || std::env::var(temp_files::KEEP_TEMP_ENV).is_ok_and(|value| value == "1"),
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let config = ServerConfig::from_args(&args);
+
+    // Socket, PID file and log paths are all resolved through AUDB_SOCKET;
+    // set it before daemonizing or starting any threads
+    if let Some(socket) = &args.socket {
+        std::env::set_var(audb_core::features::config::instance::SOCKET_ENV, socket);
+    }
 
     // Start server (daemon or foreground based on args)
     if args.foreground {
@@ -36,18 +111,26 @@ fn main() -> Result<()> {
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()?
-            .block_on(run_server())?;
+            .block_on(run_server(config))?;
     } else {
         // Daemonize FIRST, then start tokio runtime
-        daemon::daemonize_and_run()?;
+        daemon::daemonize_and_run(config)?;
     }
 
     Ok(())
 }
 
-async fn run_server() -> Result<()> {
+async fn run_server(config: ServerConfig) -> Result<()> {
+    // Protect the daemon from commands with runaway output
+    audb_core::tools::ssh::SshClient::set_max_output_bytes(config.max_output_bytes);
+
+    temp_files::set_keep(config.keep_temp);
+    if config.keep_temp {
+        info!("Keeping temp files (--keep-temp)");
+    }
+
     // Create connection pool
-    let pool = Arc::new(ConnectionPool::new());
+    let pool = Arc::new(ConnectionPool::new().with_idle_timeout(config.idle_timeout));
 
     // Load devices from config and add to pool
     if let Ok(devices) = audb_core::features::config::device_store::DeviceStore::list_enabled() {
@@ -56,11 +139,20 @@ async fn run_server() -> Result<()> {
         }
     }
 
+    // Open the audit trail if requested
+    let audit = if config.audit {
+        let audit = audit::AuditLog::new()?;
+        info!("Audit logging enabled");
+        Some(Arc::new(audit))
+    } else {
+        None
+    };
+
     // Setup signal handlers
     let shutdown_signal = setup_signal_handlers()?;
 
     // Start Unix socket server with connection pool
-    socket_server::start_server(pool, shutdown_signal).await?;
+    socket_server::start_server(pool, audit, config.max_clients, config.rate_limit, shutdown_signal).await?;
 
     info!("Server shutdown complete");
     Ok(())
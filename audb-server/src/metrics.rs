@@ -0,0 +1,82 @@
+use audb_protocol::{CommandMetrics, ServerMetrics};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Instant;
+
+/// Server-wide command counters since start
+///
+/// Unlike the per-device `ConnectionStats`, these cover every command the
+/// server processes, including server-only ones like `ping`.
+pub struct Metrics {
+    started_at: Instant,
+    counters: RwLock<HashMap<&'static str, CommandCounter>>,
+}
+
+#[derive(Default)]
+struct CommandCounter {
+    count: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl CommandCounter {
+    fn record(&self, failed: bool) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            counters: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Count one processed command of the given type
+    pub fn record(&self, name: &'static str, failed: bool) {
+        // Fast path: the counter already exists, only the read lock is needed
+        if let Some(counter) = self.counters.read().unwrap().get(name) {
+            counter.record(failed);
+            return;
+        }
+
+        self.counters
+            .write()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .record(failed);
+    }
+
+    /// Snapshot of all counters
+    pub fn snapshot(&self) -> ServerMetrics {
+        let counters = self.counters.read().unwrap();
+
+        let mut commands: Vec<CommandMetrics> = counters
+            .iter()
+            .map(|(name, counter)| CommandMetrics {
+                name: name.to_string(),
+                count: counter.count.load(Ordering::Relaxed),
+                failed: counter.failed.load(Ordering::Relaxed),
+            })
+            .collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        ServerMetrics {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            total_commands: commands.iter().map(|c| c.count).sum(),
+            failed_commands: commands.iter().map(|c| c.failed).sum(),
+            commands,
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
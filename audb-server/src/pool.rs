@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use audb_core::features::config::device_store::DeviceStore;
 use audb_core::tools::{ssh::SshClient, types::Device};
 use russh::client::Handle;
 use std::collections::{HashMap, HashSet};
@@ -6,6 +7,7 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 use crate::connection::{ConnectionState, DeviceConnection};
@@ -16,6 +18,8 @@ enum DeviceOperation {
     Command {
         command: String,
         as_root: bool,
+        /// Allocate a pseudo-terminal for the command
+        pty: bool,
     },
     /// Upload a file via SFTP
     Upload {
@@ -33,6 +37,8 @@ enum DeviceOperation {
         remote_path: String,
         content: String,
     },
+    /// Probe the existing session right away
+    HealthCheck,
 }
 
 /// Result of a device operation
@@ -45,6 +51,8 @@ enum OperationResult {
     DownloadOk,
     /// Script ensured
     ScriptOk,
+    /// Health probe succeeded (round-trip time)
+    HealthOk(Duration),
 }
 
 /// Command request for a device
@@ -57,6 +65,8 @@ struct DeviceCommandRequest {
 pub struct ConnectionPool {
     connections: Arc<Mutex<HashMap<String, DeviceConnection>>>,
     command_queues: Arc<Mutex<HashMap<String, mpsc::Sender<DeviceCommandRequest>>>>,
+    processors: Mutex<HashMap<String, JoinHandle<()>>>,
+    idle_timeout: Option<Duration>,
 }
 
 impl ConnectionPool {
@@ -65,13 +75,27 @@ impl ConnectionPool {
         Self {
             connections: Arc::new(Mutex::new(HashMap::new())),
             command_queues: Arc::new(Mutex::new(HashMap::new())),
+            processors: Mutex::new(HashMap::new()),
+            idle_timeout: Some(DEFAULT_IDLE_TIMEOUT),
         }
     }
 
+    /// Set how long a device session may stay unused before it is closed
+    /// (`None` keeps sessions open indefinitely)
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
     /// Add a device to the pool and start its command processor
+    ///
+    /// A device already in the pool under the same host is replaced.
     pub async fn add_device(&self, device: Device) {
         let host = device.host.clone();
 
+        // Stop the old processor first so it cannot update the new entry
+        self.remove_device(&host).await;
+
         // Add to connections map
         {
             let mut connections = self.connections.lock().await;
@@ -88,9 +112,38 @@ impl ConnectionPool {
 
         // Spawn command processor task for this device
         let connections = Arc::clone(&self.connections);
-        tokio::spawn(async move {
-            device_command_processor(host, device, rx, connections).await;
+        let idle_timeout = self.idle_timeout;
+        let processor_host = host.clone();
+        let handle = tokio::spawn(async move {
+            device_command_processor(processor_host, device, rx, connections, idle_timeout).await;
         });
+
+        self.processors.lock().await.insert(host, handle);
+    }
+
+    /// Remove a device from the pool and stop its command processor
+    ///
+    /// Commands already queued for the device still run. Waits up to
+    /// `PROCESSOR_STOP_TIMEOUT` for them before aborting the processor, which
+    /// also closes its SSH session. Returns false if the device was not in the pool.
+    pub async fn remove_device(&self, host: &str) -> bool {
+        // Dropping the sender closes the queue, which ends the processor loop
+        let queue = self.command_queues.lock().await.remove(host);
+        let processor = self.processors.lock().await.remove(host);
+        let removed = queue.is_some();
+        drop(queue);
+
+        if let Some(handle) = processor {
+            let abort_handle = handle.abort_handle();
+            if tokio::time::timeout(PROCESSOR_STOP_TIMEOUT, handle).await.is_err() {
+                warn!("Command processor for {} did not stop in time, aborting it", host);
+                abort_handle.abort();
+            }
+        }
+
+        // Removed last so the processor can still record its final results
+        let connection = self.connections.lock().await.remove(host);
+        removed || connection.is_some()
     }
 
     /// Execute a command on a device (queued execution)
@@ -99,6 +152,17 @@ impl ConnectionPool {
         host: &str,
         command: &str,
         as_root: bool,
+    ) -> Result<Vec<String>> {
+        self.execute_command_with_pty(host, command, as_root, false).await
+    }
+
+    /// Execute a command, optionally with a pseudo-terminal allocated for it
+    pub async fn execute_command_with_pty(
+        &self,
+        host: &str,
+        command: &str,
+        as_root: bool,
+        pty: bool,
     ) -> Result<Vec<String>> {
         let result = self
             .send_operation(
@@ -106,6 +170,7 @@ impl ConnectionPool {
                 DeviceOperation::Command {
                     command: command.to_string(),
                     as_root,
+                    pty,
                 },
             )
             .await?;
@@ -187,6 +252,18 @@ impl ConnectionPool {
         }
     }
 
+    /// Probe a device's existing session immediately (does not connect)
+    ///
+    /// Returns the probe round-trip time.
+    pub async fn health_check(&self, host: &str) -> Result<Duration> {
+        let result = self.send_operation(host, DeviceOperation::HealthCheck).await?;
+
+        match result {
+            OperationResult::HealthOk(latency) => Ok(latency),
+            _ => Err(anyhow!("Unexpected operation result")),
+        }
+    }
+
     /// Send an operation to a device's command queue
     async fn send_operation(
         &self,
@@ -239,6 +316,34 @@ impl ConnectionPool {
             .ok_or_else(|| anyhow!("Device {} not found", host))
     }
 
+    /// Screen resolution cached for a device, if already resolved
+    pub async fn screen_size(&self, host: &str) -> Option<(u32, u32)> {
+        let connections = self.connections.lock().await;
+        connections.get(host).and_then(|conn| conn.screen_size)
+    }
+
+    /// Remember a device's screen resolution
+    pub async fn set_screen_size(&self, host: &str, size: (u32, u32)) {
+        let mut connections = self.connections.lock().await;
+        if let Some(conn) = connections.get_mut(host) {
+            conn.screen_size = Some(size);
+        }
+    }
+
+    /// Remote home directory cached for a device, if already resolved
+    pub async fn home_dir(&self, host: &str) -> Option<String> {
+        let connections = self.connections.lock().await;
+        connections.get(host).and_then(|conn| conn.home_dir.clone())
+    }
+
+    /// Remember a device's remote home directory
+    pub async fn set_home_dir(&self, host: &str, home: String) {
+        let mut connections = self.connections.lock().await;
+        if let Some(conn) = connections.get_mut(host) {
+            conn.home_dir = Some(home);
+        }
+    }
+
     /// Get device by host
     #[allow(dead_code)]
     pub async fn get_device(&self, host: &str) -> Result<Device> {
@@ -258,6 +363,12 @@ const BACKOFF_MULTIPLIER: u64 = 2;
 /// Health check interval (60 seconds)
 const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
+/// How long `remove_device` waits for queued commands before aborting the processor
+const PROCESSOR_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time an unused session stays open (10 minutes)
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 /// Command processor for a single device
 /// Ensures commands to the same device execute serially
 /// Maintains a persistent SSH connection with auto-reconnect
@@ -266,6 +377,7 @@ async fn device_command_processor(
     device: Device,
     mut rx: mpsc::Receiver<DeviceCommandRequest>,
     connections: Arc<Mutex<HashMap<String, DeviceConnection>>>,
+    idle_timeout: Option<Duration>,
 ) {
     info!("Started command processor for device: {}", host);
 
@@ -275,12 +387,99 @@ async fn device_command_processor(
     let mut connected_since: Option<Instant> = None;
     let mut last_health_check: Option<Instant> = None;
     let mut current_backoff_ms: u64 = INITIAL_BACKOFF_MS;
+    let mut last_operation_at = Instant::now();
 
     // Track which scripts have been uploaded to avoid re-checking every time
     let mut uploaded_scripts: HashSet<String> = HashSet::new();
 
-    while let Some(request) = rx.recv().await {
+    loop {
+        // While a session is open, wake up when it has been idle for too long
+        let idle_deadline = match (&session, idle_timeout) {
+            (Some(_), Some(timeout)) => Some(last_operation_at + timeout),
+            _ => None,
+        };
+
+        let received = match idle_deadline {
+            Some(deadline) => {
+                match tokio::time::timeout_at(deadline.into(), rx.recv()).await {
+                    Ok(received) => received,
+                    Err(_) => {
+                        info!(
+                            "Closing idle SSH connection to {} (unused for {}s)",
+                            host,
+                            last_operation_at.elapsed().as_secs()
+                        );
+                        session = None;
+                        connected_since = None;
+                        uploaded_scripts.clear();
+
+                        let mut conns = connections.lock().await;
+                        if let Some(conn) = conns.get_mut(&host) {
+                            conn.set_state(ConnectionState::Disconnected, "idle timeout");
+                        }
+                        continue;
+                    }
+                }
+            }
+            None => rx.recv().await,
+        };
+
+        let Some(mut request) = received else {
+            break;
+        };
+
+        // The caller gave up (client cancelled or disconnected) while this was queued
+        if request.response_tx.is_closed() {
+            debug!("Skipping cancelled operation for {}", host);
+            continue;
+        }
+
+        // Explicit health probe: only checks the existing session, never connects
+        if matches!(request.operation, DeviceOperation::HealthCheck) {
+            let result = match session {
+                Some(ref mut sess) => {
+                    let started = Instant::now();
+                    match SshClient::exec(sess, "echo 1") {
+                        Ok(_) => {
+                            last_health_check = Some(Instant::now());
+                            let mut conns = connections.lock().await;
+                            if let (Some(conn), Some(since)) = (conns.get_mut(&host), connected_since) {
+                                conn.set_state(ConnectionState::Connected { since }, "health check passed");
+                            }
+                            Ok(OperationResult::HealthOk(started.elapsed()))
+                        }
+                        Err(e) => {
+                            warn!("Health check failed for {}: {}, will reconnect", host, e);
+                            session = None;
+                            connected_since = None;
+                            uploaded_scripts.clear();
+
+                            let mut conns = connections.lock().await;
+                            if let Some(conn) = conns.get_mut(&host) {
+                                conn.set_state(
+                                    ConnectionState::Errored {
+                                        error: e.to_string(),
+                                        next_retry: None,
+                                    },
+                                    format!("health check failed: {}", e),
+                                );
+                                conn.stats.last_error = Some(e.to_string());
+                            }
+                            Err(anyhow!("Health check failed: {}", e))
+                        }
+                    }
+                }
+                None => Err(anyhow!("Device {} has no open connection", host)),
+            };
+
+            if request.response_tx.send(result).is_err() {
+                warn!("Command response channel closed for {}", host);
+            }
+            continue;
+        }
+
         debug!("Processing operation for {}", host);
+        last_operation_at = Instant::now();
 
         // Check if we need a health check (only if connected)
         if session.is_some() {
@@ -298,6 +497,14 @@ async fn device_command_processor(
                                 session = None;
                                 connected_since = None;
                                 uploaded_scripts.clear(); // Scripts may need re-upload after reconnect
+
+                                let mut conns = connections.lock().await;
+                                if let Some(conn) = conns.get_mut(&host) {
+                                    conn.set_state(
+                                        ConnectionState::Disconnected,
+                                        format!("periodic health check failed: {}", e),
+                                    );
+                                }
                             }
                         }
                     }
@@ -319,11 +526,18 @@ async fn device_command_processor(
                     // Update state to connected
                     let mut conns = connections.lock().await;
                     if let Some(conn) = conns.get_mut(&host) {
-                        conn.state = ConnectionState::Connected {
-                            since: connected_since.unwrap(),
-                        };
+                        conn.set_state(
+                            ConnectionState::Connected {
+                                since: connected_since.unwrap(),
+                            },
+                            "SSH session established",
+                        );
                     }
                     info!("Established persistent SSH connection to {}", host);
+
+                    if let Err(e) = DeviceStore::set_last_connected(&host) {
+                        warn!("Failed to record last connection time for {}: {}", host, e);
+                    }
                 }
                 Err(e) => {
                     warn!("Failed to connect to {}: {}", host, e);
@@ -333,10 +547,13 @@ async fn device_command_processor(
                     {
                         let mut conns = connections.lock().await;
                         if let Some(conn) = conns.get_mut(&host) {
-                            conn.state = ConnectionState::Errored {
-                                error: e.to_string(),
-                                next_retry: Some(next_retry),
-                            };
+                            conn.set_state(
+                                ConnectionState::Errored {
+                                    error: e.to_string(),
+                                    next_retry: Some(next_retry),
+                                },
+                                format!("connect failed: {}", e),
+                            );
                             conn.stats.last_error = Some(e.to_string());
                         }
                     }
@@ -352,9 +569,17 @@ async fn device_command_processor(
             }
         }
 
-        // Execute the operation using persistent session
+        // Execute the operation using persistent session, abandoning it (which
+        // closes its SSH channel) if the caller goes away meanwhile
         let result = if let Some(ref mut sess) = session {
-            execute_operation(sess, &device, request.operation, &mut uploaded_scripts).await
+            let operation = execute_operation(sess, &device, request.operation, &mut uploaded_scripts);
+            tokio::select! {
+                result = operation => result,
+                _ = request.response_tx.closed() => {
+                    info!("Cancelled running operation for {}", host);
+                    continue;
+                }
+            }
         } else {
             Err(anyhow!("No active session"))
         };
@@ -367,7 +592,7 @@ async fn device_command_processor(
                 if let Some(conn) = conns.get_mut(&host) {
                     conn.stats.successful_commands += 1;
                     if let Some(since) = connected_since {
-                        conn.state = ConnectionState::Connected { since };
+                        conn.set_state(ConnectionState::Connected { since }, "command succeeded");
                     }
                 }
             }
@@ -403,16 +628,20 @@ async fn establish_connection(
     {
         let mut conns = connections.lock().await;
         if let Some(conn) = conns.get_mut(host) {
-            conn.state = ConnectionState::Connecting {
-                attempt: conn.stats.connect_attempts as u32 + 1,
-                next_retry: Instant::now(),
-            };
+            let attempt = conn.stats.connect_attempts as u32 + 1;
+            conn.set_state(
+                ConnectionState::Connecting {
+                    attempt,
+                    next_retry: Instant::now(),
+                },
+                format!("connect attempt {}", attempt),
+            );
             conn.stats.connect_attempts += 1;
         }
     }
 
     // Establish SSH connection
-    SshClient::connect(&device.host, device.port, &device.auth_path())
+    SshClient::connect_device(device)
 }
 
 /// Execute an operation on an existing SSH session
@@ -423,11 +652,11 @@ async fn execute_operation(
     uploaded_scripts: &mut HashSet<String>,
 ) -> Result<OperationResult> {
     match operation {
-        DeviceOperation::Command { command, as_root } => {
+        DeviceOperation::Command { command, as_root, pty } => {
             let lines = if as_root {
-                SshClient::exec_as_devel_su(session, &command, &device.root_password)?
+                SshClient::exec_as_devel_su_async(session, &command, &device.root_password, pty).await?
             } else {
-                SshClient::exec(session, &command)?
+                SshClient::exec_async(session, &command, pty).await?
             };
             Ok(OperationResult::Lines(lines))
         }
@@ -435,14 +664,14 @@ async fn execute_operation(
             local_path,
             remote_path,
         } => {
-            SshClient::upload(session, &local_path, &remote_path)?;
+            SshClient::upload_async(session, &local_path, &remote_path).await?;
             Ok(OperationResult::UploadOk)
         }
         DeviceOperation::Download {
             remote_path,
             local_path,
         } => {
-            SshClient::download(session, &remote_path, &local_path)?;
+            SshClient::download_async(session, &remote_path, &local_path).await?;
             Ok(OperationResult::DownloadOk)
         }
         DeviceOperation::EnsureScript {
@@ -462,7 +691,7 @@ async fn execute_operation(
                 remote_path, remote_path
             );
 
-            let result = SshClient::exec(session, &check_cmd)?;
+            let result = SshClient::exec_async(session, &check_cmd, false).await?;
             let current_size: usize = result.first().and_then(|s| s.parse().ok()).unwrap_or(0);
 
             if current_size != expected_size {
@@ -471,13 +700,13 @@ async fn execute_operation(
                     std::env::temp_dir().join(Path::new(&remote_path).file_name().unwrap());
                 std::fs::write(&temp_file, &content)?;
 
-                SshClient::upload(session, &temp_file, Path::new(&remote_path))?;
+                SshClient::upload_async(session, &temp_file, Path::new(&remote_path)).await?;
 
                 // Make executable
-                SshClient::exec(session, &format!("chmod +x {}", remote_path))?;
+                SshClient::exec_async(session, &format!("chmod +x {}", remote_path), false).await?;
 
                 // Cleanup local temp
-                std::fs::remove_file(&temp_file).ok();
+                crate::temp_files::remove_local(&temp_file);
 
                 debug!("Uploaded script {} to {}", script_name, remote_path);
             }
@@ -487,6 +716,10 @@ async fn execute_operation(
 
             Ok(OperationResult::ScriptOk)
         }
+        DeviceOperation::HealthCheck => {
+            // Handled directly by the command processor
+            Err(anyhow!("Health check must be handled by the command processor"))
+        }
     }
 }
 
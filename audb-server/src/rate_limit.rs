@@ -0,0 +1,87 @@
+use std::time::Instant;
+
+/// Token bucket limiting how fast one client connection may send requests
+///
+/// Holds up to one second's worth of tokens, so short bursts up to the rate
+/// pass and a steady flood is cut down to it.
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Bucket allowing `rate` requests per second
+    pub fn new(rate: u32) -> Self {
+        let rate = f64::from(rate.max(1));
+        Self {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Take a token for one request, or return false if the client is over its rate
+    pub fn try_take(&mut self) -> bool {
+        self.try_take_at(Instant::now())
+    }
+
+    fn try_take_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Take tokens at `at` until one is refused, returning how many were given
+    fn drain(bucket: &mut TokenBucket, at: Instant) -> usize {
+        (0..).take_while(|_| bucket.try_take_at(at)).count()
+    }
+
+    #[test]
+    fn test_burst_up_to_rate() {
+        let mut bucket = TokenBucket::new(5);
+        let start = bucket.last_refill;
+        assert_eq!(drain(&mut bucket, start), 5);
+        assert!(!bucket.try_take_at(start));
+    }
+
+    #[test]
+    fn test_refill_after_rejection() {
+        let mut bucket = TokenBucket::new(5);
+        let start = bucket.last_refill;
+        drain(&mut bucket, start);
+
+        // One token takes 200ms at 5/s
+        assert!(!bucket.try_take_at(start + Duration::from_millis(150)));
+        assert!(bucket.try_take_at(start + Duration::from_millis(250)));
+        assert!(!bucket.try_take_at(start + Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn test_refill_is_capped_at_rate() {
+        let mut bucket = TokenBucket::new(3);
+        let start = bucket.last_refill;
+        drain(&mut bucket, start);
+        assert_eq!(drain(&mut bucket, start + Duration::from_secs(60)), 3);
+    }
+
+    #[test]
+    fn test_zero_rate_allows_one_per_second() {
+        let mut bucket = TokenBucket::new(0);
+        let start = bucket.last_refill;
+        assert_eq!(drain(&mut bucket, start), 1);
+        assert!(bucket.try_take_at(start + Duration::from_secs(1)));
+    }
+}
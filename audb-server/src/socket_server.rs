@@ -1,27 +1,44 @@
 use anyhow::{anyhow, Result};
 use audb_protocol::{recv_message, send_message, Command, CommandOutput, CommandResult, Request, Response, ServerStatus};
+use crate::audit::AuditLog;
+use crate::in_flight::{ClientId, InFlight};
+use crate::metrics::Metrics;
 use crate::pool::ConnectionPool;
-use nix::unistd::Uid;
+use crate::rate_limit::TokenBucket;
+use crate::temp_files;
+use audb_core::tools::shell_escape::{escape_single_quote, quote_arg};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Semaphore;
 use tracing::{info, warn, error};
 
 // Get scripts from audb-core (single source of truth)
 const TAP_SCRIPT: &str = audb_core::features::input::scripts::ScriptManager::tap_script_content();
 const SWIPE_SCRIPT: &str = audb_core::features::input::scripts::ScriptManager::swipe_script_content();
+const PINCH_SCRIPT: &str = audb_core::features::input::scripts::ScriptManager::pinch_script_content();
+const UI_DUMP_SCRIPT: &str = audb_core::features::input::scripts::ScriptManager::ui_dump_script_content();
 const REMOTE_TAP_PATH: &str = "/tmp/audb_tap.py";
 const REMOTE_SWIPE_PATH: &str = "/tmp/audb_swipe.py";
+const REMOTE_PINCH_PATH: &str = "/tmp/audb_pinch.py";
+const REMOTE_UI_DUMP_PATH: &str = "/tmp/audb_ui_dump.py";
+
+/// Attempts at the Lipstick saveScreenshot call before giving up
+const SCREENSHOT_ATTEMPTS: u32 = 3;
+/// Delay between screenshot attempts
+const SCREENSHOT_RETRY_DELAY_MS: u64 = 500;
 
 /// Get the path to the Unix socket
 pub fn socket_path() -> PathBuf {
-    let uid = Uid::current();
-    PathBuf::from(format!("/tmp/audb-server-{}.sock", uid))
+    audb_core::features::config::instance::ServerInstance::socket_path()
 }
 
 /// Start the Unix socket server
 pub async fn start_server(
     pool: Arc<ConnectionPool>,
+    audit: Option<Arc<AuditLog>>,
+    max_clients: usize,
+    rate_limit: Option<u32>,
     mut shutdown_signal: tokio::sync::mpsc::Receiver<()>,
 ) -> Result<()> {
     let socket_path = socket_path();
@@ -43,6 +60,18 @@ pub async fn start_server(
     }
 
     info!("Listening on Unix socket: {}", socket_path.display());
+    if let Some(rate) = rate_limit {
+        info!("Limiting clients to {} requests per second", rate);
+    }
+
+    // Bound the number of concurrently served clients
+    let client_slots = Arc::new(Semaphore::new(max_clients));
+
+    // Command counters shared by all clients
+    let metrics = Arc::new(Metrics::new());
+
+    // Requests being processed, so they can be cancelled from another connection
+    let in_flight = Arc::new(InFlight::new());
 
     // Main server loop
     loop {
@@ -51,12 +80,22 @@ pub async fn start_server(
             result = listener.accept() => {
                 match result {
                     Ok((stream, _addr)) => {
+                        let Ok(permit) = Arc::clone(&client_slots).try_acquire_owned() else {
+                            warn!("Rejecting client: {} clients already connected", max_clients);
+                            tokio::spawn(reject_busy(stream));
+                            continue;
+                        };
+
                         info!("Client connected");
                         let pool_clone = Arc::clone(&pool);
+                        let audit_clone = audit.clone();
+                        let metrics_clone = Arc::clone(&metrics);
+                        let in_flight_clone = Arc::clone(&in_flight);
                         tokio::spawn(async move {
-                            if let Err(e) = handle_client(stream, pool_clone).await {
+                            if let Err(e) = handle_client(stream, pool_clone, audit_clone, metrics_clone, in_flight_clone, rate_limit).await {
                                 error!("Client handler error: {}", e);
                             }
+                            drop(permit);
                         });
                     }
                     Err(e) => {
@@ -81,8 +120,31 @@ pub async fn start_server(
     Ok(())
 }
 
+/// Answer a client with "server busy" and close the connection
+async fn reject_busy(mut stream: UnixStream) {
+    let response = Response {
+        id: 0,
+        result: CommandResult::Error {
+            message: "Server busy: too many clients connected, try again later".to_string(),
+            kind: audb_protocol::ErrorKind::ServerError,
+        },
+    };
+    send_message(&mut stream, response).await.ok();
+}
+
 /// Handle a single client connection
-async fn handle_client(mut stream: UnixStream, pool: Arc<ConnectionPool>) -> Result<()> {
+async fn handle_client(
+    mut stream: UnixStream,
+    pool: Arc<ConnectionPool>,
+    audit: Option<Arc<AuditLog>>,
+    metrics: Arc<Metrics>,
+    in_flight: Arc<InFlight>,
+    rate_limit: Option<u32>,
+) -> Result<()> {
+    let mut bucket = rate_limit.map(TokenBucket::new);
+    // Scopes request IDs for cancellation; Linux always reports the peer's PID
+    let client: ClientId = stream.peer_cred().ok().and_then(|cred| cred.pid()).unwrap_or_default();
+
     loop {
         // Receive request from client
         let request: Request = match recv_message(&mut stream).await {
@@ -96,8 +158,48 @@ async fn handle_client(mut stream: UnixStream, pool: Arc<ConnectionPool>) -> Res
 
         info!("Received request ID {}: {:?}", request.id, request.command);
 
-        // Process command
-        let result = process_command(request.command, &pool).await;
+        // Refuse floods from one client so it cannot monopolize the device queues
+        if bucket.as_mut().is_some_and(|bucket| !bucket.try_take()) {
+            warn!("Rate limit exceeded for request ID {}", request.id);
+            metrics.record(request.command.name(), true);
+            let response = Response {
+                id: request.id,
+                result: CommandResult::Error {
+                    message: format!(
+                        "Rate limit exceeded: more than {} requests per second",
+                        rate_limit.unwrap_or_default()
+                    ),
+                    kind: audb_protocol::ErrorKind::ServerError,
+                },
+            };
+            send_message(&mut stream, response).await?;
+            continue;
+        }
+
+        // Describe the command for the audit trail before it is consumed
+        let audit_entry = audit.as_ref().and_then(|_| crate::audit::describe(&request.command));
+
+        let command_name = request.command.name();
+
+        // Process command; dropping it on cancellation also aborts its device operation
+        let cancelled = in_flight.register(client, request.id);
+        let result = tokio::select! {
+            result = process_command(request.command, client, &pool, &metrics, &in_flight) => result,
+            Ok(()) = cancelled => {
+                info!("Request ID {} cancelled", request.id);
+                CommandResult::Error {
+                    message: "Cancelled by client".to_string(),
+                    kind: audb_protocol::ErrorKind::CommandFailed,
+                }
+            }
+        };
+        in_flight.finish(client, request.id);
+
+        metrics.record(command_name, matches!(result, CommandResult::Error { .. }));
+
+        if let (Some(audit), Some(entry)) = (&audit, &audit_entry) {
+            audit.record(request.id, entry, &result);
+        }
 
         // Send response
         let response = Response {
@@ -105,14 +207,20 @@ async fn handle_client(mut stream: UnixStream, pool: Arc<ConnectionPool>) -> Res
             result,
         };
 
-        send_message(&mut stream, &response).await?;
+        send_message(&mut stream, response).await?;
     }
 
     Ok(())
 }
 
 /// Process a command and return the result
-async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResult {
+async fn process_command(
+    command: Command,
+    client: ClientId,
+    pool: &ConnectionPool,
+    metrics: &Metrics,
+    in_flight: &InFlight,
+) -> CommandResult {
     match command {
         Command::Ping => {
             // Simple ping/pong for testing
@@ -121,6 +229,10 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
+        Command::Metrics => CommandResult::Success {
+            output: CommandOutput::Metrics(metrics.snapshot()),
+        },
+
         Command::ServerStatus => {
             // Return server status
             match get_server_status(pool).await {
@@ -134,6 +246,29 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
+        Command::ReloadDevices => match reload_devices(pool).await {
+            Ok(lines) => CommandResult::Success {
+                output: CommandOutput::Lines(lines),
+            },
+            Err(e) => CommandResult::Error {
+                message: format!("Failed to reload devices: {}", e),
+                kind: audb_protocol::ErrorKind::ServerError,
+            },
+        },
+
+        Command::Cancel { request_id } => {
+            if in_flight.cancel(client, request_id) {
+                CommandResult::Success {
+                    output: CommandOutput::Lines(vec![format!("Cancelled request {}", request_id)]),
+                }
+            } else {
+                CommandResult::Error {
+                    message: format!("No request {} in progress", request_id),
+                    kind: audb_protocol::ErrorKind::InvalidRequest,
+                }
+            }
+        }
+
         Command::KillServer => {
             // Signal graceful shutdown
             info!("Kill server command received, initiating shutdown");
@@ -145,10 +280,80 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
         }
 
         // Shell command - Phase 2 implementation
-        Command::Shell { device, root, command } => {
-            match pool.execute_command(&device, &command, root).await {
-                Ok(lines) => CommandResult::Success {
-                    output: CommandOutput::Lines(lines),
+        Command::Shell { device, root, pty, command, args, run_as, time, script, login, head, tail } => {
+            let uploaded = match script {
+                Some(script) => match upload_shell_script(pool, &device, &script).await {
+                    Ok(remote) => Some(remote),
+                    Err(e) => {
+                        return CommandResult::Error {
+                            message: format!("Failed to upload script: {}", e),
+                            kind: audb_protocol::ErrorKind::CommandFailed,
+                        }
+                    }
+                },
+                None => None,
+            };
+            let command = if let Some(remote) = &uploaded {
+                format!("/bin/sh {}", quote_arg(remote))
+            } else if args.is_empty() {
+                command
+            } else {
+                audb_core::tools::shell_escape::join_args(&args)
+            };
+            let command = if login {
+                format!("bash -lc '{}'", escape_single_quote(&command))
+            } else {
+                command
+            };
+            // Cut the output on the device so the rest is never transferred;
+            // the subshell keeps `a; b` together and a trailing comment harmless
+            let command = match (head, tail) {
+                (Some(lines), _) => format!("( {}\n) | head -n {}", command, lines),
+                (None, Some(lines)) => format!("( {}\n) | tail -n {}", command, lines),
+                (None, None) => command,
+            };
+            // Timed on the server, so client/server IPC is excluded (the SSH round trip is not)
+            let started = std::time::Instant::now();
+            let result = match run_as {
+                Some(user) => match as_user_command(&user, &command) {
+                    Ok(command) => pool.execute_command_with_pty(&device, &command, true, pty).await,
+                    Err(e) => Err(e),
+                },
+                None => pool.execute_command_with_pty(&device, &command, root, pty).await,
+            };
+            if let Some(remote) = uploaded {
+                // Removed as the uploading user, whoever the script ran as
+                if let Err(e) = temp_files::remove_remote(pool, &device, &remote, false).await {
+                    warn!("Failed to remove script {} from {}: {}", remote, device, e);
+                }
+            }
+            match result {
+                Ok(mut lines) => {
+                    if time {
+                        lines.push(format!("real: {:.2}s", started.elapsed().as_secs_f64()));
+                    }
+                    CommandResult::Success {
+                        output: CommandOutput::Lines(lines),
+                    }
+                }
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Install { device, rpm_path, rpm_data, check_deps, backend } => {
+            match execute_install(pool, &device, &rpm_path, rpm_data, check_deps, backend).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
                 },
                 Err(e) => {
                     let kind = if e.to_string().contains("not found") {
@@ -164,8 +369,8 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Install { device, rpm_path, rpm_data } => {
-            match execute_install(pool, &device, &rpm_path, rpm_data).await {
+        Command::Validate { device, rpm_path, rpm_data } => {
+            match execute_validate(pool, &device, &rpm_path, rpm_data).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -202,6 +407,25 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
+        Command::UninstallMany { device, package_names } => {
+            match execute_uninstall_many(pool, &device, &package_names).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
         Command::Packages { device, filter } => {
             match execute_packages(pool, &device, filter).await {
                 Ok(output) => CommandResult::Success {
@@ -221,8 +445,8 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Push { device, local_path, remote_path, data } => {
-            match execute_push(pool, &device, &local_path, &remote_path, data).await {
+        Command::Push { device, local_path, remote_path, data, mkdirs } => {
+            match execute_push(pool, &device, &local_path, &remote_path, data, mkdirs).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -240,8 +464,13 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Pull { device, remote_path } => {
-            match execute_pull(pool, &device, &remote_path).await {
+        Command::Pull { device, remote_path, tar } => {
+            let result = if tar {
+                execute_pull_tar(pool, &device, &remote_path).await
+            } else {
+                execute_pull(pool, &device, &remote_path).await
+            };
+            match result {
                 Ok(data) => CommandResult::Success {
                     output: CommandOutput::Binary(data),
                 },
@@ -259,10 +488,81 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
+        Command::Cat { device, remote_path, as_root } => {
+            match execute_cat(pool, &device, &remote_path, as_root).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
         Command::Info { device, category } => {
             match execute_info(pool, &device, category).await {
-                Ok(info) => CommandResult::Success {
-                    output: CommandOutput::DeviceInfo(info),
+                Ok(info) => {
+                    // Keep the latest info for offline reference
+                    if let Err(e) = audb_core::features::config::info_cache::InfoCache::save(&device, &info) {
+                        warn!("Failed to cache device info for {}: {}", device, e);
+                    }
+                    if !info.device_model.is_empty() && info.device_model != "Unknown" {
+                        if let Err(e) = audb_core::features::config::device_store::DeviceStore::set_model(&device, &info.device_model) {
+                            warn!("Failed to store device model for {}: {}", device, e);
+                        }
+                    }
+                    CommandResult::Success {
+                        output: CommandOutput::DeviceInfo(Box::new(info)),
+                    }
+                }
+                Err(e) => {
+                    // Individual queries fall back to defaults, so any error here
+                    // means the device itself could not be reached
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::DeviceDisconnected
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Tap { device, x, y, event_device, duration_ms, orientation, clamp } => {
+            let placement = Placement { orientation, clamp };
+            match execute_tap(pool, &device, x, y, event_device, duration_ms, placement).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::FindElement { device, query, app } => {
+            match execute_find_element(pool, &device, query.as_deref(), app.as_deref()).await {
+                Ok(elements) => CommandResult::Success {
+                    output: CommandOutput::Elements(elements),
                 },
                 Err(e) => {
                     let kind = if e.to_string().contains("not found") {
@@ -278,8 +578,9 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Tap { device, x, y, event_device, duration_ms } => {
-            match execute_tap(pool, &device, x, y, event_device, duration_ms).await {
+        Command::Swipe { device, mode, event_device, profile, duration_ms, orientation, clamp } => {
+            let placement = Placement { orientation, clamp };
+            match execute_swipe(pool, &device, mode, event_device, profile, duration_ms, placement).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -297,8 +598,8 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Swipe { device, mode, event_device } => {
-            match execute_swipe(pool, &device, mode, event_device).await {
+        Command::Pinch { device, direction, center, distance, event_device } => {
+            match execute_pinch(pool, &device, direction, center, distance, event_device).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -335,8 +636,8 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Screenshot { device } => {
-            match execute_screenshot(pool, &device).await {
+        Command::Screenshot { device, display } => {
+            match execute_screenshot(pool, &device, display.unwrap_or(0)).await {
                 Ok(data) => CommandResult::Success {
                     output: CommandOutput::Binary(data),
                 },
@@ -354,8 +655,8 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Launch { device, app_name } => {
-            match execute_launch(pool, &device, &app_name).await {
+        Command::Launch { device, app_name, args, uri } => {
+            match execute_launch(pool, &device, &app_name, &args, uri.as_deref()).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -373,8 +674,9 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Stop { device, app_name } => {
-            match execute_stop(pool, &device, &app_name).await {
+        Command::Stop { device, app_name, force, timeout_secs } => {
+            let force_timeout = force.then(|| timeout_secs.unwrap_or(STOP_FORCE_TIMEOUT_SECS));
+            match execute_stop(pool, &device, &app_name, force_timeout).await {
                 Ok(output) => CommandResult::Success {
                     output: CommandOutput::Lines(output),
                 },
@@ -411,11 +713,162 @@ async fn process_command(command: Command, pool: &ConnectionPool) -> CommandResu
             }
         }
 
-        Command::Reconnect { device } => {
-            warn!("Reconnect command not yet implemented: device={:?}", device);
-            CommandResult::Error {
-                message: "Reconnect command not yet implemented in Phase 1".to_string(),
-                kind: audb_protocol::ErrorKind::ServerError,
+        Command::Reconnect { device } => match reconnect_devices(pool, device.as_deref()).await {
+            Ok(lines) => CommandResult::Success {
+                output: CommandOutput::Lines(lines),
+            },
+            Err(e) => {
+                let kind = if e.to_string().contains("not found") {
+                    audb_protocol::ErrorKind::DeviceNotFound
+                } else {
+                    audb_protocol::ErrorKind::ServerError
+                };
+                CommandResult::Error {
+                    message: format!("Failed to reconnect: {}", e),
+                    kind,
+                }
+            }
+        },
+
+        Command::DeviceHistory { device } => match pool.get_device_info(&device).await {
+            Ok(conn) => CommandResult::Success {
+                output: CommandOutput::History(
+                    conn.history
+                        .iter()
+                        .map(|event| audb_protocol::StateTransition {
+                            timestamp: event
+                                .at
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .map(|d| d.as_secs())
+                                .unwrap_or(0),
+                            from: event.from.to_string(),
+                            to: event.to.to_string(),
+                            reason: event.reason.clone(),
+                        })
+                        .collect(),
+                ),
+            },
+            Err(e) => CommandResult::Error {
+                message: e.to_string(),
+                kind: audb_protocol::ErrorKind::DeviceNotFound,
+            },
+        },
+
+        Command::HealthCheck { device } => {
+            match pool.health_check(&device).await {
+                Ok(latency) => CommandResult::Success {
+                    output: CommandOutput::Lines(vec![format!(
+                        "{} is alive ({} ms)",
+                        device,
+                        latency.as_millis()
+                    )]),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::DeviceDisconnected
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Builtin { device, builtin } => {
+            match execute_builtin(pool, &device, builtin).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Diagnose { device, topic } => {
+            match execute_diagnose(pool, &device, topic, metrics).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Radio { device, radio, enabled, force } => {
+            match execute_radio(pool, &device, radio, enabled, force).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::Service { device, action, unit, user } => {
+            match execute_service(pool, &device, action, &unit, user).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
+            }
+        }
+
+        Command::OpenFile { device, local_path, data } => {
+            match execute_open_file(pool, &device, &local_path, data).await {
+                Ok(output) => CommandResult::Success {
+                    output: CommandOutput::Lines(output),
+                },
+                Err(e) => {
+                    let kind = if e.to_string().contains("not found") {
+                        audb_protocol::ErrorKind::DeviceNotFound
+                    } else {
+                        audb_protocol::ErrorKind::CommandFailed
+                    };
+                    CommandResult::Error {
+                        message: e.to_string(),
+                        kind,
+                    }
+                }
             }
         }
 
@@ -490,51 +943,372 @@ async fn get_server_status(pool: &ConnectionPool) -> Result<ServerStatus> {
     })
 }
 
-/// Execute Install command
-async fn execute_install(
-    pool: &ConnectionPool,
-    device_host: &str,
-    rpm_path: &str,
-    rpm_data: Vec<u8>,
-) -> Result<Vec<String>> {
-    info!("Installing {} on device {}", rpm_path, device_host);
+/// Bring the pool in line with the enabled devices in the config
+///
+/// Devices that are still enabled keep their processor and session.
+async fn reload_devices(pool: &ConnectionPool) -> Result<Vec<String>> {
+    use std::collections::HashSet;
 
-    // Get just the filename
-    let file_name = std::path::Path::new(rpm_path)
-        .file_name()
-        .ok_or_else(|| anyhow!("Invalid RPM path"))?
-        .to_string_lossy()
-        .to_string();
+    let enabled = audb_core::features::config::device_store::DeviceStore::list_enabled()?;
+    let enabled_hosts: HashSet<String> = enabled.iter().map(|d| d.host.clone()).collect();
+    let pooled_hosts: HashSet<String> = pool.list_devices().await.into_iter().map(|(host, _)| host).collect();
 
-    // Write RPM data to temporary local file
-    let local_temp = std::env::temp_dir().join(&file_name);
-    std::fs::write(&local_temp, rpm_data)?;
+    let mut lines = vec![];
 
-    // Upload to device Downloads directory
-    let remote_path = PathBuf::from(format!("/home/defaultuser/Downloads/{}", file_name));
-    info!("Uploading {} to {}...", file_name, remote_path.display());
-    pool.upload_file(device_host, &local_temp, &remote_path).await?;
+    for host in pooled_hosts.difference(&enabled_hosts) {
+        if pool.remove_device(host).await {
+            info!("Removed device {} from pool", host);
+            lines.push(format!("Removed {}", host));
+        }
+    }
 
-    // Cleanup local temp file
-    std::fs::remove_file(&local_temp).ok();
+    for device in enabled {
+        if !pooled_hosts.contains(&device.host) {
+            info!("Added device {} to pool", device.host);
+            lines.push(format!("Added {}", device.display_name()));
+            pool.add_device(device).await;
+        }
+    }
 
-    // Install via D-Bus APM
-    info!("Installing package via APM...");
-    let install_command = format!(
-        "gdbus call --system --dest ru.omp.APM --object-path /ru/omp/APM --method ru.omp.APM.Install \"{}\" \"{{}}\"",
-        remote_path.display()
-    );
+    if lines.is_empty() {
+        lines.push("Devices already up to date".to_string());
+    }
+
+    Ok(lines)
+}
 
-    let output = pool.execute_command(device_host, &install_command, false).await?;
+/// Upload a `shell --from-file` script to the device, returning its remote path
+///
+/// The file goes over SFTP byte for byte, so it never passes through the
+/// quoting that wraps root and `--as-user` commands.
+async fn upload_shell_script(pool: &ConnectionPool, device_host: &str, script: &str) -> Result<String> {
+    let file_name = temp_files::unique_name("audb-script", "sh");
 
-    // Cleanup remote file
-    let cleanup_command = format!("rm -f {}", remote_path.display());
-    pool.execute_command(device_host, &cleanup_command, false).await.ok();
+    let local_temp = std::env::temp_dir().join(&file_name);
+    std::fs::write(&local_temp, script)?;
+
+    let remote_path = format!("/tmp/{}", file_name);
+    let result = pool.upload_file(device_host, &local_temp, &PathBuf::from(&remote_path)).await;
+    temp_files::remove_local(&local_temp);
+    result?;
+
+    Ok(remote_path)
+}
+
+/// Re-read device config from disk and rebuild pool entries with it
+///
+/// Without a host every pooled and enabled device is reconnected. Each device
+/// gets a fresh processor built from its current config (key path, port,
+/// password), so config edits apply without restarting the server.
+async fn reconnect_devices(pool: &ConnectionPool, host: Option<&str>) -> Result<Vec<String>> {
+    let configured = audb_core::features::config::device_store::DeviceStore::list()?;
+
+    let hosts: Vec<String> = match host {
+        Some(host) => {
+            if !configured.iter().any(|d| d.host == host) {
+                return Err(anyhow!("Device {} not found in config", host));
+            }
+            vec![host.to_string()]
+        }
+        None => {
+            let mut hosts: Vec<String> = pool.list_devices().await.into_iter().map(|(host, _)| host).collect();
+            for device in configured.iter().filter(|d| d.enabled) {
+                if !hosts.contains(&device.host) {
+                    hosts.push(device.host.clone());
+                }
+            }
+            hosts.sort();
+            hosts
+        }
+    };
+
+    let mut lines = vec![];
+
+    for host in hosts {
+        let device = configured.iter().find(|d| d.host == host && d.enabled).cloned();
+        let Some(device) = device else {
+            // Gone from the config or disabled since the server loaded it
+            if pool.remove_device(&host).await {
+                info!("Removed device {} from pool", host);
+                lines.push(format!("Removed {} (no longer enabled in config)", host));
+            } else {
+                lines.push(format!("Skipped {} (disabled in config)", host));
+            }
+            continue;
+        };
+
+        let name = device.display_name();
+        info!("Reconnecting {} with reloaded config", host);
+        pool.add_device(device).await;
+
+        // The processor connects lazily; a trivial command opens the session now
+        // (a health check only probes a session that is already open)
+        let started = std::time::Instant::now();
+        match pool.execute_command(&host, "true", false).await {
+            Ok(_) => lines.push(format!("Reconnected {} ({}ms)", name, started.elapsed().as_millis())),
+            Err(e) => {
+                warn!("Reconnect to {} failed: {}", host, e);
+                lines.push(format!("Failed to reconnect {}: {}", name, e));
+            }
+        }
+    }
 
+    if lines.is_empty() {
+        lines.push("No devices to reconnect".to_string());
+    }
+
+    Ok(lines)
+}
+
+/// Execute Install command
+async fn execute_install(
+    pool: &ConnectionPool,
+    device_host: &str,
+    rpm_path: &str,
+    rpm_data: Vec<u8>,
+    check_deps: bool,
+    backend: audb_protocol::InstallBackend,
+) -> Result<Vec<String>> {
+    info!("Installing {} on device {} via {}", rpm_path, device_host, backend.name());
+
+    let (file_name, remote_path) = upload_rpm(pool, device_host, rpm_path, rpm_data).await?;
+    let remote_rpm = remote_path.to_string_lossy().to_string();
+
+    let result = install_uploaded_rpm(pool, device_host, &file_name, &remote_rpm, check_deps, backend).await;
+
+    // Removed whether or not the install went through
+    if let Err(e) = temp_files::remove_remote(pool, device_host, &remote_rpm, false).await {
+        warn!("Failed to remove staged package {} from {}: {}", remote_rpm, device_host, e);
+    }
+
+    let output = result?;
     info!("Package installed successfully");
     Ok(output)
 }
 
+/// Check dependencies of an uploaded RPM (if asked) and install it
+async fn install_uploaded_rpm(
+    pool: &ConnectionPool,
+    device_host: &str,
+    file_name: &str,
+    remote_rpm: &str,
+    check_deps: bool,
+    backend: audb_protocol::InstallBackend,
+) -> Result<Vec<String>> {
+    use audb_protocol::InstallBackend;
+
+    if check_deps {
+        info!("Checking package dependencies...");
+        let missing = find_missing_dependencies(pool, device_host, remote_rpm).await?;
+        if !missing.is_empty() {
+            let details: Vec<String> = missing.iter().map(|dep| format!("missing dependency {}", dep)).collect();
+            return Err(anyhow!("Not installing {}: {}", file_name, details.join(", ")));
+        }
+    }
+
+    // APM installs through D-Bus as the user; pkcon and rpm need root
+    let escaped_path = escape_single_quote(remote_rpm);
+    let (install_command, as_root) = match backend {
+        InstallBackend::Apm => (
+            format!(
+                "gdbus call --system --dest ru.omp.APM --object-path /ru/omp/APM --method ru.omp.APM.Install \"{}\" \"{{}}\"",
+                remote_rpm
+            ),
+            false,
+        ),
+        InstallBackend::Pkcon => (format!("pkcon -y install-local '{}'", escaped_path), true),
+        InstallBackend::Rpm => (format!("rpm -U --replacepkgs '{}'", escaped_path), true),
+    };
+
+    info!("Installing package via {}...", backend.name());
+    pool.execute_command(device_host, &install_command, as_root).await
+}
+
+/// Prefix of RPMs staged in Downloads; the name is fixed per package so a
+/// retried install replaces a file left behind by an interrupted one
+const STAGED_RPM_PREFIX: &str = ".audb-staged-";
+
+/// Upload an RPM to a staging name in the device's Downloads directory,
+/// returning its file name and remote path
+///
+/// The uploaded size is checked against the package, and a partial or
+/// mismatched upload is removed before returning the error.
+async fn upload_rpm(
+    pool: &ConnectionPool,
+    device_host: &str,
+    rpm_path: &str,
+    rpm_data: Vec<u8>,
+) -> Result<(String, PathBuf)> {
+    // Get just the filename
+    let file_name = std::path::Path::new(rpm_path)
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid RPM path"))?
+        .to_string_lossy()
+        .to_string();
+
+    // Write RPM data to temporary local file
+    let local_temp = std::env::temp_dir().join(&file_name);
+    let expected_size = rpm_data.len() as u64;
+    std::fs::write(&local_temp, rpm_data)?;
+
+    // Upload to device Downloads directory
+    let home = remote_home(pool, device_host).await?;
+    let remote_path = PathBuf::from(format!("{}/Downloads/{}{}", home, STAGED_RPM_PREFIX, file_name));
+    let remote_rpm = remote_path.to_string_lossy().to_string();
+    info!("Uploading {} to {}...", file_name, remote_rpm);
+    let mut result = pool.upload_file(device_host, &local_temp, &remote_path).await;
+
+    // Cleanup local temp file
+    temp_files::remove_local(&local_temp);
+
+    if result.is_ok() {
+        let size_command = format!("stat -c %s '{}'", escape_single_quote(&remote_rpm));
+        let size = pool
+            .execute_command(device_host, &size_command, false)
+            .await
+            .ok()
+            .and_then(|lines| lines.first().and_then(|line| line.trim().parse::<u64>().ok()));
+        if size != Some(expected_size) {
+            result = Err(anyhow!(
+                "Upload of {} is incomplete ({} of {} bytes on the device)",
+                file_name,
+                size.map_or("unknown".to_string(), |s| s.to_string()),
+                expected_size
+            ));
+        }
+    }
+
+    match result {
+        Ok(()) => Ok((file_name, remote_path)),
+        Err(e) => {
+            temp_files::remove_remote(pool, device_host, &remote_rpm, false).await.ok();
+            Err(e)
+        }
+    }
+}
+
+/// Execute Validate command - check an RPM would install on this device
+///
+/// `rpm -U --test` resolves dependencies and conflicts against the device's
+/// installed packages without touching them.
+async fn execute_validate(
+    pool: &ConnectionPool,
+    device_host: &str,
+    rpm_path: &str,
+    rpm_data: Vec<u8>,
+) -> Result<Vec<String>> {
+    info!("Test-installing {} on device {}", rpm_path, device_host);
+
+    let (file_name, remote_path) = upload_rpm(pool, device_host, rpm_path, rpm_data).await?;
+    let remote_rpm = remote_path.to_string_lossy().to_string();
+
+    let test_command = format!("rpm -U --test --replacepkgs '{}' 2>&1", escape_single_quote(&remote_rpm));
+    let result = pool.execute_command(device_host, &test_command, true).await;
+
+    temp_files::remove_remote(pool, device_host, &remote_rpm, false).await.ok();
+
+    match result {
+        Ok(output) => {
+            let mut lines: Vec<String> = output.into_iter().filter(|line| !line.is_empty()).collect();
+            lines.push(format!("✓ {} would install on this device", file_name));
+            Ok(lines)
+        }
+        Err(e) => Err(anyhow!("✗ {} would not install on this device:\n{}", file_name, e)),
+    }
+}
+
+/// List the requirements of an RPM on the device that no installed package provides
+///
+/// Only capability names are checked; version constraints are not compared.
+async fn find_missing_dependencies(
+    pool: &ConnectionPool,
+    device_host: &str,
+    remote_rpm: &str,
+) -> Result<Vec<String>> {
+    let requires = pool
+        .execute_command(device_host, &format!("rpm -qpR '{}'", escape_single_quote(remote_rpm)), false)
+        .await
+        .map_err(|e| anyhow!("Failed to read package requirements: {}", e))?
+        .join("\n");
+
+    // "name [op version]" per line; rpmlib() entries are features of rpm itself
+    let mut names: Vec<&str> = requires
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| !name.starts_with("rpmlib("))
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    if names.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let check_command = names
+        .iter()
+        .map(|name| {
+            let escaped = escape_single_quote(name);
+            format!("rpm -q --whatprovides '{}' >/dev/null 2>&1 || echo '{}'", escaped, escaped)
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let missing = pool
+        .execute_command(device_host, &format!("{}; true", check_command), false)
+        .await?
+        .join("\n");
+
+    Ok(missing.lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// Execute FindElement command - walk the accessibility tree on the device
+///
+/// Returns every element without a query, else the matching ones (best first).
+async fn execute_find_element(
+    pool: &ConnectionPool,
+    device_host: &str,
+    query: Option<&str>,
+    app: Option<&str>,
+) -> Result<Vec<audb_protocol::UiElement>> {
+    use audb_core::features::input::ui::{find_elements, parse_ui_dump};
+
+    info!("Reading accessibility tree on device {}", device_host);
+
+    pool.ensure_script(device_host, "ui-dump", REMOTE_UI_DUMP_PATH, UI_DUMP_SCRIPT).await?;
+
+    // AT-SPI lives on the user's session bus, so this runs as the user
+    let mut command = format!("python3 {}", REMOTE_UI_DUMP_PATH);
+    if let Some(app) = app {
+        command.push_str(&format!(" --app {}", quote_arg(app)));
+    }
+    let output = pool.execute_command(device_host, &command, false).await
+        .map_err(|e| {
+            let hint = if e.to_string().contains("AT-SPI") {
+                "\nAccessibility services may not be running: check that at-spi2 is installed, \
+                 or enable accessibility in Settings and restart the app"
+            } else {
+                ""
+            };
+            anyhow!("Failed to read the accessibility tree: {}{}", e, hint)
+        })?
+        .join("\n");
+
+    let elements = parse_ui_dump(&output);
+    let Some(query) = query else {
+        return Ok(elements);
+    };
+
+    let found: Vec<_> = find_elements(&elements, query).into_iter().cloned().collect();
+    if found.is_empty() {
+        return Err(anyhow!(
+            "No on-screen element named '{}' ({} elements checked; see 'audb ui-dump')",
+            query,
+            elements.len()
+        ));
+    }
+    Ok(found)
+}
+
 /// Execute Tap command
 async fn execute_tap(
     pool: &ConnectionPool,
@@ -543,19 +1317,35 @@ async fn execute_tap(
     y: u16,
     event_device: Option<String>,
     duration_ms: Option<u32>,
+    placement: Placement,
 ) -> Result<Vec<String>> {
     info!("Tapping at ({}, {}) on device {}", x, y, device_host);
 
-    // Validate coordinates
-    if x > 4096 || y > 4096 {
-        return Err(anyhow!("Coordinates out of range: ({}, {}). Max: 4096x4096", x, y));
-    }
+    let orientation = resolve_orientation(pool, device_host, placement.orientation).await;
+    let mut clamp_notes = vec![];
+
+    // Validate coordinates (or pull them onto the screen)
+    let (x, y) = if placement.clamp {
+        let bounds = screen_bounds(pool, device_host, orientation).await;
+        clamp_point((x, y), bounds, &mut clamp_notes)
+    } else {
+        if x > 4096 || y > 4096 {
+            return Err(anyhow!("Coordinates out of range: ({}, {}). Max: 4096x4096", x, y));
+        }
+        (x, y)
+    };
 
     // Ensure tap script is present (uses persistent connection)
     pool.ensure_script(device_host, "tap", REMOTE_TAP_PATH, TAP_SCRIPT).await?;
 
+    let (event_device, mut notes) = resolve_event_device(pool, device_host, event_device).await;
+    notes.extend(clamp_notes);
+
     // Build tap command with optional --event and --duration flags
-    let mut tap_command = format!("python3 {} {} {}", REMOTE_TAP_PATH, x, y);
+    let mut tap_command = format!(
+        "{}python3 {} {} {}",
+        screen_env(pool, device_host).await, REMOTE_TAP_PATH, x, y
+    );
     
     if let Some(ref event_dev) = event_device {
         tap_command.push_str(&format!(" --event {}", event_dev));
@@ -565,10 +1355,117 @@ async fn execute_tap(
         tap_command.push_str(&format!(" --duration {}", duration));
     }
 
+    if let Some(name) = orientation {
+        tap_command.push_str(&format!(" --orientation {}", name));
+    }
+
     info!("Executing tap with devel-su...");
     let output = pool.execute_command(device_host, &tap_command, true).await?;
 
-    Ok(output)
+    notes.extend(output);
+    notes.push(injected_line(&format!("{},{}", x, y), event_device.as_deref()));
+    Ok(notes)
+}
+
+/// Final line of a gesture's output: `injected: <points> via <event device|uinput>`
+///
+/// Points are in screen coordinates (before rotation handling), so automated
+/// tests can check what was hit.
+fn injected_line(points: &str, event_device: Option<&str>) -> String {
+    format!("injected: {} via {}", points, event_device.unwrap_or("uinput"))
+}
+
+/// How tap/swipe coordinates are placed on the screen
+struct Placement {
+    orientation: audb_protocol::ScreenOrientation,
+    /// Clamp coordinates onto the screen instead of rejecting them
+    clamp: bool,
+}
+
+/// Largest valid coordinates as the user currently sees the screen
+///
+/// Falls back to the 4096 protocol limit if the screen size is unknown.
+async fn screen_bounds(pool: &ConnectionPool, device_host: &str, orientation: Option<&str>) -> (u16, u16) {
+    match get_screen_dimensions(pool, device_host).await {
+        Ok((width, height)) => {
+            let (width, height) = match orientation {
+                Some("landscape" | "inverted-landscape") => (height, width),
+                _ => (width, height),
+            };
+            let max = |size: u32| size.saturating_sub(1).min(4096) as u16;
+            (max(width), max(height))
+        }
+        Err(e) => {
+            warn!("{}, clamping to 4096x4096", e);
+            (4096, 4096)
+        }
+    }
+}
+
+/// Clamp a point into `0..=max`, adding a warning if it moved
+fn clamp_point((x, y): (u16, u16), (max_x, max_y): (u16, u16), notes: &mut Vec<String>) -> (u16, u16) {
+    let clamped = (x.min(max_x), y.min(max_y));
+    if clamped != (x, y) {
+        notes.push(format!(
+            "warning: clamped ({},{}) to ({},{}) to fit the {}x{} screen",
+            x,
+            y,
+            clamped.0,
+            clamped.1,
+            max_x as u32 + 1,
+            max_y as u32 + 1
+        ));
+    }
+    clamped
+}
+
+/// Orientation to pass to the tap/swipe scripts
+///
+/// `Auto` reads the orientation lipstick reports for the user session (the
+/// scripts run as root and cannot see it). None leaves it to the script,
+/// which assumes portrait, i.e. no transform.
+async fn resolve_orientation(
+    pool: &ConnectionPool,
+    device_host: &str,
+    requested: audb_protocol::ScreenOrientation,
+) -> Option<&'static str> {
+    use audb_protocol::ScreenOrientation;
+
+    if requested != ScreenOrientation::Auto {
+        return requested.script_name();
+    }
+
+    let output = pool
+        .execute_command(device_host, "dconf read /desktop/lipstick-jolla-home/dialog_orientation", false)
+        .await
+        .ok()?
+        .join("\n");
+
+    // Qt::ScreenOrientation values
+    let detected = match output.trim().parse::<u32>().ok()? {
+        1 => ScreenOrientation::Portrait,
+        2 => ScreenOrientation::Landscape,
+        4 => ScreenOrientation::InvertedPortrait,
+        8 => ScreenOrientation::InvertedLandscape,
+        other => {
+            warn!("Unknown screen orientation {} on {}, not transforming coordinates", other, device_host);
+            return None;
+        }
+    };
+    detected.script_name()
+}
+
+/// `XMAX=.. YMAX=.. ` prefix telling a gesture script the real screen size
+///
+/// Empty if the size cannot be determined; the script then uses its defaults.
+async fn screen_env(pool: &ConnectionPool, device_host: &str) -> String {
+    match get_screen_dimensions(pool, device_host).await {
+        Ok((width, height)) => format!("XMAX={} YMAX={} ", width, height),
+        Err(e) => {
+            warn!("{}, using script defaults", e);
+            String::new()
+        }
+    }
 }
 
 /// Execute Swipe command
@@ -577,25 +1474,51 @@ async fn execute_swipe(
     device_host: &str,
     mode: audb_protocol::SwipeMode,
     event_device: Option<String>,
+    profile: audb_protocol::SwipeProfile,
+    duration_ms: Option<u32>,
+    placement: Placement,
 ) -> Result<Vec<String>> {
     info!("Executing swipe on device {}", device_host);
 
-    // Validate coordinates if needed
-    if let audb_protocol::SwipeMode::Coords { x1, y1, x2, y2 } = &mode {
-        for coord in [x1, y1, x2, y2] {
-            if *coord > 4096 {
-                return Err(anyhow!("Coordinate out of range: {}. Max: 4096", coord));
-            }
+    if let Some(ms) = duration_ms {
+        if !(10..=10000).contains(&ms) {
+            return Err(anyhow!("Swipe duration out of range: {}ms. Use 10-10000ms", ms));
         }
     }
 
+    let orientation = resolve_orientation(pool, device_host, placement.orientation).await;
+    let mut clamp_notes = vec![];
+
+    // Validate coordinates if needed (or pull them onto the screen)
+    let mode = match mode {
+        audb_protocol::SwipeMode::Coords { x1, y1, x2, y2 } if placement.clamp => {
+            let bounds = screen_bounds(pool, device_host, orientation).await;
+            let (x1, y1) = clamp_point((x1, y1), bounds, &mut clamp_notes);
+            let (x2, y2) = clamp_point((x2, y2), bounds, &mut clamp_notes);
+            audb_protocol::SwipeMode::Coords { x1, y1, x2, y2 }
+        }
+        audb_protocol::SwipeMode::Coords { x1, y1, x2, y2 } => {
+            for coord in [x1, y1, x2, y2] {
+                if coord > 4096 {
+                    return Err(anyhow!("Coordinate out of range: {}. Max: 4096", coord));
+                }
+            }
+            mode
+        }
+        direction => direction,
+    };
+
     // Ensure swipe script is present (uses persistent connection)
     pool.ensure_script(device_host, "swipe", REMOTE_SWIPE_PATH, SWIPE_SCRIPT).await?;
 
+    let (event_device, mut notes) = resolve_event_device(pool, device_host, event_device).await;
+    notes.extend(clamp_notes);
+
     // Build command based on mode
+    let env = screen_env(pool, device_host).await;
     let base_cmd = match mode {
         audb_protocol::SwipeMode::Coords { x1, y1, x2, y2 } => {
-            format!("python3 {} {} {} {} {}", REMOTE_SWIPE_PATH, x1, y1, x2, y2)
+            format!("{}python3 {} {} {} {} {}", env, REMOTE_SWIPE_PATH, x1, y1, x2, y2)
         }
         audb_protocol::SwipeMode::Direction(dir) => {
             let dir_arg = match dir {
@@ -604,41 +1527,194 @@ async fn execute_swipe(
                 audb_protocol::SwipeDirection::Up => "du",
                 audb_protocol::SwipeDirection::Down => "ud",
             };
-            format!("python3 {} {}", REMOTE_SWIPE_PATH, dir_arg)
+            format!("{}python3 {} {}", env, REMOTE_SWIPE_PATH, dir_arg)
         }
     };
 
     // Add --event flag if specified
-    let swipe_command = if let Some(ref event_dev) = event_device {
+    let mut swipe_command = if let Some(ref event_dev) = event_device {
         format!("{} --event {}", base_cmd, event_dev)
     } else {
         base_cmd
     };
 
+    let profile_arg = match profile {
+        audb_protocol::SwipeProfile::Linear => "linear",
+        audb_protocol::SwipeProfile::EaseIn => "ease-in",
+        audb_protocol::SwipeProfile::EaseOut => "ease-out",
+        audb_protocol::SwipeProfile::Fling => "fling",
+    };
+    swipe_command.push_str(&format!(" --profile {}", profile_arg));
+
+    if let Some(ms) = duration_ms {
+        swipe_command.push_str(&format!(" --duration {}", ms));
+    }
+
+    if let Some(name) = orientation {
+        swipe_command.push_str(&format!(" --orientation {}", name));
+    }
+
     info!("Executing swipe with devel-su...");
-    let output = pool.execute_command(device_host, &swipe_command, true).await?;
+    let output = pool.execute_command(device_host, &swipe_command, true).await?.join("\n");
+
+    // The script reports the endpoints it resolved as "endpoints: x0,y0 x1,y1"
+    let mut endpoints = None;
+    for line in output.lines() {
+        match line.strip_prefix("endpoints: ") {
+            Some(points) => endpoints = Some(points.replacen(' ', " -> ", 1)),
+            None => notes.push(line.to_string()),
+        }
+    }
 
-    Ok(output)
+    if let Some(points) = endpoints {
+        notes.push(injected_line(&points, event_device.as_deref()));
+    }
+    Ok(notes)
+}
+
+/// Execute Pinch command
+async fn execute_pinch(
+    pool: &ConnectionPool,
+    device_host: &str,
+    direction: audb_protocol::PinchDirection,
+    center: Option<(u16, u16)>,
+    distance: Option<u16>,
+    event_device: Option<String>,
+) -> Result<Vec<String>> {
+    info!("Executing pinch {:?} on device {}", direction, device_host);
+
+    // Validate center if provided
+    if let Some((x, y)) = center {
+        if x > 4096 || y > 4096 {
+            return Err(anyhow!("Center out of range: ({}, {}). Max: 4096x4096", x, y));
+        }
+    }
+
+    // Ensure pinch script is present (uses persistent connection)
+    pool.ensure_script(device_host, "pinch", REMOTE_PINCH_PATH, PINCH_SCRIPT).await?;
+
+    let (event_device, mut notes) = resolve_event_device(pool, device_host, event_device).await;
+
+    let (width, height) = get_screen_dimensions(pool, device_host).await?;
+    let dir_arg = match direction {
+        audb_protocol::PinchDirection::In => "in",
+        audb_protocol::PinchDirection::Out => "out",
+    };
+
+    let mut pinch_command = format!(
+        "XMAX={} YMAX={} python3 {} {}",
+        width, height, REMOTE_PINCH_PATH, dir_arg
+    );
+
+    if let Some((x, y)) = center {
+        pinch_command.push_str(&format!(" --center {},{}", x, y));
+    }
+
+    if let Some(distance) = distance {
+        pinch_command.push_str(&format!(" --distance {}", distance));
+    }
+
+    if let Some(ref event_dev) = event_device {
+        pinch_command.push_str(&format!(" --event {}", event_dev));
+    }
+
+    info!("Executing pinch with devel-su...");
+    let output = pool.execute_command(device_host, &pinch_command, true).await?;
+
+    notes.extend(output);
+    Ok(notes)
+}
+
+/// Find the device's touchscreen event node by its input device name
+async fn detect_touchscreen(pool: &ConnectionPool, device_host: &str) -> Option<String> {
+    // Same name patterns as find_touchscreen() in the input scripts
+    let detect_cmd = "for n in /sys/class/input/event*/device/name; do \
+        case \"$(tr 'A-Z' 'a-z' < \"$n\")\" in \
+        *touch*|*tpd*|*ts*|*silead*|*goodix*|*fts*|*atmel*|*synaptics*|*elan*|*chsc*|*himax*) \
+        e=${n%/device/name}; echo /dev/input/${e##*/}; break;; esac; done";
+
+    pool.execute_command(device_host, detect_cmd, false).await
+        .ok()
+        .and_then(|output| {
+            output.join("\n")
+                .lines()
+                .map(str::trim)
+                .find(|line| line.starts_with("/dev/input/event"))
+                .map(String::from)
+        })
+}
+
+/// Resolve `--event auto` to the device's touchscreen node
+///
+/// Returns the event device to pass to the input script plus any notes for the
+/// user. If no touchscreen is detected, falls back to the default (uinput) path.
+async fn resolve_event_device(
+    pool: &ConnectionPool,
+    device_host: &str,
+    event_device: Option<String>,
+) -> (Option<String>, Vec<String>) {
+    if event_device.as_deref() != Some("auto") {
+        return (event_device, Vec::new());
+    }
+
+    match detect_touchscreen(pool, device_host).await {
+        Some(path) => {
+            info!("Auto-detected touchscreen {} on {}", path, device_host);
+            (Some(path), Vec::new())
+        }
+        None => {
+            warn!("No touchscreen detected on {}, using default input path", device_host);
+            (None, vec!["warning: no touchscreen node detected, using default (uinput) input path".to_string()])
+        }
+    }
 }
 
 /// Get screen dimensions from device
-async fn get_screen_dimensions(pool: &ConnectionPool, device_host: &str) -> (u32, u32) {
+///
+/// Resolved once per device and cached in the pool. Fails rather than guessing,
+/// since gestures placed for the wrong resolution land in the wrong spot.
+async fn get_screen_dimensions(pool: &ConnectionPool, device_host: &str) -> Result<(u32, u32)> {
+    if let Some(size) = pool.screen_size(device_host).await {
+        return Ok(size);
+    }
+
     // Query screen resolution via D-Bus
     let dbus_cmd = "gdbus call --system --dest ru.omp.deviceinfo --object-path /ru/omp/deviceinfo/Features --method ru.omp.deviceinfo.Features.getScreenResolution";
-    
+
+    let mut size = None;
     if let Ok(output) = pool.execute_command(device_host, dbus_cmd, false).await {
         if let Some(line) = output.first() {
             // Parse format like "('720x1440',)"
             let s = line.trim_matches(|c| c == '(' || c == ')' || c == ',' || c == '\'').trim();
-            if let Some((w, h)) = s.split_once('x') {
-                if let (Ok(width), Ok(height)) = (w.parse(), h.parse()) {
-                    return (width, height);
-                }
-            }
+            size = parse_resolution(s, 'x');
         }
     }
-    // Default fallback
-    (720, 1440)
+
+    // Fall back to the kernel's view: the current DRM mode ("720x1440"),
+    // then the framebuffer size ("720,1440")
+    if size.is_none() {
+        warn!("Screen resolution D-Bus query failed on {}, reading it from sysfs", device_host);
+        let sysfs_cmd = "cat /sys/class/drm/card*-*/modes 2>/dev/null | head -n1; echo; cat /sys/class/graphics/fb0/virtual_size 2>/dev/null";
+        if let Ok(output) = pool.execute_command(device_host, sysfs_cmd, false).await {
+            let output = output.join("\n");
+            size = output
+                .lines()
+                .map(str::trim)
+                .find_map(|line| parse_resolution(line, 'x').or_else(|| parse_resolution(line, ',')));
+        }
+    }
+
+    let size = size.ok_or_else(|| anyhow!("Could not determine the screen resolution of {}", device_host))?;
+    pool.set_screen_size(device_host, size).await;
+    Ok(size)
+}
+
+/// Parse "WIDTHxHEIGHT"-style text, ignoring zero sizes
+fn parse_resolution(text: &str, separator: char) -> Option<(u32, u32)> {
+    let (w, h) = text.split_once(separator)?;
+    let width: u32 = w.trim().parse().ok()?;
+    let height: u32 = h.trim().parse().ok()?;
+    (width > 0 && height > 0).then_some((width, height))
 }
 
 /// Execute Key command
@@ -663,7 +1739,7 @@ async fn execute_key(
 
         // Home - Sailfish uses swipe from bottom edge, simulate with swipe gesture
         "home" => {
-            let (width, height) = get_screen_dimensions(pool, device_host).await;
+            let (width, height) = get_screen_dimensions(pool, device_host).await?;
             let center_x = width / 2;
             let center_y = height / 2;
             
@@ -681,7 +1757,7 @@ async fn execute_key(
 
         // Back - Sailfish uses swipe from left edge
         "back" => {
-            let (width, height) = get_screen_dimensions(pool, device_host).await;
+            let (width, height) = get_screen_dimensions(pool, device_host).await?;
             
             pool.ensure_script(device_host, "swipe", REMOTE_SWIPE_PATH, SWIPE_SCRIPT).await?;
             // Use lr direction with correct screen dimensions
@@ -733,7 +1809,7 @@ os.close(fd)
 
         // Menu - swipe from top (shows events/notifications)
         "menu" => {
-            let (width, height) = get_screen_dimensions(pool, device_host).await;
+            let (width, height) = get_screen_dimensions(pool, device_host).await?;
             
             pool.ensure_script(device_host, "swipe", REMOTE_SWIPE_PATH, SWIPE_SCRIPT).await?;
             // Use ud direction with correct screen dimensions
@@ -745,7 +1821,7 @@ os.close(fd)
 
         // Close app - same as home gesture (swipe from bottom edge)
         "close" => {
-            let (width, height) = get_screen_dimensions(pool, device_host).await;
+            let (width, height) = get_screen_dimensions(pool, device_host).await?;
             let center_x = width / 2;
             let center_y = height / 2;
             
@@ -795,14 +1871,16 @@ os.close(fd)
 async fn execute_screenshot(
     pool: &ConnectionPool,
     device_host: &str,
+    display_index: u32,
 ) -> Result<Vec<u8>> {
-    info!("Taking screenshot on device {}", device_host);
+    info!("Taking screenshot of display {} on device {}", display_index, device_host);
+    check_screenshot_display(pool, device_host, display_index).await?;
 
     // Generate timestamped filename
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let remote_filename = format!("/home/defaultuser/Pictures/Screenshots/audb_screenshot_{}.png", timestamp);
+    let home = remote_home(pool, device_host).await?;
+    let remote_filename = format!("{}/Pictures/Screenshots/audb_screenshot_{}.png", home, timestamp);
 
-    // Execute D-Bus screenshot command (needs root)
     let dbus_command = format!(
         "dbus-send --session --print-reply \
          --dest=org.nemomobile.lipstick \
@@ -812,52 +1890,183 @@ async fn execute_screenshot(
         remote_filename
     );
 
-    pool.execute_command(device_host, &dbus_command, true).await?;
-
-    // Read screenshot file as base64 (needs root)
-    let read_command = format!("base64 {}", remote_filename);
-    let base64_lines = pool.execute_command(device_host, &read_command, true).await?;
-    let base64_data = base64_lines.join("").replace(['\n', '\r'], "");
-
-    // Decode base64 to binary
-    use base64::Engine;
-    let binary_data = base64::engine::general_purpose::STANDARD.decode(&base64_data)
-        .map_err(|e| anyhow!("Failed to decode base64 screenshot: {}", e))?;
+    // Many devices accept the call from the user session and leave the file
+    // readable by defaultuser, so try without devel-su (and its root password) first
+    let as_user = async {
+        save_screenshot(pool, device_host, &dbus_command, &remote_filename, false, 1).await?;
+        read_screenshot(pool, device_host, &remote_filename, false).await
+    };
+    match as_user.await {
+        Ok(binary_data) => {
+            info!("Screenshot captured without root ({} bytes)", binary_data.len());
+            return Ok(binary_data);
+        }
+        Err(e) if e.to_string().contains("not found") => return Err(e),
+        Err(e) => info!("Screenshot as user failed ({}), retrying with devel-su", e),
+    }
 
-    // Cleanup remote file
-    let cleanup_cmd = format!("rm -f {}", remote_filename);
-    pool.execute_command(device_host, &cleanup_cmd, true).await.ok();
+    save_screenshot(pool, device_host, &dbus_command, &remote_filename, true, SCREENSHOT_ATTEMPTS).await?;
+    let binary_data = read_screenshot(pool, device_host, &remote_filename, true).await?;
 
     info!("Screenshot captured successfully ({} bytes)", binary_data.len());
     Ok(binary_data)
 }
 
-/// Execute Launch command
-async fn execute_launch(
-    pool: &ConnectionPool,
-    device_host: &str,
-    app_name: &str,
-) -> Result<Vec<String>> {
-    info!("Launching {} on device {}", app_name, device_host);
-
-    // Validate app name
-    if app_name.is_empty() {
-        return Err(anyhow!("App name cannot be empty"));
+/// Fail unless `display` can be captured, rather than quietly capturing the primary one
+///
+/// Lipstick's saveScreenshot has no output argument and always captures the
+/// primary display (0). Other indexes are checked against the connected DRM
+/// connectors so a missing display is reported as such.
+async fn check_screenshot_display(pool: &ConnectionPool, device_host: &str, display: u32) -> Result<()> {
+    if display == 0 {
+        return Ok(());
     }
-    if !app_name.contains('.') {
+
+    let output = pool
+        .execute_command(device_host, "cat /sys/class/drm/card*-*/status 2>/dev/null; true", false)
+        .await?;
+    // Devices with no DRM connectors listed (e.g. hwcomposer only) still have the primary display
+    let connected = output.iter().filter(|line| line.trim() == "connected").count().max(1);
+
+    if display as usize >= connected {
         return Err(anyhow!(
-            "Invalid app name: '{}'. Expected D-Bus format: ru.domain.AppName",
-            app_name
+            "Display {} does not exist: the device has {} connected display(s), numbered from 0",
+            display,
+            connected
         ));
     }
+    Err(anyhow!(
+        "Display {} is connected, but the compositor can only capture the primary display (0)",
+        display
+    ))
+}
 
-    // Build D-Bus launch command
-    let launch_command = format!(
-        "gdbus call --system --dest ru.omp.RuntimeManager \
-         --object-path /ru/omp/RuntimeManager/Control1 \
-         --method ru.omp.RuntimeManager.Control1.Start \"{}\"",
-        app_name
-    );
+/// Ask Lipstick to save a screenshot, retrying until the file exists
+async fn save_screenshot(
+    pool: &ConnectionPool,
+    device_host: &str,
+    dbus_command: &str,
+    remote_filename: &str,
+    as_root: bool,
+    attempts: u32,
+) -> Result<()> {
+    // Lipstick can be briefly unavailable (e.g. during app transitions),
+    // so retry the D-Bus call until the file actually exists
+    let check_command = format!("test -s {} && echo ok", remote_filename);
+
+    for attempt in 1..=attempts {
+        let error = match pool.execute_command(device_host, dbus_command, as_root).await {
+            Ok(_) => {
+                let exists = pool.execute_command(device_host, &check_command, as_root).await
+                    .map(|output| output.iter().any(|line| line.trim() == "ok"))
+                    .unwrap_or(false);
+                if exists {
+                    return Ok(());
+                }
+                String::from("screenshot file was not created")
+            }
+            Err(e) if e.to_string().contains("not found") => return Err(e),
+            Err(e) => e.to_string(),
+        };
+
+        if attempt == attempts {
+            return Err(anyhow!(
+                "Screenshot service unavailable after {} attempt(s): {}",
+                attempts,
+                error
+            ));
+        }
+
+        warn!("Screenshot attempt {}/{} failed: {}", attempt, attempts, error);
+        tokio::time::sleep(std::time::Duration::from_millis(SCREENSHOT_RETRY_DELAY_MS)).await;
+    }
+
+    Ok(())
+}
+
+/// Read a saved screenshot back (as base64) and remove it from the device
+async fn read_screenshot(
+    pool: &ConnectionPool,
+    device_host: &str,
+    remote_filename: &str,
+    as_root: bool,
+) -> Result<Vec<u8>> {
+    let read_command = format!("base64 {}", remote_filename);
+    let base64_lines = pool.execute_command(device_host, &read_command, as_root).await?;
+    let base64_data = base64_lines.join("").replace(['\n', '\r'], "");
+
+    // Decode base64 to binary
+    use base64::Engine;
+    let binary_data = base64::engine::general_purpose::STANDARD.decode(&base64_data)
+        .map_err(|e| anyhow!("Failed to decode base64 screenshot: {}", e))?;
+    if binary_data.is_empty() {
+        return Err(anyhow!("Screenshot file is empty or unreadable"));
+    }
+
+    // Cleanup remote file
+    temp_files::remove_remote(pool, device_host, remote_filename, as_root).await.ok();
+
+    Ok(binary_data)
+}
+
+/// Execute Launch command
+///
+/// A URI alone goes to the app as an OpenURI intent. Arguments cannot be
+/// passed through RuntimeManager's `Start`, so with arguments the app binary
+/// is started through `invoker` instead (the URI, if any, becomes the last
+/// argument).
+async fn execute_launch(
+    pool: &ConnectionPool,
+    device_host: &str,
+    app_name: &str,
+    args: &[String],
+    uri: Option<&str>,
+) -> Result<Vec<String>> {
+    info!("Launching {} on device {}", app_name, device_host);
+
+    // Validate app name
+    if app_name.is_empty() {
+        return Err(anyhow!("App name cannot be empty"));
+    }
+    if !app_name.contains('.') {
+        return Err(anyhow!(
+            "Invalid app name: '{}'. Expected D-Bus format: ru.domain.AppName",
+            app_name
+        ));
+    }
+
+    let plain_name = app_name.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+    if (!args.is_empty() || uri.is_some()) && !plain_name {
+        return Err(anyhow!("Invalid app name: '{}'", app_name));
+    }
+    if let Some(uri) = uri {
+        validate_uri(uri)?;
+    }
+
+    let launch_command = if !args.is_empty() {
+        let mut argv = vec![format!("/usr/bin/{}", app_name)];
+        argv.extend(args.iter().cloned());
+        argv.extend(uri.map(String::from));
+        format!("invoker --type=generic --no-wait {}", audb_core::tools::shell_escape::join_args(&argv))
+    } else if let Some(uri) = uri {
+        // validate_uri() keeps quotes out, so the GVariant literals are safe
+        audb_core::tools::shell_escape::join_args(&[
+            "gdbus", "call", "--system", "--dest", "ru.omp.RuntimeManager",
+            "--object-path", "/ru/omp/RuntimeManager/Intents1",
+            "--method", "ru.omp.RuntimeManager.Intents1.InvokeIntent",
+            "OpenURI",
+            &format!("{{'preferredHandler': <'{}'>}}", app_name),
+            &format!("{{'uri': <'{}'>}}", uri),
+        ])
+    } else {
+        // Build D-Bus launch command
+        format!(
+            "gdbus call --system --dest ru.omp.RuntimeManager \
+             --object-path /ru/omp/RuntimeManager/Control1 \
+             --method ru.omp.RuntimeManager.Control1.Start \"{}\"",
+            app_name
+        )
+    };
 
     // Execute via pool (doesn't need root)
     let output = pool.execute_command(device_host, &launch_command, false).await?;
@@ -866,11 +2075,39 @@ async fn execute_launch(
     Ok(output)
 }
 
+/// Check a launch URI: an RFC 3986 scheme followed by URI characters only
+///
+/// Quotes, spaces and other characters outside RFC 3986 must be percent-encoded.
+fn validate_uri(uri: &str) -> Result<()> {
+    let (scheme, rest) = uri
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid URI '{}': missing scheme (e.g. https:)", uri))?;
+
+    let scheme_ok = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    if !scheme_ok {
+        return Err(anyhow!("Invalid URI scheme '{}'", scheme));
+    }
+
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&()*+,;=%".contains(c);
+    if rest.is_empty() || !rest.chars().all(allowed) {
+        return Err(anyhow!(
+            "Invalid URI '{}': percent-encode quotes, spaces and other special characters",
+            uri
+        ));
+    }
+    Ok(())
+}
+
 /// Execute Stop command
+///
+/// With `force_timeout`, waits that many seconds for the app's processes to
+/// exit after Terminate and SIGKILLs (as root) whatever is left.
 async fn execute_stop(
     pool: &ConnectionPool,
     device_host: &str,
     app_name: &str,
+    force_timeout: Option<u64>,
 ) -> Result<Vec<String>> {
     info!("Stopping {} on device {}", app_name, device_host);
 
@@ -896,8 +2133,61 @@ async fn execute_stop(
     // Execute via pool (doesn't need root)
     let output = pool.execute_command(device_host, &stop_command, false).await?;
 
-    info!("Application stopped successfully");
-    Ok(output)
+    let Some(timeout) = force_timeout else {
+        info!("Application stopped successfully");
+        return Ok(output);
+    };
+
+    // Wait for the app to go away on its own
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(timeout);
+    let mut pids = app_pids(pool, device_host, app_name).await?;
+    while !pids.is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(tokio::time::Duration::from_millis(STOP_POLL_INTERVAL_MS)).await;
+        pids = app_pids(pool, device_host, app_name).await?;
+    }
+
+    if pids.is_empty() {
+        info!("Application stopped gracefully");
+        return Ok(vec![format!("{} stopped gracefully", app_name)]);
+    }
+
+    warn!("{} still running after {}s, sending SIGKILL to {:?}", app_name, timeout, pids);
+    pool.execute_command(device_host, &format!("kill -9 {}", pids.join(" ")), true).await?;
+
+    Ok(vec![format!(
+        "{} did not exit within {}s; force-killed (PID {})",
+        app_name,
+        timeout,
+        pids.join(", ")
+    )])
+}
+
+/// Default seconds `stop --force` waits before killing the app
+const STOP_FORCE_TIMEOUT_SECS: u64 = 5;
+
+/// How often `stop --force` checks whether the app has exited
+const STOP_POLL_INTERVAL_MS: u64 = 500;
+
+/// PIDs of the processes running the app's binary (`/usr/bin/<app_name>`)
+async fn app_pids(pool: &ConnectionPool, device_host: &str, app_name: &str) -> Result<Vec<String>> {
+    if !app_name.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c)) {
+        return Err(anyhow!("Invalid app name: '{}'", app_name));
+    }
+
+    // "[r]u.example.App" keeps pgrep from matching the shell running it
+    let mut chars = app_name.chars();
+    let first = chars.next().unwrap_or_default();
+    let pattern = format!("/usr/bin/[{}]{}", first, chars.as_str().replace('.', "\\."));
+
+    let output = pool
+        .execute_command(device_host, &format!("pgrep -f '{}' || true", pattern), false)
+        .await?;
+    Ok(output
+        .join("\n")
+        .split_whitespace()
+        .filter(|pid| pid.chars().all(|c| c.is_ascii_digit()))
+        .map(String::from)
+        .collect())
 }
 
 /// Execute Logs command
@@ -915,6 +2205,9 @@ async fn execute_logs(
     if args.kernel && args.unit.is_some() {
         return Err(anyhow!("Cannot specify both --kernel and --unit"));
     }
+    if args.since_boot && args.since.is_some() {
+        return Err(anyhow!("Cannot specify both --since-boot and --since"));
+    }
 
     // Handle clear logs
     if args.clear {
@@ -947,8 +2240,9 @@ fn build_journalctl_command(args: &audb_protocol::LogsArgs) -> Result<String> {
     // Number of lines
     cmd.push_str(&format!(" -n {}", args.lines));
 
-    // Priority level
+    // Priority level or range (validated, so it needs no quoting)
     if let Some(ref priority) = args.priority {
+        let priority = audb_core::features::logs::journalctl_priority(priority)?;
         cmd.push_str(&format!(" -p {}", priority));
     }
 
@@ -964,21 +2258,58 @@ fn build_journalctl_command(args: &audb_protocol::LogsArgs) -> Result<String> {
         cmd.push_str(&format!(" --since '{}'", escaped));
     }
 
+    // Current boot only
+    if args.since_boot {
+        cmd.push_str(" -b 0");
+    }
+
+    // Resume after the last entry seen by a follower
+    if let Some(ref cursor) = args.after_cursor {
+        let escaped = escape_single_quote(cursor);
+        cmd.push_str(&format!(" --after-cursor '{}'", escaped));
+    }
+
     // Output options
     cmd.push_str(" --no-pager --no-hostname");
+    if args.show_cursor {
+        cmd.push_str(" -q --show-cursor");
+    }
 
-    // Grep filter (as pipe, with escaping)
+    // Output format (validated, so it needs no quoting)
+    if let Some(ref output) = args.output {
+        let filtered = args.grep.is_some() || args.wait_for.is_some();
+        let output = audb_core::features::logs::journalctl_output(output, filtered)?;
+        cmd.push_str(&format!(" -o {}", output));
+    }
+
+    // Grep filter (as pipe, with escaping); the cursor line must get through it
     if let Some(ref grep_pattern) = args.grep {
         let escaped = escape_single_quote(grep_pattern);
-        cmd.push_str(&format!(" | grep '{}'", escaped));
+        if args.show_cursor {
+            cmd.push_str(&format!(" | grep -e '^-- cursor: ' -e '{}'", escaped));
+        } else {
+            cmd.push_str(&format!(" | grep '{}'", escaped));
+        }
+    }
+
+    // Wait-for pattern, literal unless a regex was asked for
+    if let Some(ref pattern) = args.wait_for {
+        let escaped = escape_single_quote(pattern);
+        let mode = if args.regex { "-E" } else { "-F" };
+        if args.show_cursor {
+            cmd.push_str(&format!(" | grep {} -e '-- cursor: ' -e '{}'", mode, escaped));
+        } else {
+            cmd.push_str(&format!(" | grep {} -e '{}'", mode, escaped));
+        }
     }
 
     Ok(cmd)
 }
 
-/// Escape single quotes for shell command (simple implementation)
-fn escape_single_quote(s: &str) -> String {
-    s.replace('\'', "'\\''")
+/// Wrap a command so that, run as root, it executes as another user
+fn as_user_command(user: &str, command: &str) -> Result<String> {
+    audb_core::tools::validation::validate_user_name(user)?;
+    Ok(format!("su -s /bin/sh '{}' -c '{}'", user, escape_single_quote(command)))
 }
 
 /// Execute Uninstall command
@@ -1006,6 +2337,45 @@ async fn execute_uninstall(
     Ok(output)
 }
 
+/// Execute UninstallMany command, removing each package in turn
+///
+/// A failed removal does not stop the rest; the per-package results are
+/// reported together (plain `✓`/`✗` lines, coloured by the client) and the
+/// command fails if any removal did.
+async fn execute_uninstall_many(
+    pool: &ConnectionPool,
+    device_host: &str,
+    package_names: &[String],
+) -> Result<Vec<String>> {
+    if package_names.is_empty() {
+        return Err(anyhow!("No packages given"));
+    }
+    if package_names.iter().any(|name| name.trim().is_empty()) {
+        return Err(anyhow!("Package name cannot be empty"));
+    }
+
+    let mut results = Vec::with_capacity(package_names.len());
+    let mut failed = 0;
+
+    for package_name in package_names {
+        match execute_uninstall(pool, device_host, package_name).await {
+            Ok(_) => results.push(format!("✓ {}: removed", package_name)),
+            Err(e) => {
+                failed += 1;
+                results.push(format!("✗ {}: {}", package_name, e));
+            }
+        }
+    }
+
+    if failed > 0 {
+        results.push(format!("{} of {} package(s) failed to uninstall", failed, package_names.len()));
+        return Err(anyhow!("{}", results.join("\n")));
+    }
+
+    results.push(format!("Uninstalled {} package(s)", package_names.len()));
+    Ok(results)
+}
+
 /// Execute Packages command - list installed packages
 async fn execute_packages(
     pool: &ConnectionPool,
@@ -1061,38 +2431,96 @@ async fn execute_push(
     local_path: &str,
     remote_path: &str,
     data: Vec<u8>,
+    mkdirs: bool,
 ) -> Result<Vec<String>> {
+    let remote_path = &expand_remote_path(pool, device_host, remote_path).await?;
     info!("Pushing {} to {} on device {}", local_path, remote_path, device_host);
 
-    // Get just the filename for temp file
-    let file_name = std::path::Path::new(local_path)
-        .file_name()
-        .ok_or_else(|| anyhow!("Invalid local path"))?
-        .to_string_lossy()
-        .to_string();
+    // SFTP fails obscurely when the destination directory is missing, so check first
+    let parent = std::path::Path::new(remote_path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| !p.is_empty());
+    if let Some(parent) = parent {
+        let escaped = escape_single_quote(&parent);
+        if mkdirs {
+            pool.execute_command(device_host, &format!("mkdir -p '{}'", escaped), false).await?;
+        } else {
+            let check = pool
+                .execute_command(device_host, &format!("[ -d '{}' ] && echo yes || echo no", escaped), false)
+                .await?;
+            if check.join("").trim() != "yes" {
+                return Err(anyhow!(
+                    "Remote directory {} does not exist; pass --mkdirs to create it",
+                    parent
+                ));
+            }
+        }
+    }
 
-    // Write data to temporary local file
-    let local_temp = std::env::temp_dir().join(&file_name);
+    // Write data to a temporary local file; the name does not come from the
+    // client's path, which may be a placeholder such as <stdin>
+    let local_temp = std::env::temp_dir().join(temp_files::unique_name("audb-push", ""));
     std::fs::write(&local_temp, &data)?;
 
     // Upload to device
     let remote = PathBuf::from(remote_path);
-    pool.upload_file(device_host, &local_temp, &remote).await?;
+    let result = pool.upload_file(device_host, &local_temp, &remote).await;
 
     // Cleanup local temp file
-    std::fs::remove_file(&local_temp).ok();
+    temp_files::remove_local(&local_temp);
+    result?;
 
     let size = data.len();
     info!("Pushed {} bytes to {}", size, remote_path);
     Ok(vec![format!("{}: {} bytes", remote_path, size)])
 }
 
+/// Home directory of the SSH user on the device, resolved once per connection
+///
+/// Images differ in their default user, so paths under the home directory are
+/// never hard-coded to /home/defaultuser.
+async fn remote_home(pool: &ConnectionPool, device_host: &str) -> Result<String> {
+    if let Some(home) = pool.home_dir(device_host).await {
+        return Ok(home);
+    }
+
+    let output = pool.execute_command(device_host, "echo \"$HOME\"", false).await?;
+    let home = output
+        .first()
+        .map(|line| line.trim())
+        .filter(|home| home.starts_with('/'))
+        .map(String::from)
+        .ok_or_else(|| anyhow!("Could not resolve the home directory on device {}", device_host))?;
+
+    pool.set_home_dir(device_host, home.clone()).await;
+    Ok(home)
+}
+
+/// Expand a leading `~` or `~/` in a remote path to the device user's home
+///
+/// Other paths (including `~user/...`) are returned unchanged, without asking
+/// the device for its home directory.
+async fn expand_remote_path(pool: &ConnectionPool, device_host: &str, path: &str) -> Result<String> {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => return Ok(path.to_string()),
+    };
+
+    let home = remote_home(pool, device_host).await?;
+    if rest.is_empty() {
+        return Ok(home);
+    }
+    Ok(format!("{}{}", home.trim_end_matches('/'), rest))
+}
+
 /// Execute Pull command - download file from device
 async fn execute_pull(
     pool: &ConnectionPool,
     device_host: &str,
     remote_path: &str,
 ) -> Result<Vec<u8>> {
+    let remote_path = &expand_remote_path(pool, device_host, remote_path).await?;
     info!("Pulling {} from device {}", remote_path, device_host);
 
     // Get filename for temp file
@@ -1113,12 +2541,94 @@ async fn execute_pull(
         .map_err(|e| anyhow!("Failed to read downloaded file: {}", e))?;
 
     // Cleanup temp file
-    std::fs::remove_file(&local_temp).ok();
+    temp_files::remove_local(&local_temp);
 
     info!("Pulled {} bytes from {}", data.len(), remote_path);
     Ok(data)
 }
 
+/// Execute Pull --tar command - pack a directory on the device and download the tarball
+///
+/// One `tar czf` on the device and one SFTP transfer, instead of a transfer
+/// per file. The tarball holds the directory itself (by its base name).
+async fn execute_pull_tar(
+    pool: &ConnectionPool,
+    device_host: &str,
+    remote_path: &str,
+) -> Result<Vec<u8>> {
+    let remote_path = &expand_remote_path(pool, device_host, remote_path).await?;
+    info!("Pulling directory {} from device {} as a tarball", remote_path, device_host);
+
+    let trimmed = remote_path.trim_end_matches('/');
+    let (parent, name) = match trimmed.rsplit_once('/') {
+        Some((parent, name)) => (if parent.is_empty() { "/" } else { parent }, name),
+        None => (".", trimmed),
+    };
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(anyhow!("Invalid remote directory: {}", remote_path));
+    }
+
+    let file_name = temp_files::unique_name("audb-pull", "tar.gz");
+    let remote_tar = format!("/tmp/{}", file_name);
+
+    let pack_command = format!(
+        "[ -d {dir} ] || {{ echo 'not a directory' >&2; exit 1; }}; tar czf {tar} -C {parent} {name} || {{ rm -f {tar}; exit 1; }}",
+        dir = quote_arg(remote_path),
+        tar = quote_arg(&remote_tar),
+        parent = quote_arg(parent),
+        name = quote_arg(name),
+    );
+    pool.execute_command(device_host, &pack_command, false)
+        .await
+        .map_err(|e| anyhow!("Failed to pack {}: {}", remote_path, e))?;
+
+    let local_temp = std::env::temp_dir().join(&file_name);
+    let result = pool.download_file(device_host, &PathBuf::from(&remote_tar), &local_temp).await;
+    if let Err(e) = temp_files::remove_remote(pool, device_host, &remote_tar, false).await {
+        warn!("Failed to remove {} from {}: {}", remote_tar, device_host, e);
+    }
+    result?;
+
+    let data = std::fs::read(&local_temp)
+        .map_err(|e| anyhow!("Failed to read downloaded tarball: {}", e))?;
+    temp_files::remove_local(&local_temp);
+
+    info!("Pulled {} as a {} byte tarball", remote_path, data.len());
+    Ok(data)
+}
+
+/// Execute Cat command - read a text file from device without a temp file
+async fn execute_cat(
+    pool: &ConnectionPool,
+    device_host: &str,
+    remote_path: &str,
+    as_root: bool,
+) -> Result<Vec<String>> {
+    if remote_path.is_empty() {
+        return Err(anyhow!("Remote path cannot be empty"));
+    }
+    let remote_path = &expand_remote_path(pool, device_host, remote_path).await?;
+    info!("Reading {} from device {}", remote_path, device_host);
+
+    // Read through base64 so the exact file contents survive the exec channel
+    let read_command = format!("base64 '{}'", escape_single_quote(remote_path));
+    let base64_lines = pool.execute_command(device_host, &read_command, as_root).await?;
+    if base64_lines.last().is_some_and(|l| l.starts_with(audb_core::tools::ssh::OUTPUT_TRUNCATED_MARKER)) {
+        return Err(anyhow!("File {} is too large to print; use 'audb pull' instead", remote_path));
+    }
+    let base64_data = base64_lines.join("").replace(['\n', '\r'], "");
+
+    use base64::Engine;
+    let data = base64::engine::general_purpose::STANDARD.decode(&base64_data)
+        .map_err(|e| anyhow!("Failed to decode file contents: {}", e))?;
+
+    let text = String::from_utf8_lossy(&data);
+    let lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+
+    info!("Read {} bytes from {}", data.len(), remote_path);
+    Ok(lines)
+}
+
 /// Execute Info command - get device information
 async fn execute_info(
     pool: &ConnectionPool,
@@ -1127,6 +2637,9 @@ async fn execute_info(
 ) -> Result<audb_protocol::DeviceInfo> {
     info!("Getting device info from {}", device_host);
 
+    // Make sure the device is reachable; the queries below fall back to defaults
+    pool.execute_command(device_host, "true", false).await?;
+
     // D-Bus calls to ru.omp.deviceinfo.Features (system bus)
     let dbus_base = "gdbus call --system --dest ru.omp.deviceinfo --object-path /ru/omp/deviceinfo/Features --method ru.omp.deviceinfo.Features";
 
@@ -1292,20 +2805,121 @@ async fn execute_info(
         .map(|o| extract_f64(&o))
         .unwrap_or(0.0);
 
-    // Get storage info using stat -f (more reliable than df)
-    let storage_info = pool.execute_command(
+    // Get storage info for each writable disk mount using stat -f (more reliable than df)
+    let mounts_output = pool.execute_command(
         device_host,
-        "stat -f -c '%b %a %S' /home",
+        "while read dev mnt fstype opts rest; do \
+            case \"$fstype\" in ext2|ext3|ext4|f2fs|btrfs|xfs|vfat|exfat|fuseblk) ;; *) continue ;; esac; \
+            case \",$opts,\" in *,rw,*) ;; *) continue ;; esac; \
+            echo \"$(stat -f -c '%b %a %S' \"$mnt\" 2>/dev/null) $mnt\"; \
+        done < /proc/mounts",
         false
     ).await.unwrap_or_default();
+    let storage_mounts = parse_storage_mounts(&mounts_output);
+
+    // The legacy internal storage fields describe the filesystem holding /home
+    // (which can be a read-only root, left out of the writable mounts above)
+    let home_mount = storage_mounts
+        .iter()
+        .find(|m| m.mount == "/home")
+        .or_else(|| storage_mounts.iter().find(|m| m.mount == "/"));
+    let home_mount = match home_mount {
+        Some(mount) => Some(mount.clone()),
+        None => {
+            let output = pool.execute_command(device_host, "echo \"$(stat -f -c '%b %a %S' /home) /home\"", false)
+                .await
+                .unwrap_or_default();
+            parse_storage_mounts(&output).into_iter().next()
+        }
+    };
+    let internal_storage_total_mb = home_mount.as_ref().map(|m| m.total_mb).unwrap_or(0);
+    let internal_storage_free_mb = home_mount.as_ref().map(|m| m.free_mb).unwrap_or(0);
 
-    let storage_parts: Vec<u64> = storage_info.first()
-        .map(|s| s.split_whitespace().filter_map(|p| p.parse().ok()).collect())
+    // Get thermal zones as "<type> <millidegrees>" pairs
+    let thermal_zones = pool.execute_command(device_host, THERMAL_ZONES_COMMAND, false).await.unwrap_or_default();
+    let cpu_temp_c = parse_cpu_temp(&thermal_zones);
+
+    // Get battery temperature (power_supply reports tenths of a degree)
+    let battery_temp = pool.execute_command(device_host, BATTERY_TEMP_COMMAND, false).await.unwrap_or_default();
+    let battery_temp_c = parse_battery_temp(&battery_temp);
+
+    // Get kernel release, OS build and hardware revision, one per line (a missing
+    // os-release must not abort the subshell, or the later lines would shift)
+    let build_info = pool.execute_command(
+        device_host,
+        "uname -r; ([ -r /etc/os-release ] && . /etc/os-release; echo \"${BUILD_ID:-$VERSION_ID}\"); \
+         (cat /sys/devices/soc0/revision 2>/dev/null || sed -n 's/^Revision[[:space:]]*: *//p' /proc/cpuinfo) | head -n 1; true",
+        false
+    ).await
+        .unwrap_or_default()
+        .join("\n");
+    let mut build_lines = build_info.lines().map(|l| l.trim().to_string());
+    let kernel_version = build_lines.next().unwrap_or_default();
+    let build_id = build_lines.next().unwrap_or_default();
+    let hardware_revision = build_lines.next().unwrap_or_default();
+
+    // Get developer mode and SSH daemon status as key=value lines
+    let devmode_output = pool.execute_command(
+        device_host,
+        "[ -x /usr/bin/devel-su ] && echo devmode=yes || echo devmode=no; \
+         for u in sshd.socket sshd.service; do \
+            echo \"enabled=$(systemctl is-enabled $u 2>/dev/null)\"; echo \"active=$(systemctl is-active $u 2>/dev/null)\"; \
+         done; \
+         echo \"listen=$(systemctl show -p Listen --value sshd.socket 2>/dev/null)\"; \
+         echo \"port=$(sed -n 's/^[[:space:]]*Port[[:space:]][[:space:]]*//p' /etc/ssh/sshd_config 2>/dev/null | head -n 1)\"",
+        false
+    ).await.unwrap_or_default();
+    let devmode = parse_devmode_status(&devmode_output.join("\n"));
+
+    // Read the device clock, timing the round trip to estimate the offset from ours
+    let clock_sent = chrono::Utc::now();
+    let clock_output = pool.execute_command(
+        device_host,
+        "date '+%s%N %Y-%m-%d %H:%M:%S %Z %z'; readlink /etc/localtime; true",
+        false
+    ).await.unwrap_or_default();
+    let clock = parse_device_clock(&clock_output.join("\n"), clock_sent, chrono::Utc::now());
+
+    // Get uptime and load average (first line: /proc/uptime, second: /proc/loadavg)
+    let proc_info = pool.execute_command(device_host, "cat /proc/uptime /proc/loadavg", false).await
+        .unwrap_or_default()
+        .join("\n");
+    let mut proc_lines = proc_info.lines();
+
+    let uptime_secs = proc_lines.next()
+        .and_then(|l| l.split_whitespace().next())
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|v| v as u64)
+        .unwrap_or(0);
+
+    let mut load_avg = [0.0; 3];
+    if let Some(line) = proc_lines.next() {
+        for (slot, value) in load_avg.iter_mut().zip(line.split_whitespace()) {
+            *slot = value.parse().unwrap_or(0.0);
+        }
+    }
+
+    // Get attached USB devices from sysfs (lsusb is not installed on every image)
+    let usb_devices = pool.execute_command(
+        device_host,
+        "for d in /sys/bus/usb/devices/*; do [ -r \"$d/idVendor\" ] && echo \"$(cat \"$d/idVendor\"):$(cat \"$d/idProduct\") $(cat \"$d/manufacturer\" 2>/dev/null) $(cat \"$d/product\" 2>/dev/null)\"; done; true",
+        false
+    ).await
+        .map(|o| parse_usb_devices(&o))
         .unwrap_or_default();
 
-    let block_size = storage_parts.get(2).copied().unwrap_or(4096);
-    let internal_storage_total_mb = storage_parts.first().copied().unwrap_or(0) * block_size / (1024 * 1024);
-    let internal_storage_free_mb = storage_parts.get(1).copied().unwrap_or(0) * block_size / (1024 * 1024);
+    // Get paired/connected Bluetooth devices from BlueZ (skipped without Bluetooth)
+    let bluetooth_devices = if has_bluetooth {
+        pool.execute_command(
+            device_host,
+            "gdbus call --system --dest org.bluez --object-path / --method org.freedesktop.DBus.ObjectManager.GetManagedObjects",
+            false
+        ).await
+            .map(|o| parse_bluez_devices(&o.join("\n")))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
 
     info!("Device info retrieved successfully");
 
@@ -1331,9 +2945,734 @@ async fn execute_info(
         frontal_camera_mp,
         internal_storage_total_mb,
         internal_storage_free_mb,
+        cpu_temp_c,
+        battery_temp_c,
+        uptime_secs,
+        load_avg,
+        usb_devices,
+        bluetooth_devices,
+        storage_mounts,
+        kernel_version,
+        build_id,
+        hardware_revision,
+        developer_mode: devmode.developer_mode,
+        ssh_enabled: devmode.ssh_enabled,
+        ssh_running: devmode.ssh_running,
+        ssh_port: devmode.ssh_port,
+        device_time: clock.time,
+        timezone: clock.timezone,
+        clock_offset_ms: clock.offset_ms,
+        clock_rtt_ms: clock.rtt_ms,
     })
 }
 
+/// Device clock reading with its offset from the host clock
+#[derive(Default)]
+struct DeviceClock {
+    time: String,
+    timezone: String,
+    offset_ms: Option<i64>,
+    rtt_ms: Option<u64>,
+}
+
+/// Parse `date '+%s%N %Y-%m-%d %H:%M:%S %Z %z'` followed by the /etc/localtime link
+///
+/// The device read is assumed to happen halfway between `sent` and
+/// `received`. A `date` without `%N` support prints a literal `N` after the
+/// seconds, which costs the offset its sub-second precision.
+fn parse_device_clock(
+    output: &str,
+    sent: chrono::DateTime<chrono::Utc>,
+    received: chrono::DateTime<chrono::Utc>,
+) -> DeviceClock {
+    let mut lines = output.lines();
+    let Some(date_line) = lines.next() else {
+        return DeviceClock::default();
+    };
+    let fields: Vec<&str> = date_line.split_whitespace().collect();
+    let [stamp, day, time, abbreviation, utc_offset, ..] = fields.as_slice() else {
+        return DeviceClock::default();
+    };
+
+    let device_ms = match stamp.strip_suffix('N') {
+        Some(secs) => secs.parse::<i64>().ok().map(|s| s * 1000),
+        // Seconds followed by nine digits of nanoseconds
+        None if stamp.len() > 9 => stamp.parse::<i64>().ok().map(|ns| ns / 1_000_000),
+        None => None,
+    };
+    let rtt_ms = (received - sent).num_milliseconds().max(0);
+    let midpoint_ms = sent.timestamp_millis() + rtt_ms / 2;
+
+    // e.g. /usr/share/zoneinfo/Europe/Moscow
+    let zone = lines
+        .next()
+        .and_then(|link| link.trim().split_once("zoneinfo/").map(|(_, zone)| zone.to_string()));
+    let timezone = match zone {
+        Some(zone) => format!("{} ({}, {})", zone, abbreviation, utc_offset),
+        None => format!("{} ({})", abbreviation, utc_offset),
+    };
+
+    DeviceClock {
+        time: format!("{} {}", day, time),
+        timezone,
+        offset_ms: device_ms.map(|device_ms| device_ms - midpoint_ms),
+        rtt_ms: device_ms.map(|_| rtt_ms as u64),
+    }
+}
+
+/// Developer mode and SSH daemon state read from the device
+#[derive(Default)]
+struct DevModeStatus {
+    developer_mode: Option<bool>,
+    ssh_enabled: Option<bool>,
+    ssh_running: Option<bool>,
+    ssh_port: Option<u16>,
+}
+
+/// Parse the `devmode=`, `enabled=`, `active=`, `listen=` and `port=` lines
+///
+/// SSH counts as enabled/running if either the socket or the service unit is.
+/// Units systemctl does not know print nothing and are ignored. The port comes
+/// from the socket's Listen= address, else sshd_config, else the default 22.
+fn parse_devmode_status(output: &str) -> DevModeStatus {
+    let mut status = DevModeStatus::default();
+    let mut listen_port = None;
+    let mut config_port = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "devmode" => status.developer_mode = Some(value == "yes"),
+            "enabled" if !value.is_empty() => {
+                let enabled = matches!(value, "enabled" | "static" | "enabled-runtime");
+                status.ssh_enabled = Some(status.ssh_enabled.unwrap_or(false) || enabled);
+            }
+            "active" if !value.is_empty() && value != "unknown" => {
+                status.ssh_running = Some(status.ssh_running.unwrap_or(false) || value == "active");
+            }
+            // e.g. "[::]:22 (Stream)" or "0.0.0.0:2222 (Stream)"
+            "listen" => {
+                listen_port = value
+                    .split_whitespace()
+                    .next()
+                    .and_then(|address| address.rsplit(':').next())
+                    .and_then(|port| port.parse().ok());
+            }
+            "port" => config_port = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    status.ssh_port = listen_port
+        .or(config_port)
+        .or_else(|| status.ssh_running.is_some().then_some(22));
+    status
+}
+
+/// Parse "<blocks> <available> <block size> <mount point>" lines
+///
+/// Bind mounts of the same mount point are reported once.
+fn parse_storage_mounts(output: &[String]) -> Vec<audb_protocol::StorageMount> {
+    let mut mounts: Vec<audb_protocol::StorageMount> = Vec::new();
+
+    for line in output.join("\n").lines() {
+        let mut parts = line.trim().splitn(4, ' ');
+        let (Some(blocks), Some(available), Some(block_size), Some(mount)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let (Ok(blocks), Ok(available), Ok(block_size)) =
+            (blocks.parse::<u64>(), available.parse::<u64>(), block_size.parse::<u64>())
+        else {
+            continue;
+        };
+
+        // /proc/mounts escapes spaces in paths as \040
+        let mount = mount.replace("\\040", " ");
+        if blocks == 0 || mounts.iter().any(|m| m.mount == mount) {
+            continue;
+        }
+
+        mounts.push(audb_protocol::StorageMount {
+            mount,
+            total_mb: blocks * block_size / (1024 * 1024),
+            free_mb: available * block_size / (1024 * 1024),
+        });
+    }
+
+    mounts
+}
+
+/// USB vendor ID of the Linux root hubs, which are not peripherals
+const USB_ROOT_HUB_VENDOR: &str = "1d6b";
+
+/// Parse "vendor:product manufacturer product" lines, dropping root hubs
+fn parse_usb_devices(output: &[String]) -> Vec<String> {
+    output
+        .join("\n")
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty() && !line.starts_with(USB_ROOT_HUB_VENDOR))
+        .collect()
+}
+
+/// Extract paired or connected devices from a BlueZ `GetManagedObjects` reply
+///
+/// Each `objectpath '...'` starts one object's section; device objects carry
+/// `org.bluez.Device1` properties like `'Address': <'AA:BB:..'>`.
+fn parse_bluez_devices(output: &str) -> Vec<audb_protocol::BluetoothDevice> {
+    let property = |section: &str, key: &str| -> Option<String> {
+        let start = section.find(&format!("'{}': <", key))? + key.len() + 5;
+        let value = &section[start..];
+        let end = value.find('>')?;
+        Some(value[..end].trim_matches('\'').to_string())
+    };
+
+    output
+        .split("objectpath '")
+        .filter(|section| section.contains("'org.bluez.Device1'"))
+        .filter_map(|section| {
+            let address = property(section, "Address")?;
+            let name = property(section, "Alias")
+                .or_else(|| property(section, "Name"))
+                .unwrap_or_else(|| address.clone());
+            let paired = property(section, "Paired").as_deref() == Some("true");
+            let connected = property(section, "Connected").as_deref() == Some("true");
+            (paired || connected).then_some(audb_protocol::BluetoothDevice { address, name, paired, connected })
+        })
+        .collect()
+}
+
+/// Print "<type> <millidegrees>" for each readable thermal zone
+///
+/// Ends with `true`: the loop's status is that of its last test, which fails
+/// when the last zone has no readable temperature.
+const THERMAL_ZONES_COMMAND: &str = "for z in /sys/class/thermal/thermal_zone*; do \
+    [ -r \"$z/temp\" ] && echo \"$(cat \"$z/type\" 2>/dev/null) $(cat \"$z/temp\")\"; done; true";
+
+/// Print the temperature of each battery power supply (ends with `true`, as above)
+const BATTERY_TEMP_COMMAND: &str = "for p in /sys/class/power_supply/*; do \
+    [ \"$(cat \"$p/type\" 2>/dev/null)\" = Battery ] && cat \"$p/temp\" 2>/dev/null; done; true";
+
+/// Battery temperature in degrees from power_supply output (tenths of a degree)
+fn parse_battery_temp(output: &[String]) -> Option<f64> {
+    output
+        .join("\n")
+        .lines()
+        .find_map(|l| l.trim().parse::<f64>().ok())
+        .map(|t| t / 10.0)
+}
+
+/// Pick the CPU temperature from thermal zone output ("<type> <millidegrees>" per line)
+///
+/// Prefers zones whose type mentions the CPU and falls back to the hottest zone.
+fn parse_cpu_temp(output: &[String]) -> Option<f64> {
+    let zones: Vec<(String, f64)> = output
+        .join("\n")
+        .lines()
+        .filter_map(|line| {
+            let (zone_type, temp) = line.trim().rsplit_once(' ')?;
+            let temp: f64 = temp.parse().ok()?;
+            Some((zone_type.to_lowercase(), temp / 1000.0))
+        })
+        .collect();
+
+    zones
+        .iter()
+        .filter(|(zone_type, _)| zone_type.contains("cpu"))
+        .map(|(_, temp)| *temp)
+        .reduce(f64::max)
+        .or_else(|| zones.iter().map(|(_, temp)| *temp).reduce(f64::max))
+}
+
+/// Execute a read-only builtin check and format its output
+async fn execute_builtin(
+    pool: &ConnectionPool,
+    device_host: &str,
+    builtin: audb_protocol::Builtin,
+) -> Result<Vec<String>> {
+    use audb_protocol::Builtin;
+
+    info!("Running builtin '{}' on device {}", builtin.name(), device_host);
+
+    let command = match builtin {
+        Builtin::Free => "cat /proc/meminfo",
+        Builtin::Date => "date '+%s %Y-%m-%d %H:%M:%S %Z'",
+        Builtin::Df => "df -kP",
+    };
+    let output = pool.execute_command(device_host, command, false).await?.join("\n");
+
+    match builtin {
+        Builtin::Free => format_free(&output),
+        Builtin::Date => format_date(&output),
+        Builtin::Df => format_df(&output),
+    }
+}
+
+/// Execute Radio command - switch WiFi, Bluetooth or airplane mode via ConnMan
+///
+/// Switching off the radio that carries the SSH connection would leave no
+/// way to switch it back on remotely, so that is refused without `force`, as
+/// is any switch that cuts links when the SSH interface cannot be identified.
+/// When forced, the switch runs detached after a short delay so the reply
+/// gets out before the link drops.
+async fn execute_radio(
+    pool: &ConnectionPool,
+    device_host: &str,
+    radio: audb_protocol::Radio,
+    enabled: bool,
+    force: bool,
+) -> Result<Vec<String>> {
+    use audb_protocol::Radio;
+
+    let state = if enabled { "on" } else { "off" };
+    info!("Switching {} {} on device {}", radio.name(), state, device_host);
+
+    let (object_path, interface, property, value) = match radio {
+        Radio::Wifi => ("/net/connman/technology/wifi", "net.connman.Technology", "Powered", enabled),
+        Radio::Bluetooth => ("/net/connman/technology/bluetooth", "net.connman.Technology", "Powered", enabled),
+        // Airplane mode on means radios off
+        Radio::Airplane => ("/", "net.connman.Manager", "OfflineMode", enabled),
+    };
+    let dbus_command = format!(
+        "gdbus call --system --dest net.connman --object-path {} --method {}.SetProperty {} '<{}>'",
+        quote_arg(object_path),
+        interface,
+        quote_arg(property),
+        value
+    );
+
+    // Interfaces each switch takes down: WLAN, Bluetooth PAN, and for offline mode cellular too
+    let cuts_off: &[&str] = match (radio, enabled) {
+        (Radio::Wifi, false) => &["wlan"],
+        (Radio::Bluetooth, false) => &["bnep"],
+        (Radio::Airplane, true) => &["wlan", "bnep", "rmnet", "ccmni"],
+        _ => &[],
+    };
+    let link = if cuts_off.is_empty() {
+        None
+    } else {
+        match ssh_interface(pool, device_host).await {
+            Some(interface) if !cuts_off.iter().any(|prefix| interface.starts_with(prefix)) => None,
+            // A link that cannot be identified may be one the switch takes down
+            interface => Some(interface.unwrap_or_else(|| "an unidentified interface".to_string())),
+        }
+    };
+
+    if let Some(interface) = link {
+        if !force {
+            return Err(anyhow!(
+                "audb is connected to this device over {}; switching {} {} may drop the connection \
+                 and it could not be switched back remotely. Connect over USB, or pass --force",
+                interface,
+                radio.name(),
+                state
+            ));
+        }
+
+        let detached = format!("(sleep 1; {}) >/dev/null 2>&1 &", dbus_command);
+        pool.execute_command(device_host, &detached, true).await?;
+        warn!("Switching {} {} on {} may drop its connection over {}", radio.name(), state, device_host, interface);
+        return Ok(vec![
+            format!("{}: switching {} in 1s", radio.name(), state),
+            format!("The connection over {} may drop; reconnect over another link", interface),
+        ]);
+    }
+
+    match pool.execute_command(device_host, &dbus_command, true).await {
+        Ok(_) => Ok(vec![format!("{}: {}", radio.name(), state)]),
+        // ConnMan refuses to set a technology to the state it is already in
+        Err(e) if e.to_string().contains("AlreadyEnabled") || e.to_string().contains("AlreadyDisabled") => {
+            Ok(vec![format!("{}: already {}", radio.name(), state)])
+        }
+        Err(e) if e.to_string().contains("UnknownObject") => {
+            Err(anyhow!("The device has no {} technology", radio.name()))
+        }
+        Err(e) => Err(anyhow!("Failed to switch {} {}: {}", radio.name(), state, e)),
+    }
+}
+
+/// Network interface the SSH connection arrives on, from `$SSH_CONNECTION` (None if unknown)
+async fn ssh_interface(pool: &ConnectionPool, device_host: &str) -> Option<String> {
+    let command = "addr=$(echo \"$SSH_CONNECTION\" | cut -d' ' -f3); \
+                   [ -n \"$addr\" ] && ip -o addr show | awk -v a=\"$addr\" '{ split($4, p, \"/\"); if (p[1] == a) print $2 }'; true";
+    let output = pool.execute_command(device_host, command, false).await.ok()?;
+    output
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .map(String::from)
+}
+
+/// Journal lines shown by `audb service status`
+const SERVICE_STATUS_LOG_LINES: usize = 10;
+
+/// Execute Service command - control or inspect a systemd unit
+///
+/// System units are started, stopped and restarted as root; `--user` units
+/// and status queries run as the regular user.
+async fn execute_service(
+    pool: &ConnectionPool,
+    device_host: &str,
+    action: audb_protocol::ServiceAction,
+    unit: &str,
+    user: bool,
+) -> Result<Vec<String>> {
+    use audb_core::features::service::validate_unit_name;
+    use audb_protocol::ServiceAction;
+
+    info!("Service {} {} on device {}", action.name(), unit, device_host);
+    validate_unit_name(unit)?;
+
+    let systemctl = if user { "systemctl --user" } else { "systemctl" };
+    let status = unit_status(pool, device_host, systemctl, unit).await?;
+    // Unknown unit names are reported as such, not as a failed start
+    if !status.exists() {
+        return Err(anyhow!("Unit {} does not exist on the device", unit));
+    }
+
+    if action == ServiceAction::Status {
+        return service_status_report(pool, device_host, unit, user, status).await;
+    }
+
+    let command = format!("{} {} {}", systemctl, action.name(), quote_arg(unit));
+    pool.execute_command(device_host, &command, !user)
+        .await
+        .map_err(|e| anyhow!("Failed to {} {}: {}", action.name(), unit, e))?;
+
+    let status = unit_status(pool, device_host, systemctl, unit).await?;
+    Ok(vec![format!("{}: {}", unit, status.active_summary())])
+}
+
+/// Read a unit's state with `systemctl show`
+async fn unit_status(
+    pool: &ConnectionPool,
+    device_host: &str,
+    systemctl: &str,
+    unit: &str,
+) -> Result<audb_core::features::service::UnitStatus> {
+    use audb_core::features::service::{parse_unit_status, STATUS_PROPERTIES};
+
+    let command = format!("{} show -p {} {}", systemctl, STATUS_PROPERTIES.join(" -p "), quote_arg(unit));
+    let output = pool.execute_command(device_host, &command, false).await?;
+    Ok(parse_unit_status(&output.join("\n")))
+}
+
+/// Format the `audb service status` report: state, then the unit's recent journal
+async fn service_status_report(
+    pool: &ConnectionPool,
+    device_host: &str,
+    unit: &str,
+    user: bool,
+    status: audb_core::features::service::UnitStatus,
+) -> Result<Vec<String>> {
+    let mut lines = vec![format!("unit:     {}", unit)];
+    if !status.description.is_empty() {
+        lines.push(format!("desc:     {}", status.description));
+    }
+    lines.push(format!("active:   {}", status.active_summary()));
+    if !status.unit_file_state.is_empty() {
+        lines.push(format!("enabled:  {}", status.unit_file_state));
+    }
+
+    let unit_filter = if user { "--user-unit" } else { "-u" };
+    let journal_command = format!(
+        "journalctl {} {} -n {} --no-pager -o short-iso",
+        unit_filter,
+        quote_arg(unit),
+        SERVICE_STATUS_LOG_LINES
+    );
+    // The journal may be unreadable without root; the state above is still useful
+    match pool.execute_command(device_host, &journal_command, false).await {
+        Ok(journal) => {
+            let journal: Vec<String> = journal
+                .into_iter()
+                .filter(|line| !line.is_empty() && !line.starts_with("-- "))
+                .collect();
+            if !journal.is_empty() {
+                lines.push(String::new());
+                lines.push("recent log:".to_string());
+                lines.extend(journal.into_iter().map(|line| format!("  {}", line)));
+            }
+        }
+        Err(e) => warn!("Could not read the journal for {}: {}", unit, e),
+    }
+
+    Ok(lines)
+}
+
+/// Run the probes for a diagnostic topic and summarize them, one finding per line
+async fn execute_diagnose(
+    pool: &ConnectionPool,
+    device_host: &str,
+    topic: audb_protocol::DiagTopic,
+    metrics: &Metrics,
+) -> Result<Vec<String>> {
+    use audb_protocol::DiagTopic;
+
+    info!("Running '{}' diagnostics on device {}", topic.name(), device_host);
+
+    // Fail early (device not found / unreachable) rather than reporting every probe as failed
+    pool.execute_command(device_host, "true", false).await?;
+
+    let mut report = DiagReport::default();
+    match topic {
+        DiagTopic::Input => diagnose_input(pool, device_host, metrics, &mut report).await,
+    }
+    Ok(report.finish())
+}
+
+/// Findings of a diagnostic run
+#[derive(Default)]
+struct DiagReport {
+    lines: Vec<String>,
+    problems: usize,
+}
+
+impl DiagReport {
+    fn ok(&mut self, text: impl AsRef<str>) {
+        self.lines.push(format!("ok    {}", text.as_ref()));
+    }
+
+    fn warn(&mut self, text: impl AsRef<str>) {
+        self.problems += 1;
+        self.lines.push(format!("warn  {}", text.as_ref()));
+    }
+
+    fn fail(&mut self, text: impl AsRef<str>) {
+        self.problems += 1;
+        self.lines.push(format!("FAIL  {}", text.as_ref()));
+    }
+
+    fn detail(&mut self, text: impl AsRef<str>) {
+        self.lines.push(format!("        {}", text.as_ref()));
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.lines.push(String::new());
+        self.lines.push(match self.problems {
+            0 => "summary: no problems found".to_string(),
+            n => format!("summary: {} problem(s) found", n),
+        });
+        self.lines
+    }
+}
+
+/// Probe what tap/swipe/pinch depend on: python3, root, /dev/uinput, a touchscreen node
+async fn diagnose_input(pool: &ConnectionPool, device_host: &str, metrics: &Metrics, report: &mut DiagReport) {
+    let text = |output: Vec<String>| output.join("\n").trim().to_string();
+
+    // The input scripts are Python
+    match pool.execute_command(device_host, "python3 --version 2>&1", false).await.map(text) {
+        Ok(version) if version.starts_with("Python 3") => report.ok(format!("python3 available ({})", version)),
+        Ok(_) | Err(_) => report.fail("python3 not found; tap/swipe/pinch scripts cannot run"),
+    }
+
+    // Injection runs as root through devel-su
+    let root_ok = match pool.execute_command(device_host, "id -u", true).await.map(text) {
+        Ok(uid) if uid == "0" => {
+            report.ok("root access through devel-su works");
+            true
+        }
+        Ok(uid) => {
+            report.fail(format!("devel-su did not give root (uid {})", uid));
+            false
+        }
+        Err(e) => {
+            report.fail(format!("root access failed: {}", e));
+            false
+        }
+    };
+
+    // /dev/uinput must exist and be writable by root for the default path
+    let uinput = pool.execute_command(device_host, "ls -l /dev/uinput 2>/dev/null", false).await.map(text);
+    match uinput {
+        Ok(listing) if listing.starts_with('c') => {
+            report.ok(format!("/dev/uinput exists ({})", listing));
+            if root_ok {
+                let writable = pool
+                    .execute_command(device_host, "[ -w /dev/uinput ] && echo yes || echo no", true)
+                    .await
+                    .map(text);
+                match writable.as_deref() {
+                    Ok("yes") => report.ok("/dev/uinput is writable as root"),
+                    _ => report.fail("/dev/uinput is not writable as root (SELinux or device policy?)"),
+                }
+            }
+        }
+        _ => report.fail("/dev/uinput is missing (uinput module not loaded?); only --event/--fast can work"),
+    }
+
+    // Fast mode writes to the touchscreen node directly
+    match detect_touchscreen(pool, device_host).await {
+        Some(node) => report.ok(format!("touchscreen detected at {} (usable with --fast)", node)),
+        None => report.warn("no touchscreen node detected; --fast falls back to uinput"),
+    }
+
+    // How recent gestures went (counters are server-wide since server start)
+    let snapshot = metrics.snapshot();
+    for name in ["tap", "swipe", "pinch"] {
+        match snapshot.commands.iter().find(|c| c.name == name) {
+            Some(c) if c.failed > 0 => {
+                report.warn(format!("{}: {} of {} runs failed since server start", name, c.failed, c.count))
+            }
+            Some(c) => report.ok(format!("{}: {} runs, none failed since server start", name, c.count)),
+            None => {}
+        }
+    }
+    if let Ok(conn) = pool.get_device_info(device_host).await {
+        if let Some(error) = conn.stats.last_error {
+            report.detail(format!("last command error on this device: {}", error));
+        }
+    }
+
+    // Permission denials in the journal around input injection
+    if root_ok {
+        let denials = pool
+            .execute_command(
+                device_host,
+                "journalctl --since '-1h' -q --no-pager 2>/dev/null \
+                 | grep -iE 'avc:.*denied|permission denied|operation not permitted' \
+                 | grep -iE 'uinput|/dev/input|python' | tail -n 5",
+                true,
+            )
+            .await
+            .map(text)
+            .unwrap_or_default();
+        if denials.is_empty() {
+            report.ok("no input-related permission denials in the journal in the last hour");
+        } else {
+            report.fail("input-related permission denials in the journal in the last hour:");
+            for line in denials.lines() {
+                report.detail(line.trim());
+            }
+        }
+    }
+}
+
+/// `/proc/meminfo` as a `free -h` style table
+fn format_free(meminfo: &str) -> Result<Vec<String>> {
+    let fields: std::collections::HashMap<&str, u64> = meminfo
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let kib = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), kib))
+        })
+        .collect();
+    let field = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+    let total = fields
+        .get("MemTotal")
+        .copied()
+        .ok_or_else(|| anyhow!("Unexpected /proc/meminfo output"))?;
+    let free = field("MemFree");
+    let buff_cache = field("Buffers") + field("Cached") + field("SReclaimable");
+    let available = fields.get("MemAvailable").copied().unwrap_or(free + buff_cache);
+    let swap_total = field("SwapTotal");
+    let swap_free = field("SwapFree");
+
+    Ok(vec![
+        format!("{:<6} {:>8} {:>8} {:>8} {:>11} {:>10}", "", "total", "used", "free", "buff/cache", "available"),
+        format!(
+            "{:<6} {:>8} {:>8} {:>8} {:>11} {:>10}",
+            "Mem:",
+            format_kib(total),
+            format_kib(total.saturating_sub(available)),
+            format_kib(free),
+            format_kib(buff_cache),
+            format_kib(available)
+        ),
+        format!(
+            "{:<6} {:>8} {:>8} {:>8}",
+            "Swap:",
+            format_kib(swap_total),
+            format_kib(swap_total.saturating_sub(swap_free)),
+            format_kib(swap_free)
+        ),
+    ])
+}
+
+/// Device time, with how far its clock is off from this machine's
+fn format_date(output: &str) -> Result<Vec<String>> {
+    let (epoch, local) = output
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| anyhow!("Unexpected date output: {}", output.trim()))?;
+    let device_secs: i64 = epoch
+        .parse()
+        .map_err(|_| anyhow!("Unexpected date output: {}", output.trim()))?;
+    let host_secs = chrono::Utc::now().timestamp();
+
+    let offset = device_secs - host_secs;
+    let drift = if offset.abs() <= 1 {
+        "in sync with host".to_string()
+    } else if offset > 0 {
+        format!("{}s ahead of host", offset)
+    } else {
+        format!("{}s behind host", -offset)
+    };
+
+    Ok(vec![local.to_string(), format!("Clock: {}", drift)])
+}
+
+/// `df -kP` as a `df -h` style table, block-device filesystems only
+fn format_df(output: &str) -> Result<Vec<String>> {
+    let mut lines = vec![format!(
+        "{:<28} {:>7} {:>7} {:>7} {:>5}  {}",
+        "Filesystem", "Size", "Used", "Avail", "Use%", "Mounted on"
+    )];
+
+    for line in output.lines().skip(1) {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let [filesystem, size, used, avail, capacity, mount @ ..] = columns.as_slice() else {
+            continue;
+        };
+        if !filesystem.starts_with("/dev/") || mount.is_empty() {
+            continue;
+        }
+        let (Ok(size), Ok(used), Ok(avail)) = (size.parse(), used.parse(), avail.parse()) else {
+            continue;
+        };
+        lines.push(format!(
+            "{:<28} {:>7} {:>7} {:>7} {:>5}  {}",
+            filesystem,
+            format_kib(size),
+            format_kib(used),
+            format_kib(avail),
+            capacity,
+            mount.join(" ")
+        ));
+    }
+
+    if lines.len() == 1 {
+        return Err(anyhow!("No mounted filesystems found"));
+    }
+    Ok(lines)
+}
+
+/// Human-readable size for a KiB count ("812M", "3.6G")
+fn format_kib(kib: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+
+    let mut value = kib as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if value < 10.0 && unit > 0 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
+
 /// Execute Open command - open URL on device
 async fn execute_open(
     pool: &ConnectionPool,
@@ -1353,3 +3692,193 @@ async fn execute_open(
     info!("URL opened successfully");
     Ok(vec![format!("Opened: {}", url)])
 }
+
+/// Execute OpenFile command - push a file to ~/Downloads, then open it
+async fn execute_open_file(
+    pool: &ConnectionPool,
+    device_host: &str,
+    local_path: &str,
+    data: Vec<u8>,
+) -> Result<Vec<String>> {
+    let file_name = std::path::Path::new(local_path)
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid local path"))?
+        .to_string_lossy()
+        .to_string();
+
+    // openUrl needs an absolute path, so resolve the Downloads folder first
+    let downloads = pool
+        .execute_command(device_host, "mkdir -p ~/Downloads && cd ~/Downloads && pwd", false)
+        .await?
+        .join("\n");
+    let downloads = downloads.trim();
+    if downloads.is_empty() {
+        return Err(anyhow!("Could not resolve the Downloads folder on the device"));
+    }
+    let remote_path = format!("{}/{}", downloads, file_name);
+
+    let mut output = execute_push(pool, device_host, local_path, &remote_path, data, false).await?;
+    output.extend(execute_open(pool, device_host, &file_url(&remote_path)).await?);
+    Ok(output)
+}
+
+/// Build a file:// URL, percent-encoding everything but unreserved characters and '/'
+fn file_url(path: &str) -> String {
+    let mut url = String::from("file://");
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs_args() -> audb_protocol::LogsArgs {
+        audb_protocol::LogsArgs {
+            lines: 100,
+            priority: None,
+            unit: None,
+            grep: None,
+            since: None,
+            clear: false,
+            force: false,
+            kernel: false,
+            show_cursor: false,
+            after_cursor: None,
+            wait_for: None,
+            regex: false,
+            since_boot: false,
+            output: None,
+        }
+    }
+
+    #[test]
+    fn test_journalctl_priority_range() {
+        let args = audb_protocol::LogsArgs {
+            priority: Some("W..E".to_string()),
+            ..logs_args()
+        };
+        let cmd = build_journalctl_command(&args).unwrap();
+        assert!(cmd.contains(" -p warning..err "), "{}", cmd);
+
+        let args = audb_protocol::LogsArgs {
+            priority: Some("err; reboot".to_string()),
+            ..logs_args()
+        };
+        assert!(build_journalctl_command(&args).is_err());
+    }
+
+    #[test]
+    fn test_journalctl_output_format() {
+        let args = audb_protocol::LogsArgs {
+            output: Some("short-monotonic".to_string()),
+            ..logs_args()
+        };
+        let cmd = build_journalctl_command(&args).unwrap();
+        assert!(cmd.ends_with(" -o short-monotonic"), "{}", cmd);
+
+        // Following with cat output: the cursor line must still pass the grep
+        let args = audb_protocol::LogsArgs {
+            output: Some("cat".to_string()),
+            grep: Some("ERROR".to_string()),
+            show_cursor: true,
+            ..logs_args()
+        };
+        let cmd = build_journalctl_command(&args).unwrap();
+        assert!(cmd.contains(" --show-cursor -o cat | grep -e '^-- cursor: ' -e 'ERROR'"), "{}", cmd);
+
+        let args = audb_protocol::LogsArgs {
+            output: Some("cat".to_string()),
+            wait_for: Some("ready".to_string()),
+            show_cursor: true,
+            ..logs_args()
+        };
+        let cmd = build_journalctl_command(&args).unwrap();
+        assert!(cmd.contains(" -o cat | grep -F -e '-- cursor: ' -e 'ready'"), "{}", cmd);
+
+        let args = audb_protocol::LogsArgs {
+            output: Some("verbose".to_string()),
+            grep: Some("ERROR".to_string()),
+            ..logs_args()
+        };
+        assert!(build_journalctl_command(&args).is_err());
+    }
+
+    /// Run a device-side command under `sh`, with `/sys` moved to `root`
+    fn run_with_sysfs(command: &str, root: &std::path::Path) -> (bool, Vec<String>) {
+        let command = command.replace("/sys/", &format!("{}/", root.display()));
+        let output = std::process::Command::new("sh").arg("-c").arg(command).output().unwrap();
+        let lines = String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect();
+        (output.status.success(), lines)
+    }
+
+    #[test]
+    fn test_temperature_loops_survive_failing_last_entry() {
+        let root = std::env::temp_dir().join(format!("audb-test-sysfs-{}", std::process::id()));
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        // The last entries are not a battery / have no temperature
+        write("class/power_supply/battery/type", "Battery\n");
+        write("class/power_supply/battery/temp", "312\n");
+        write("class/power_supply/usb/type", "USB\n");
+        write("class/thermal/thermal_zone0/type", "cpu-thermal\n");
+        write("class/thermal/thermal_zone0/temp", "45000\n");
+        write("class/thermal/thermal_zone1/type", "pmic\n");
+
+        let (ok, battery) = run_with_sysfs(BATTERY_TEMP_COMMAND, &root);
+        assert!(ok);
+        assert_eq!(parse_battery_temp(&battery), Some(31.2));
+
+        let (ok, zones) = run_with_sysfs(THERMAL_ZONES_COMMAND, &root);
+        assert!(ok);
+        assert_eq!(parse_cpu_temp(&zones), Some(45.0));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_parse_device_clock() {
+        let sent = chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let received = sent + chrono::Duration::milliseconds(200);
+
+        // %N supported: nanosecond stamp, read at the 100ms midpoint
+        let clock = parse_device_clock(
+            "1700000000600000000 2023-11-15 01:13:20 MSK +0300\n/usr/share/zoneinfo/Europe/Moscow\n",
+            sent,
+            received,
+        );
+        assert_eq!(clock.time, "2023-11-15 01:13:20");
+        assert_eq!(clock.timezone, "Europe/Moscow (MSK, +0300)");
+        assert_eq!(clock.offset_ms, Some(500));
+        assert_eq!(clock.rtt_ms, Some(200));
+
+        // No %N support: whole seconds followed by a literal N
+        let clock = parse_device_clock("1700000002N 2023-11-15 01:13:22 MSK +0300\n", sent, received);
+        assert_eq!(clock.offset_ms, Some(1900));
+
+        // No /etc/localtime link: only the abbreviation and offset
+        let clock = parse_device_clock("1700000000100000000 2023-11-14 22:13:20 UTC +0000\n", sent, received);
+        assert_eq!(clock.timezone, "UTC (+0000)");
+        assert_eq!(clock.offset_ms, Some(0));
+
+        // A device clock behind ours gives a negative offset
+        let clock = parse_device_clock(
+            "1699999995000000000 2023-11-14 19:13:15 -03 -0300\n/usr/share/zoneinfo/America/Sao_Paulo\n",
+            sent,
+            received,
+        );
+        assert_eq!(clock.timezone, "America/Sao_Paulo (-03, -0300)");
+        assert_eq!(clock.offset_ms, Some(-5100));
+
+        assert_eq!(parse_device_clock("", sent, received).offset_ms, None);
+    }
+}
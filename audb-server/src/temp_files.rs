@@ -0,0 +1,67 @@
+use crate::pool::ConnectionPool;
+use anyhow::Result;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::info;
+
+/// Environment variable that keeps temp files like `--keep-temp` (set to 1)
+pub const KEEP_TEMP_ENV: &str = "AUDB_KEEP_TEMP";
+
+/// Whether temp files are left in place for debugging
+static KEEP_TEMP: AtomicBool = AtomicBool::new(false);
+
+/// Keep (or again remove) the temp files written for installs, pushes,
+/// screenshots and scripts
+pub fn set_keep(keep: bool) {
+    KEEP_TEMP.store(keep, Ordering::Relaxed);
+}
+
+fn keep() -> bool {
+    KEEP_TEMP.load(Ordering::Relaxed)
+}
+
+/// Temp file name `<prefix>-<pid>-<nanos>[.<ext>]`, unique to this server process
+pub fn unique_name(prefix: &str, ext: &str) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let name = format!("{}-{}-{}", prefix, std::process::id(), nanos);
+    if ext.is_empty() {
+        name
+    } else {
+        format!("{}.{}", name, ext)
+    }
+}
+
+/// Remove a local temp file, or log where it was kept
+pub fn remove_local(path: &Path) {
+    if keep() {
+        info!("Keeping temp file {}", path.display());
+        return;
+    }
+    std::fs::remove_file(path).ok();
+}
+
+/// Remove a temp file on the device, or log where it was kept
+pub async fn remove_remote(pool: &ConnectionPool, host: &str, path: &str, as_root: bool) -> Result<()> {
+    if keep() {
+        info!("Keeping temp file {} on {}", path, host);
+        return Ok(());
+    }
+    let command = format!("rm -f {}", audb_core::tools::shell_escape::quote_arg(path));
+    pool.execute_command(host, &command, as_root).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_name() {
+        let name = unique_name("audb-pull", "tar.gz");
+        assert!(name.starts_with(&format!("audb-pull-{}-", std::process::id())));
+        assert!(name.ends_with(".tar.gz"));
+        assert!(!unique_name("audb-push", "").contains('.'));
+    }
+}
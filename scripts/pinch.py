@@ -0,0 +1,364 @@
+#!/usr/bin/env python3
+"""
+Two-finger pinch injection on Sailfish/Aurora OS.
+
+Two modes:
+  1. Default (uinput): Creates virtual device, reliable but slower (~150ms setup)
+  2. Fast (--event): Direct evdev injection, fast but requires correct device
+
+Usage:
+  python3 pinch.py in|out                          # pinch at screen center
+  python3 pinch.py out --center 360,720            # pinch around a point
+  python3 pinch.py in --distance 400               # finger travel span in px
+  python3 pinch.py out --event /dev/input/event4   # fast mode
+  python3 pinch.py out --event auto                # fast mode, auto-detect
+  python3 pinch.py in --no-rotate                  # disable rotation handling
+
+Run as root: devel-su -c "python3 pinch.py out"
+
+Env overrides:
+  XMAX=720 YMAX=1440 SLOT_MAX=4
+  TOUCH_MAJOR=19 WIDTH_MAJOR=19
+  SETTLE=0.15   # seconds to wait after creating uinput device
+  STEPS=20      # number of move steps
+  STEP_DELAY=0.005  # seconds between move steps
+  MIN_GAP=60    # finger gap in px at the closed end of the pinch
+"""
+
+import os
+import sys
+import time
+import ctypes
+import fcntl
+import glob
+import subprocess
+
+# ---------- Defaults ----------
+XMAX = int(os.environ.get("XMAX", "720"))
+YMAX = int(os.environ.get("YMAX", "1440"))
+SLOT_MAX = int(os.environ.get("SLOT_MAX", "4"))
+
+TOUCH_MAJOR = int(os.environ.get("TOUCH_MAJOR", "19"))
+WIDTH_MAJOR = int(os.environ.get("WIDTH_MAJOR", "19"))
+
+SETTLE = float(os.environ.get("SETTLE", "0.15"))
+STEPS = int(os.environ.get("STEPS", "20"))
+STEP_DELAY = float(os.environ.get("STEP_DELAY", "0.005"))
+
+MIN_GAP = int(os.environ.get("MIN_GAP", "60"))
+
+# ---------- Orientation constants (Qt::ScreenOrientation) ----------
+ORIENTATION_PORTRAIT = 1
+ORIENTATION_LANDSCAPE = 2
+ORIENTATION_INVERTED_PORTRAIT = 4
+ORIENTATION_INVERTED_LANDSCAPE = 8
+
+# ---------- Input constants ----------
+EV_SYN = 0x00
+EV_KEY = 0x01
+EV_ABS = 0x03
+
+SYN_REPORT = 0
+BTN_TOUCH = 0x14A
+INPUT_PROP_DIRECT = 0x01
+
+ABS_X = 0x00
+ABS_Y = 0x01
+ABS_MT_SLOT = 0x2F
+ABS_MT_TOUCH_MAJOR = 0x30
+ABS_MT_WIDTH_MAJOR = 0x32
+ABS_MT_POSITION_X = 0x35
+ABS_MT_POSITION_Y = 0x36
+ABS_MT_TRACKING_ID = 0x39
+
+# ---------- ioctl macros ----------
+_IOC_NRBITS = 8
+_IOC_TYPEBITS = 8
+_IOC_SIZEBITS = 14
+_IOC_DIRBITS = 2
+
+_IOC_NRSHIFT = 0
+_IOC_TYPESHIFT = _IOC_NRSHIFT + _IOC_NRBITS
+_IOC_SIZESHIFT = _IOC_TYPESHIFT + _IOC_TYPEBITS
+_IOC_DIRSHIFT = _IOC_SIZESHIFT + _IOC_SIZEBITS
+
+_IOC_NONE = 0
+_IOC_WRITE = 1
+
+def _IOC(direction, t, nr, size):
+    return (direction << _IOC_DIRSHIFT) | (t << _IOC_TYPESHIFT) | (nr << _IOC_NRSHIFT) | (size << _IOC_SIZESHIFT)
+
+def _IO(t, nr):
+    return _IOC(_IOC_NONE, t, nr, 0)
+
+def _IOW(t, nr, size):
+    return _IOC(_IOC_WRITE, t, nr, size)
+
+U = ord('U')
+INTSZ = ctypes.sizeof(ctypes.c_int)
+
+UI_SET_EVBIT   = _IOW(U, 100, INTSZ)
+UI_SET_KEYBIT  = _IOW(U, 101, INTSZ)
+UI_SET_ABSBIT  = _IOW(U, 103, INTSZ)
+UI_SET_PROPBIT = _IOW(U, 110, INTSZ)
+UI_DEV_CREATE  = _IO(U, 1)
+UI_DEV_DESTROY = _IO(U, 2)
+
+# ---------- Structs ----------
+class TimeVal(ctypes.Structure):
+    _fields_ = [("tv_sec", ctypes.c_long), ("tv_usec", ctypes.c_long)]
+
+class InputEvent(ctypes.Structure):
+    _fields_ = [("time", TimeVal), ("type", ctypes.c_ushort), ("code", ctypes.c_ushort), ("value", ctypes.c_int)]
+
+class InputID(ctypes.Structure):
+    _fields_ = [("bustype", ctypes.c_ushort), ("vendor", ctypes.c_ushort), ("product", ctypes.c_ushort), ("version", ctypes.c_ushort)]
+
+ABS_CNT = 64
+
+class UInputUserDev(ctypes.Structure):
+    _fields_ = [
+        ("name", ctypes.c_char * 80),
+        ("id", InputID),
+        ("ff_effects_max", ctypes.c_int),
+        ("absmax", ctypes.c_int * ABS_CNT),
+        ("absmin", ctypes.c_int * ABS_CNT),
+        ("absfuzz", ctypes.c_int * ABS_CNT),
+        ("absflat", ctypes.c_int * ABS_CNT),
+    ]
+
+def emit(fd, etype, code, value):
+    os.write(fd, bytes(InputEvent(TimeVal(0, 0), etype, code, value)))
+
+def syn(fd):
+    emit(fd, EV_SYN, SYN_REPORT, 0)
+
+def clamp(v, lo, hi):
+    return max(lo, min(hi, v))
+
+def find_touchscreen():
+    """Auto-detect touchscreen device."""
+    for dev_path in sorted(glob.glob("/dev/input/event*")):
+        try:
+            num = dev_path.split("event")[-1]
+            name_path = f"/sys/class/input/event{num}/device/name"
+            if os.path.exists(name_path):
+                with open(name_path) as f:
+                    name = f.read().strip().lower()
+                    if any(x in name for x in ["touch", "tpd", "ts", "silead", "goodix", "fts", "atmel", "synaptics", "elan", "chsc", "himax"]):
+                        return dev_path
+        except:
+            pass
+    return "/dev/input/event3"
+
+def get_screen_orientation():
+    """Get current screen orientation from dconf."""
+    try:
+        result = subprocess.run(
+            ["dconf", "read", "/desktop/lipstick-jolla-home/dialog_orientation"],
+            capture_output=True, text=True, timeout=2
+        )
+        if result.returncode == 0 and result.stdout.strip():
+            return int(result.stdout.strip())
+    except:
+        pass
+    return ORIENTATION_PORTRAIT
+
+def transform_coordinates(x, y, orientation):
+    """Transform screen coordinates based on orientation."""
+    if orientation == ORIENTATION_PORTRAIT:
+        return x, y
+    elif orientation == ORIENTATION_LANDSCAPE:
+        return XMAX - y, x
+    elif orientation == ORIENTATION_INVERTED_PORTRAIT:
+        return XMAX - x, YMAX - y
+    elif orientation == ORIENTATION_INVERTED_LANDSCAPE:
+        return y, YMAX - x
+    else:
+        return x, y
+
+def get_screen_dimensions(orientation):
+    """Get screen dimensions based on orientation."""
+    if orientation in (ORIENTATION_LANDSCAPE, ORIENTATION_INVERTED_LANDSCAPE):
+        return YMAX, XMAX  # width, height swapped
+    return XMAX, YMAX
+
+def finger_positions(cx, cy, gap):
+    """Two finger positions on a horizontal line, gap px apart."""
+    half = gap // 2
+    return (cx - half, cy), (cx + half, cy)
+
+# ---------- UINPUT MODE ----------
+def pinch_uinput(frames):
+    # Open uinput
+    fd = None
+    for p in ("/dev/uinput", "/dev/input/uinput"):
+        try:
+            fd = os.open(p, os.O_WRONLY | os.O_NONBLOCK)
+            break
+        except OSError:
+            pass
+    if fd is None:
+        raise SystemExit("ERROR: can't open /dev/uinput")
+
+    # Setup
+    fcntl.ioctl(fd, UI_SET_EVBIT, EV_KEY)
+    fcntl.ioctl(fd, UI_SET_EVBIT, EV_ABS)
+    fcntl.ioctl(fd, UI_SET_EVBIT, EV_SYN)
+    fcntl.ioctl(fd, UI_SET_KEYBIT, BTN_TOUCH)
+    fcntl.ioctl(fd, UI_SET_PROPBIT, INPUT_PROP_DIRECT)
+
+    for c in (ABS_X, ABS_Y, ABS_MT_SLOT, ABS_MT_TRACKING_ID, ABS_MT_POSITION_X, ABS_MT_POSITION_Y, ABS_MT_TOUCH_MAJOR, ABS_MT_WIDTH_MAJOR):
+        fcntl.ioctl(fd, UI_SET_ABSBIT, c)
+
+    u = UInputUserDev()
+    u.name = b"sfos-uinput-touch"
+    u.id = InputID(0x18, 0x0, 0x0, 0x0)
+    u.absmin[ABS_X] = 0; u.absmax[ABS_X] = XMAX
+    u.absmin[ABS_Y] = 0; u.absmax[ABS_Y] = YMAX
+    u.absmin[ABS_MT_SLOT] = 0; u.absmax[ABS_MT_SLOT] = SLOT_MAX
+    u.absmin[ABS_MT_TRACKING_ID] = 0; u.absmax[ABS_MT_TRACKING_ID] = 65535
+    u.absmin[ABS_MT_POSITION_X] = 0; u.absmax[ABS_MT_POSITION_X] = XMAX
+    u.absmin[ABS_MT_POSITION_Y] = 0; u.absmax[ABS_MT_POSITION_Y] = YMAX
+    u.absmin[ABS_MT_TOUCH_MAJOR] = 0; u.absmax[ABS_MT_TOUCH_MAJOR] = 255
+    u.absmin[ABS_MT_WIDTH_MAJOR] = 0; u.absmax[ABS_MT_WIDTH_MAJOR] = 255
+
+    os.write(fd, bytes(u))
+    fcntl.ioctl(fd, UI_DEV_CREATE)
+    time.sleep(SETTLE)
+
+    do_pinch(fd, frames)
+
+    time.sleep(0.02)
+    fcntl.ioctl(fd, UI_DEV_DESTROY)
+    os.close(fd)
+
+# ---------- EVDEV MODE ----------
+def pinch_evdev(frames, device):
+    if device == "auto":
+        device = find_touchscreen()
+
+    fd = os.open(device, os.O_WRONLY)
+    do_pinch(fd, frames)
+    os.close(fd)
+    print(f"pinch via {device}")
+
+# ---------- COMMON PINCH LOGIC ----------
+def emit_finger(fd, x, y):
+    emit(fd, EV_ABS, ABS_MT_POSITION_X, clamp(x, 0, XMAX))
+    emit(fd, EV_ABS, ABS_MT_POSITION_Y, clamp(y, 0, YMAX))
+    emit(fd, EV_ABS, ABS_MT_TOUCH_MAJOR, TOUCH_MAJOR)
+    emit(fd, EV_ABS, ABS_MT_WIDTH_MAJOR, WIDTH_MAJOR)
+
+def do_pinch(fd, frames):
+    """Play frames of ((x0, y0), (x1, y1)) finger positions in device coordinates."""
+    base_id = int(time.time() * 1000) % 60000 + 1
+
+    # DOWN (both fingers in the same report)
+    first = frames[0]
+    for slot in (0, 1):
+        emit(fd, EV_ABS, ABS_MT_SLOT, slot)
+        emit(fd, EV_ABS, ABS_MT_TRACKING_ID, base_id + slot)
+        emit_finger(fd, *first[slot])
+    emit(fd, EV_KEY, BTN_TOUCH, 1)
+    syn(fd)
+
+    # MOVE
+    for frame in frames[1:]:
+        for slot in (0, 1):
+            emit(fd, EV_ABS, ABS_MT_SLOT, slot)
+            emit_finger(fd, *frame[slot])
+        syn(fd)
+        if STEP_DELAY > 0:
+            time.sleep(STEP_DELAY)
+
+    # UP
+    for slot in (0, 1):
+        emit(fd, EV_ABS, ABS_MT_SLOT, slot)
+        emit(fd, EV_ABS, ABS_MT_TRACKING_ID, -1)
+    emit(fd, EV_KEY, BTN_TOUCH, 0)
+    syn(fd)
+
+def take_option(args, name):
+    """Remove '--name VALUE' from args and return VALUE (None if absent)."""
+    if name not in args:
+        return None
+    idx = args.index(name)
+    if idx + 1 >= len(args):
+        raise ValueError(f"{name} requires a value")
+    value = args[idx + 1]
+    del args[idx:idx + 2]
+    return value
+
+def main():
+    args = sys.argv[1:]
+    no_rotate = False
+
+    try:
+        event_device = take_option(args, "--event")
+        center = take_option(args, "--center")
+        distance = take_option(args, "--distance")
+    except ValueError as e:
+        print(f"ERROR: {e}", file=sys.stderr)
+        return 2
+
+    # Parse --no-rotate flag
+    if "--no-rotate" in args:
+        args.remove("--no-rotate")
+        no_rotate = True
+
+    if len(args) != 1 or args[0] not in ("in", "out"):
+        print("Usage: python3 pinch.py in|out [--center X,Y] [--distance PX] [--event DEV] [--no-rotate]", file=sys.stderr)
+        return 2
+    direction = args[0]
+
+    # Get orientation
+    orientation = ORIENTATION_PORTRAIT
+    if not no_rotate:
+        orientation = get_screen_orientation()
+
+    width, height = get_screen_dimensions(orientation)
+
+    try:
+        if center:
+            cx, cy = (int(v) for v in center.split(","))
+        else:
+            cx, cy = width // 2, height // 2
+        span = int(distance) if distance else int(min(width, height) * 0.6)
+    except ValueError:
+        print("ERROR: --center must be X,Y and --distance a number of pixels", file=sys.stderr)
+        return 2
+
+    span = max(span, MIN_GAP + 2)
+
+    # Gap between fingers per step (closed -> open for "out", reversed for "in")
+    steps = max(1, STEPS)
+    gaps = [int(MIN_GAP + (span - MIN_GAP) * i / steps) for i in range(steps + 1)]
+    if direction == "in":
+        gaps.reverse()
+
+    frames = []
+    for gap in gaps:
+        a, b = finger_positions(cx, cy, gap)
+        if not no_rotate:
+            a = transform_coordinates(a[0], a[1], orientation)
+            b = transform_coordinates(b[0], b[1], orientation)
+        frames.append((a, b))
+
+    if not no_rotate and orientation != ORIENTATION_PORTRAIT:
+        orient_name = {
+            ORIENTATION_LANDSCAPE: "landscape",
+            ORIENTATION_INVERTED_PORTRAIT: "inverted-portrait",
+            ORIENTATION_INVERTED_LANDSCAPE: "inverted-landscape"
+        }.get(orientation, f"unknown({orientation})")
+        print(f"orientation: {orient_name}", file=sys.stderr)
+
+    if event_device:
+        pinch_evdev(frames, event_device)
+    else:
+        pinch_uinput(frames)
+
+    return 0
+
+if __name__ == "__main__":
+    raise SystemExit(main())
@@ -12,6 +12,7 @@ Usage:
   python3 tap.py X Y --event auto       # auto-detect touchscreen
   python3 tap.py X Y --duration 1000    # long press (1000ms)
   python3 tap.py X Y --no-rotate        # disable rotation handling
+  python3 tap.py X Y --orientation landscape   # skip detection, assume landscape
 
 Run as root: devel-su -c "python3 tap.py 200 400"
 
@@ -20,6 +21,15 @@ Env overrides:
   TOUCH_MAJOR=19 WIDTH_MAJOR=19
   SETTLE=0.15   # seconds to wait after creating uinput device
   DOWN_MS=30    # press duration (ms), overridden by --duration
+
+Rotation: coordinates are given as the user sees the screen and mapped to the
+portrait-native touchscreen (XMAX x YMAX):
+  portrait            hw = (x, y)
+  landscape           hw = (XMAX - y, x)
+  inverted-portrait   hw = (XMAX - x, YMAX - y)
+  inverted-landscape  hw = (y, YMAX - x)
+The orientation is read from dconf unless --orientation names it; if it cannot
+be read, portrait (no transform) is assumed.
 """
 
 import os
@@ -47,6 +57,13 @@ ORIENTATION_LANDSCAPE = 2
 ORIENTATION_INVERTED_PORTRAIT = 4
 ORIENTATION_INVERTED_LANDSCAPE = 8
 
+ORIENTATION_NAMES = {
+    "portrait": ORIENTATION_PORTRAIT,
+    "landscape": ORIENTATION_LANDSCAPE,
+    "inverted-portrait": ORIENTATION_INVERTED_PORTRAIT,
+    "inverted-landscape": ORIENTATION_INVERTED_LANDSCAPE,
+}
+
 # ---------- Input constants ----------
 EV_SYN = 0x00
 EV_KEY = 0x01
@@ -309,8 +326,19 @@ def main():
         args.remove("--no-rotate")
         no_rotate = True
 
+    # Parse --orientation flag (overrides dconf detection)
+    forced_orientation = None
+    if "--orientation" in args:
+        idx = args.index("--orientation")
+        if idx + 1 < len(args) and args[idx + 1] in ORIENTATION_NAMES:
+            forced_orientation = ORIENTATION_NAMES[args[idx + 1]]
+            args = args[:idx] + args[idx+2:]
+        else:
+            print("ERROR: --orientation requires one of: " + ", ".join(ORIENTATION_NAMES), file=sys.stderr)
+            return 2
+
     if len(args) != 2:
-        print("Usage: python3 tap.py X Y [--event DEV] [--duration MS] [--no-rotate]", file=sys.stderr)
+        print("Usage: python3 tap.py X Y [--event DEV] [--duration MS] [--no-rotate] [--orientation O]", file=sys.stderr)
         return 2
 
     x = int(args[0])
@@ -318,7 +346,7 @@ def main():
 
     # Transform coordinates based on screen orientation
     if not no_rotate:
-        orientation = get_screen_orientation()
+        orientation = forced_orientation or get_screen_orientation()
         orig_x, orig_y = x, y
         x, y = transform_coordinates(x, y, orientation)
         if orientation != ORIENTATION_PORTRAIT:
@@ -0,0 +1,180 @@
+#!/usr/bin/env python3
+"""
+Accessibility tree dump on Sailfish/Aurora OS.
+
+Walks the AT-SPI tree of the running applications over D-Bus (with gdbus, no
+Python bindings needed) and prints one line per element:
+
+  DEPTH<TAB>ROLE<TAB>X<TAB>Y<TAB>WIDTH<TAB>HEIGHT<TAB>ID<TAB>NAME
+
+Coordinates are screen coordinates; elements without a Component interface
+get -1 for all four. ID is the element's D-Bus address (BUS_NAME:OBJECT_PATH),
+stable while the application runs. Tabs and newlines in names are replaced by
+spaces.
+
+Usage:
+  python3 ui_dump.py                 # all applications
+  python3 ui_dump.py --app NAME      # only applications whose name contains NAME
+
+Run as the session user (not root): the AT-SPI bus is per-session.
+
+Env overrides:
+  MAX_DEPTH=40       # stop descending below this depth
+  MAX_ELEMENTS=2000  # stop after this many elements
+
+Qt applications only register with AT-SPI while accessibility is enabled. The
+script enables it (org.a11y.Status.IsEnabled); applications started before
+that may need a restart to show up.
+"""
+
+import os
+import re
+import subprocess
+import sys
+
+MAX_DEPTH = int(os.environ.get("MAX_DEPTH", "40"))
+MAX_ELEMENTS = int(os.environ.get("MAX_ELEMENTS", "2000"))
+
+ROOT_PATH = "/org/a11y/atspi/accessible/root"
+REGISTRY = "org.a11y.atspi.Registry"
+
+# GVariant text strings: 'single' or "double" quoted, with backslash escapes
+STRING_RE = re.compile(r"'((?:[^'\\]|\\.)*)'|\"((?:[^\"\\]|\\.)*)\"")
+INT_RE = re.compile(r"-?\d+")
+
+
+def unescape(text):
+    return re.sub(r"\\(.)", r"\1", text)
+
+
+def strings(output):
+    return [unescape(a if a or not b else b) for a, b in STRING_RE.findall(output)]
+
+
+def gdbus(args, address=None):
+    bus = ["--address", address] if address else ["--session"]
+    result = subprocess.run(
+        ["gdbus", "call"] + bus + args,
+        stdout=subprocess.PIPE,
+        stderr=subprocess.PIPE,
+        universal_newlines=True,
+        timeout=5,
+    )
+    if result.returncode != 0:
+        raise RuntimeError(result.stderr.strip() or "gdbus call failed")
+    return result.stdout.strip()
+
+
+def ensure_session_bus():
+    if not os.environ.get("DBUS_SESSION_BUS_ADDRESS"):
+        os.environ["DBUS_SESSION_BUS_ADDRESS"] = "unix:path=/run/user/%d/dbus/user_bus_socket" % os.getuid()
+
+
+def a11y_bus_address():
+    # Best effort: turn accessibility on so Qt apps register their trees
+    try:
+        gdbus([
+            "--dest", "org.a11y.Bus", "--object-path", "/org/a11y/bus",
+            "--method", "org.freedesktop.DBus.Properties.Set",
+            "org.a11y.Status", "IsEnabled", "<true>",
+        ])
+    except (RuntimeError, subprocess.TimeoutExpired):
+        pass
+
+    output = gdbus([
+        "--dest", "org.a11y.Bus", "--object-path", "/org/a11y/bus",
+        "--method", "org.a11y.Bus.GetAddress",
+    ])
+    found = strings(output)
+    if not found:
+        raise RuntimeError("no AT-SPI bus address in %r" % output)
+    return found[0]
+
+
+class Walker:
+    def __init__(self, address):
+        self.address = address
+        self.count = 0
+
+    def call(self, dest, path, method, *args):
+        return gdbus(["--dest", dest, "--object-path", path, "--method", method] + list(args), self.address)
+
+    def children(self, dest, path):
+        found = strings(self.call(dest, path, "org.a11y.atspi.Accessible.GetChildren"))
+        # (bus name, object path) pairs
+        return list(zip(found[0::2], found[1::2]))
+
+    def name(self, dest, path):
+        output = self.call(dest, path, "org.freedesktop.DBus.Properties.Get",
+                           "org.a11y.atspi.Accessible", "Name")
+        found = strings(output)
+        return found[0] if found else ""
+
+    def role(self, dest, path):
+        found = strings(self.call(dest, path, "org.a11y.atspi.Accessible.GetRoleName"))
+        return found[0] if found else "unknown"
+
+    def extents(self, dest, path):
+        try:
+            output = self.call(dest, path, "org.a11y.atspi.Component.GetExtents", "uint32 0")
+        except (RuntimeError, subprocess.TimeoutExpired):
+            return (-1, -1, -1, -1)
+        values = [int(v) for v in INT_RE.findall(output)]
+        return tuple(values[:4]) if len(values) >= 4 else (-1, -1, -1, -1)
+
+    def emit(self, depth, dest, path):
+        if self.count >= MAX_ELEMENTS:
+            return
+        self.count += 1
+
+        try:
+            name = self.name(dest, path)
+            role = self.role(dest, path)
+        except (RuntimeError, subprocess.TimeoutExpired):
+            # The element went away while walking
+            return
+        x, y, w, h = self.extents(dest, path)
+        name = re.sub(r"[\t\r\n]+", " ", name)
+        print("%d\t%s\t%d\t%d\t%d\t%d\t%s:%s\t%s" % (depth, role, x, y, w, h, dest, path, name))
+        sys.stdout.flush()
+
+        if depth >= MAX_DEPTH:
+            return
+        try:
+            children = self.children(dest, path)
+        except (RuntimeError, subprocess.TimeoutExpired):
+            return
+        for child_dest, child_path in children:
+            self.emit(depth + 1, child_dest, child_path)
+
+
+def main():
+    app_filter = None
+    args = sys.argv[1:]
+    if len(args) == 2 and args[0] == "--app":
+        app_filter = args[1].lower()
+    elif args:
+        print(__doc__.strip(), file=sys.stderr)
+        return 2
+
+    ensure_session_bus()
+    try:
+        walker = Walker(a11y_bus_address())
+        apps = walker.children(REGISTRY, ROOT_PATH)
+    except (RuntimeError, subprocess.TimeoutExpired) as e:
+        print("AT-SPI accessibility bus unavailable: %s" % e, file=sys.stderr)
+        return 1
+
+    for dest, path in apps:
+        if app_filter is not None:
+            try:
+                if app_filter not in walker.name(dest, path).lower():
+                    continue
+            except (RuntimeError, subprocess.TimeoutExpired):
+                continue
+        walker.emit(0, dest, path)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())